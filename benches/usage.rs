@@ -11,6 +11,7 @@ fn usage(c: &mut Criterion) {
         TreeOptions {
             initial_capacity: 10,
             message_buffer_capacity: 2,
+            ..Default::default()
         }
         .create()
         .into()
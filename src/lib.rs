@@ -42,9 +42,13 @@ pub mod tree;
 ///
 pub mod render;
 
+#[cfg(feature = "progress-tree-log")]
+pub use log::debug;
 #[cfg(feature = "progress-tree-log")]
 pub use log::info;
 #[cfg(feature = "progress-tree-log")]
+pub use log::trace;
+#[cfg(feature = "progress-tree-log")]
 pub use log::warn;
 
 #[cfg(any(feature = "jiff", feature = "local-time"))]
@@ -68,7 +72,11 @@ pub use traits::{
 };
 
 mod throughput;
-pub use crate::throughput::Throughput;
+pub use crate::throughput::{Throughput, Trend};
+
+#[cfg(feature = "metrics")]
+///
+pub mod metrics;
 
 #[cfg(not(feature = "progress-tree-log"))]
 mod log {
@@ -84,4 +92,16 @@ mod log {
         (target: $target:expr, $($arg:tt)+) => {};
         ($($arg:tt)+) => {};
     }
+    /// Stub
+    #[macro_export(local_inner_macros)]
+    macro_rules! debug {
+        (target: $target:expr, $($arg:tt)+) => {};
+        ($($arg:tt)+) => {};
+    }
+    /// Stub
+    #[macro_export(local_inner_macros)]
+    macro_rules! trace {
+        (target: $target:expr, $($arg:tt)+) => {};
+        ($($arg:tt)+) => {};
+    }
 }
@@ -2,6 +2,7 @@ use std::time::SystemTime;
 
 /// The severity of a message
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageLevel {
     /// Rarely sent information related to the progress, not to be confused with the progress itself
     Info,
@@ -9,21 +10,48 @@ pub enum MessageLevel {
     Failure,
     /// Indicates a task was completed successfully
     Success,
+    /// Indicates something unexpected that doesn't keep a task from completing
+    Warning,
+    /// Detailed information to help debugging, usually not shown
+    Debug,
+    /// Very detailed information to trace what a task is doing, usually not shown
+    Trace,
+}
+
+impl MessageLevel {
+    /// Return how severe this level is, from `0` for [`Trace`][MessageLevel::Trace] to `4` for
+    /// [`Failure`][MessageLevel::Failure], with [`Info`][MessageLevel::Info] and [`Success`][MessageLevel::Success]
+    /// being equally severe.
+    ///
+    /// Renderers use it to only show messages at least as severe as a given level.
+    pub fn severity(&self) -> u8 {
+        match self {
+            MessageLevel::Trace => 0,
+            MessageLevel::Debug => 1,
+            MessageLevel::Info | MessageLevel::Success => 2,
+            MessageLevel::Warning => 3,
+            MessageLevel::Failure => 4,
+        }
+    }
 }
 
 /// A message to be stored along with the progress tree.
 ///
 /// It is created by [`Tree::message(…)`](./struct.Item.html#method.message).
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     /// The time at which the message was sent.
     pub time: SystemTime,
     /// The severity of the message
     pub level: MessageLevel,
-    /// The name of the task that created the `Message`
+    /// The name of the task that created the `Message`, or the origin it was explicitly created with.
     pub origin: String,
     /// The message itself
     pub message: String,
+    /// Structured data attached to the message as pairs of names and values, in the order they were given.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fields: Vec<(String, String)>,
 }
 
 /// A ring buffer for messages.
@@ -32,6 +60,7 @@ pub struct MessageRingBuffer {
     pub(crate) buf: Vec<Message>,
     cursor: usize,
     total: usize,
+    capacity: usize,
 }
 
 impl MessageRingBuffer {
@@ -41,19 +70,48 @@ impl MessageRingBuffer {
             buf: Vec::with_capacity(capacity),
             cursor: 0,
             total: 0,
+            capacity,
         }
     }
 
+    /// Return the amount of messages this buffer can hold before overwriting the oldest ones.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the amount of messages this buffer can hold to `capacity`, dropping the oldest messages if there are more.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        let mut messages = Vec::with_capacity(self.buf.len());
+        self.copy_all(&mut messages);
+        messages.drain(..messages.len().saturating_sub(capacity));
+        messages.shrink_to(capacity);
+        self.buf = messages;
+        self.cursor = 0;
+        self.capacity = capacity;
+    }
+
+    /// Return the amount of messages that were overwritten or dropped as the buffer was full or shrunk.
+    pub fn dropped(&self) -> usize {
+        self.total.wrapping_sub(self.buf.len())
+    }
+
     /// Push a `message` from `origin` at severity `level` into the buffer, possibly overwriting the last message added.
     pub fn push_overwrite(&mut self, level: MessageLevel, origin: String, message: impl Into<String>) {
-        let msg = Message {
+        self.push(Message {
             time: SystemTime::now(),
             level,
             origin,
             message: message.into(),
-        };
+            fields: Vec::new(),
+        })
+    }
+
+    /// Push `msg` into the buffer as is, possibly overwriting the last message added.
+    pub(crate) fn push(&mut self, msg: Message) {
         if self.has_capacity() {
             self.buf.push(msg)
+        } else if self.buf.is_empty() {
+            // without any capacity, the message is dropped right away
         } else {
             self.buf[self.cursor] = msg;
             self.cursor = (self.cursor + 1) % self.buf.len();
@@ -77,42 +135,20 @@ impl MessageRingBuffer {
     /// its `previous` return value.
     pub fn copy_new(&self, out: &mut Vec<Message>, previous: Option<MessageCopyState>) -> MessageCopyState {
         out.clear();
-        match previous {
-            Some(MessageCopyState { cursor, buf_len, total }) => {
-                if self.total.saturating_sub(total) >= self.buf.capacity() {
-                    self.copy_all(out);
-                } else {
-                    let new_elements_below_cap = self.buf.len().saturating_sub(buf_len);
-                    let cursor_ofs: isize = self.cursor as isize - cursor as isize;
-                    match cursor_ofs {
-                        // there was some capacity left without wrapping around
-                        0 => {
-                            out.extend_from_slice(&self.buf[self.buf.len() - new_elements_below_cap..]);
-                        }
-                        // cursor advanced
-                        c if c > 0 => {
-                            out.extend_from_slice(&self.buf[(cursor % self.buf.len())..self.cursor]);
-                        }
-                        // cursor wrapped around
-                        c if c < 0 => {
-                            out.extend_from_slice(&self.buf[(cursor % self.buf.len())..]);
-                            out.extend_from_slice(&self.buf[..self.cursor]);
-                        }
-                        _ => unreachable!("logic dictates that… yeah, you really shouldn't ever see this!"),
-                    }
-                }
-            }
-            None => self.copy_all(out),
+        // Counting by the total amount of messages keeps working even if the capacity changed in the meantime.
+        let new_messages = match previous {
+            Some(MessageCopyState { total }) => self.total.wrapping_sub(total).min(self.buf.len()),
+            None => self.buf.len(),
         };
-        MessageCopyState {
-            cursor: self.cursor,
-            buf_len: self.buf.len(),
-            total: self.total,
-        }
+        let (older, newer) = (&self.buf[self.cursor..], &self.buf[..self.cursor]);
+        let from_older = new_messages.saturating_sub(newer.len());
+        out.extend_from_slice(&older[older.len() - from_older..]);
+        out.extend_from_slice(&newer[newer.len() - (new_messages - from_older)..]);
+        MessageCopyState { total: self.total }
     }
 
     fn has_capacity(&self) -> bool {
-        self.buf.len() < self.buf.capacity()
+        self.buf.len() < self.capacity
     }
 }
 
@@ -120,7 +156,5 @@ impl MessageRingBuffer {
 ///
 /// Note that due to the nature of a ring buffer, there is no guarantee that you see all messages.
 pub struct MessageCopyState {
-    cursor: usize,
-    buf_len: usize,
     total: usize,
 }
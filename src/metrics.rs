@@ -0,0 +1,157 @@
+/*!
+* A module to expose the progress of all tasks as [Prometheus](https://prometheus.io) metrics.
+*
+* **Please note** that it is behind the `metrics` feature toggle.
+*
+* Each task is identified by its `key`, the dot-separated positions of the task and its ancestors in the tree,
+* along with its `name`, as in
+*
+* ```text
+* prodash_task_step{key="0.1",name="fetch"} 42
+* prodash_task_done_at{key="0.1",name="fetch"} 100
+* prodash_task_failed{key="0.1",name="fetch"} 0
+* prodash_tasks 2
+* prodash_messages_total{level="info"} 7
+* ```
+*/
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+    net::{TcpListener, ToSocketAddrs},
+    sync::atomic::Ordering,
+};
+
+use crate::{
+    Root, WeakRoot,
+    messages::{MessageCopyState, MessageLevel},
+    progress::Key,
+};
+
+/// Keeps the state needed to turn progress trees into metrics, like the amount of messages seen so far.
+#[derive(Default)]
+pub struct Exporter {
+    messages_seen: [u64; 6],
+    copy_state: Option<MessageCopyState>,
+    messages: Vec<crate::messages::Message>,
+    tasks: Vec<(Key, crate::progress::Task)>,
+}
+
+impl Exporter {
+    /// Return all metrics of `progress` in the Prometheus text format, counting messages that were added since the last call.
+    ///
+    /// Note that messages that were overwritten in the message buffer in the meantime can't be counted.
+    pub fn scrape(&mut self, progress: &impl Root) -> String {
+        self.copy_state = Some(progress.copy_new_messages(&mut self.messages, self.copy_state.take()));
+        for message in &self.messages {
+            self.messages_seen[level_index(message.level)] += 1;
+        }
+        progress.sorted_snapshot(&mut self.tasks);
+
+        let mut out = String::new();
+        let tasks_with_progress = || {
+            self.tasks
+                .iter()
+                .filter_map(|(key, task)| Some((key, task.name.as_str(), task.progress.as_ref()?)))
+        };
+        out.push_str(
+            "# HELP prodash_task_step The amount of steps a task completed.\n# TYPE prodash_task_step gauge\n",
+        );
+        for (key, name, progress) in tasks_with_progress() {
+            push_task_metric(
+                &mut out,
+                "prodash_task_step",
+                key,
+                name,
+                progress.step.load(Ordering::Relaxed),
+            );
+        }
+        out.push_str(
+            "# HELP prodash_task_done_at The amount of steps after which a bounded task is done.\n# TYPE prodash_task_done_at gauge\n",
+        );
+        for (key, name, progress) in tasks_with_progress() {
+            if let Some(done_at) = progress.done_at {
+                push_task_metric(&mut out, "prodash_task_done_at", key, name, done_at);
+            }
+        }
+        out.push_str(
+            "# HELP prodash_task_failed The amount of steps of a task that failed.\n# TYPE prodash_task_failed gauge\n",
+        );
+        for (key, name, progress) in tasks_with_progress() {
+            push_task_metric(&mut out, "prodash_task_failed", key, name, progress.failed);
+        }
+        writeln!(
+            out,
+            "# HELP prodash_tasks The amount of tasks in the tree.\n# TYPE prodash_tasks gauge\nprodash_tasks {}",
+            self.tasks.len()
+        )
+        .ok();
+        out.push_str(
+            "# HELP prodash_messages_total The amount of messages sent.\n# TYPE prodash_messages_total counter\n",
+        );
+        for (level, count) in ["info", "failure", "success", "warning", "debug", "trace"]
+            .iter()
+            .zip(self.messages_seen)
+        {
+            writeln!(out, "prodash_messages_total{{level=\"{level}\"}} {count}").ok();
+        }
+        out
+    }
+}
+
+/// Serve the metrics of `progress` over HTTP to each client connecting to `addr`, blocking until `progress`
+/// is dropped or accepting connections fails.
+///
+/// Clients are served one after another, which suffices for scrapers.
+pub fn serve(progress: impl WeakRoot, addr: impl ToSocketAddrs) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    let mut exporter = Exporter::default();
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let Some(progress) = progress.upgrade() else {
+            break;
+        };
+        let mut request_line = String::new();
+        if io::BufReader::new(&stream).read_line(&mut request_line).is_err() {
+            continue;
+        }
+        let metrics = exporter.scrape(&progress);
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{metrics}",
+            metrics.len()
+        )
+        .ok();
+    }
+    Ok(())
+}
+
+fn level_index(level: MessageLevel) -> usize {
+    match level {
+        MessageLevel::Info => 0,
+        MessageLevel::Failure => 1,
+        MessageLevel::Success => 2,
+        MessageLevel::Warning => 3,
+        MessageLevel::Debug => 4,
+        MessageLevel::Trace => 5,
+    }
+}
+
+fn push_task_metric(out: &mut String, metric: &str, key: &Key, name: &str, value: usize) {
+    write!(out, "{metric}{{key=\"").ok();
+    for level in 1..=key.level() {
+        if level != 1 {
+            out.push('.');
+        }
+        write!(out, "{}", key[level]).ok();
+    }
+    out.push_str("\",name=\"");
+    for c in name.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    writeln!(out, "\"}} {value}").ok();
+}
@@ -0,0 +1,141 @@
+use std::io;
+
+use crate::{Progress, Unit};
+
+/// A reader counting all bytes read from the `inner` reader as progress, created by [`Progress::reader()`].
+pub struct Reader<R, P> {
+    /// The reader to read from.
+    pub inner: R,
+    /// The progress to count bytes with.
+    pub progress: P,
+}
+
+/// A writer counting all bytes written to the `inner` writer as progress, created by [`Progress::writer()`].
+pub struct Writer<W, P> {
+    /// The writer to write to.
+    pub inner: W,
+    /// The progress to count bytes with.
+    pub progress: P,
+}
+
+impl<R, P: Progress> Reader<R, P> {
+    /// Create a new instance to count bytes read from `inner` with `progress`, using a unit for bytes unless
+    /// `progress` has a unit already.
+    pub fn new(inner: R, mut progress: P) -> Self {
+        use_bytes_unit(&mut progress);
+        Reader { inner, progress }
+    }
+}
+
+impl<W, P: Progress> Writer<W, P> {
+    /// Create a new instance to count bytes written to `inner` with `progress`, using a unit for bytes unless
+    /// `progress` has a unit already.
+    pub fn new(inner: W, mut progress: P) -> Self {
+        use_bytes_unit(&mut progress);
+        Writer { inner, progress }
+    }
+}
+
+fn use_bytes_unit(progress: &mut impl Progress) {
+    if progress.unit().is_none() {
+        let max = progress.max();
+        progress.init(max, Some(bytes()));
+    }
+}
+
+#[cfg(feature = "unit-bytes")]
+fn bytes() -> Unit {
+    crate::unit::dynamic_and_mode(crate::unit::Bytes, crate::unit::display::Mode::with_throughput())
+}
+
+#[cfg(not(feature = "unit-bytes"))]
+fn bytes() -> Unit {
+    crate::unit::label("bytes")
+}
+
+impl<R: io::Read, P: Progress> io::Read for Reader<R, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.progress.inc_by(bytes_read);
+        Ok(bytes_read)
+    }
+}
+
+impl<R: io::BufRead, P: Progress> io::BufRead for Reader<R, P> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.progress.inc_by(amt);
+    }
+}
+
+impl<W: io::Write, P: Progress> io::Write for Writer<W, P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        self.progress.inc_by(bytes_written);
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "progress-async")]
+mod async_io {
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+
+    use super::{Reader, Writer};
+    use crate::Progress;
+
+    impl<R: AsyncRead + Unpin, P: Progress + Unpin> AsyncRead for Reader<R, P> {
+        fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_read(cx, buf);
+            if let Poll::Ready(Ok(bytes_read)) = res {
+                this.progress.inc_by(bytes_read);
+            }
+            res
+        }
+    }
+
+    impl<R: AsyncBufRead + Unpin, P: Progress + Unpin> AsyncBufRead for Reader<R, P> {
+        fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+            Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
+        }
+
+        fn consume(self: Pin<&mut Self>, amt: usize) {
+            let this = self.get_mut();
+            Pin::new(&mut this.inner).consume(amt);
+            this.progress.inc_by(amt);
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin, P: Progress + Unpin> AsyncWrite for Writer<W, P> {
+        fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+            if let Poll::Ready(Ok(bytes_written)) = res {
+                this.progress.inc_by(bytes_written);
+            }
+            res
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_close(cx)
+        }
+    }
+}
@@ -12,6 +12,7 @@ pub(crate) type Id = u16;
 
 /// A type identifying a spot in the hierarchy of `Tree` items.
 #[derive(Copy, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Key(Option<Id>, Option<Id>, Option<Id>, Option<Id>, Option<Id>, Option<Id>);
 
 /// Determines if a sibling is above or below in the given level of hierarchy
@@ -241,6 +242,19 @@ impl Key {
         adjecency
     }
 
+    /// Return the amount of direct children of the key in `sorted` at the given `index`.
+    ///
+    /// It's vital that the invariant of `sorted` to actually be sorted by key is upheld
+    /// for the result to be reliable.
+    pub fn num_children(sorted: &[(Key, Task)], index: usize) -> usize {
+        let key_level = sorted[index].0.level();
+        sorted[index + 1..]
+            .iter()
+            .take_while(|(k, _)| k.level() > key_level)
+            .filter(|(k, _)| k.level() == key_level + 1)
+            .count()
+    }
+
     /// The maximum amount of path components we can represent.
     pub const fn max_level() -> Level {
         6
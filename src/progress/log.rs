@@ -134,6 +134,9 @@ impl Progress for Log {
             MessageLevel::Info => log::info!("ℹ{} → {}", self.name, message),
             MessageLevel::Failure => log::error!("𐄂{} → {}", self.name, message),
             MessageLevel::Success => log::info!("✓{} → {}", self.name, message),
+            MessageLevel::Warning => log::warn!("⚠{} → {}", self.name, message),
+            MessageLevel::Debug => log::debug!("{} → {}", self.name, message),
+            MessageLevel::Trace => log::trace!("{} → {}", self.name, message),
         }
     }
 }
@@ -1,9 +1,10 @@
 use std::{
+    collections::HashMap,
     sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use crate::unit::Unit;
@@ -13,8 +14,19 @@ pub mod key;
 #[doc(inline)]
 pub use key::Key;
 
+mod io;
+pub use io::{Reader, Writer};
+
+#[cfg(feature = "progress-async")]
+mod stream;
+#[cfg(feature = "progress-async")]
+pub use stream::{InformProgress, StreamExt};
+
 mod utils;
 
+mod per_key;
+pub(crate) use per_key::PerKey;
+
 #[cfg(feature = "progress-log")]
 mod log;
 pub use utils::{Discard, DoOrDiscard, Either, ThroughputOnDrop};
@@ -62,6 +74,8 @@ pub enum State {
 pub struct Value {
     /// The amount of progress currently made
     pub step: StepShared,
+    /// The amount of steps that failed, in addition to the successful ones counted in `step`.
+    pub failed: Step,
     /// The step at which no further progress has to be made.
     ///
     /// If unset, the progress is unbounded.
@@ -70,19 +84,55 @@ pub struct Value {
     pub unit: Option<Unit>,
     /// Whether progress can be made or not
     pub state: State,
+    /// A short explanation of why the task is halted, typically to describe a failure.
+    ///
+    /// It is cleared whenever the state changes.
+    pub detail: Option<Arc<str>>,
+    /// The task this one is waiting on, if any.
+    ///
+    /// It's informational only and needs to be unset once the other task is done.
+    pub blocked_by: Option<Key>,
+    /// The time at which the progress was initialized, if known, to tell how long the task has been running.
+    pub started_at: Option<SystemTime>,
+    /// The time at which the task was last [blocked][State::Blocked] or [halted][State::Halted] while it was running,
+    /// to tell for how long it couldn't make progress. It's unset while the task is running.
+    pub stopped_since: Option<SystemTime>,
+    /// The time at which the task was paused, if it is, to stop counting the time it's running and its throughput.
+    pub paused_since: Option<SystemTime>,
+    /// The time the task was paused for in the past, not counting the current pause.
+    pub paused_for: Duration,
+    /// If true, this progress isn't made by the task itself but aggregated from the progress of its children, so it
+    /// doesn't count towards [overall progress][overall_fraction()].
+    pub aggregated: bool,
 }
 
 impl std::hash::Hash for Value {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         let Self {
             step,
+            failed,
             done_at,
             unit,
             state: our_state,
+            detail,
+            blocked_by,
+            started_at,
+            stopped_since,
+            paused_since,
+            paused_for,
+            aggregated,
         } = self;
+        failed.hash(state);
         done_at.hash(state);
         unit.hash(state);
         our_state.hash(state);
+        detail.hash(state);
+        blocked_by.hash(state);
+        started_at.hash(state);
+        stopped_since.hash(state);
+        paused_since.hash(state);
+        paused_for.hash(state);
+        aggregated.hash(state);
         step.load(Ordering::Relaxed).hash(state);
     }
 }
@@ -90,10 +140,52 @@ impl std::hash::Hash for Value {
 impl Value {
     /// Returns a number between `Some(0.0)` and `Some(1.0)`, or `None` if the progress is unbounded.
     ///
-    /// A task half done would return `Some(0.5)`.
+    /// A task half done would return `Some(0.5)`. [Failed](Value::failed) steps count as done.
     pub fn fraction(&self) -> Option<f32> {
-        self.done_at
-            .map(|done_at| self.step.load(Ordering::SeqCst) as f32 / done_at as f32)
+        self.done_at.map(|done_at| self.steps_done() as f32 / done_at as f32)
+    }
+
+    /// Returns the amount of steps done, successful or [failed](Value::failed), saturating at `Step::MAX`.
+    pub fn steps_done(&self) -> Step {
+        self.step.load(Ordering::SeqCst).saturating_add(self.failed)
+    }
+
+    /// Returns true if the progress is bounded and all of its steps are done, successful or [failed](Value::failed).
+    pub fn is_done(&self) -> bool {
+        self.done_at.is_some_and(|done_at| self.steps_done() >= done_at)
+    }
+
+    /// Returns the fractions of successful and [failed](Value::failed) steps as `(succeeded, failed)`, or `None` if the
+    /// progress is unbounded or no step failed.
+    ///
+    /// Both are clamped so that together they don't exceed `1.0`, with failed steps taking precedence.
+    pub fn outcome_fractions(&self) -> Option<(f32, f32)> {
+        let done_at = self.done_at.filter(|_| self.failed > 0)? as f32;
+        let failed = (self.failed as f32 / done_at).min(1.0);
+        let succeeded = (self.step.load(Ordering::SeqCst) as f32 / done_at).min(1.0 - failed);
+        Some((succeeded, failed))
+    }
+
+    /// Returns how long the task has been running since it [started](Value::started_at), not counting the time
+    /// it was [paused](Value::paused_since), or `None` if it's unknown when it started.
+    pub fn elapsed(&self) -> Option<Duration> {
+        let running_and_paused = self.started_at?.elapsed().ok()?;
+        let paused = self.paused_for
+            + self
+                .paused_since
+                .and_then(|since| since.elapsed().ok())
+                .unwrap_or_default();
+        Some(running_and_paused.saturating_sub(paused))
+    }
+
+    /// Returns the task this one is [blocked by][Value::blocked_by] from the `sorted` snapshot of all tasks,
+    /// or `None` if there is none or it isn't present anymore.
+    pub fn blocker<'a>(&self, sorted: &'a [(Key, Task)]) -> Option<&'a Task> {
+        let blocked_by = self.blocked_by?;
+        sorted
+            .binary_search_by_key(&blocked_by, |(key, _)| *key)
+            .ok()
+            .map(|index| &sorted[index].1)
     }
 }
 
@@ -108,3 +200,183 @@ pub struct Task {
     /// The progress itself, unless this value belongs to an `Item` serving as organizational unit.
     pub progress: Option<Value>,
 }
+
+/// Returns the fraction of work done across all bounded tasks in the `sorted` snapshot of all tasks, between `0.0` and
+/// `1.0`, or `None` if there are none.
+///
+/// Each task is weighted by its amount of steps. Unbounded tasks are ignored, as are [aggregated][Value::aggregated]
+/// ones so each step is counted once.
+pub fn overall_fraction(sorted: &[(Key, Task)]) -> Option<f32> {
+    let (done, total, _started_at) = bounded_work(sorted);
+    (total > 0.0).then(|| (done / total) as f32)
+}
+
+/// Estimates the time it takes until all bounded tasks in the `sorted` snapshot of all tasks are done, or `None` if
+/// there are none or no progress was made yet.
+///
+/// As it's unknown which tasks run in parallel, the estimate assumes that the remaining work across all bounded
+/// tasks is completed at the same average rate as the work done so far since the earliest of them
+/// [started][Value::started_at]. Tasks are counted like in [`overall_fraction()`], so unbounded ones are ignored.
+/// Each call computes a new estimate without looking at previous ones, so it may jump as tasks are added and
+/// removed, which renderers can smooth out over time.
+pub fn overall_eta(sorted: &[(Key, Task)]) -> Option<Duration> {
+    let (done, total, started_at) = bounded_work(sorted);
+    if done == 0.0 {
+        return None;
+    }
+    let elapsed = started_at?.elapsed().ok()?;
+    Duration::try_from_secs_f64(elapsed.as_secs_f64() * (total - done) / done).ok()
+}
+
+/// Returns the steps done and the steps to do across all bounded tasks in `sorted` that aren't
+/// [aggregated][Value::aggregated], along with the time at which the earliest of them started, if known.
+fn bounded_work(sorted: &[(Key, Task)]) -> (f64, f64, Option<SystemTime>) {
+    sorted
+        .iter()
+        .filter_map(|(_key, task)| task.progress.as_ref())
+        .filter(|progress| !progress.aggregated)
+        .filter_map(|progress| {
+            let done_at = progress.done_at?;
+            let step = progress.steps_done();
+            Some((step.min(done_at) as f64, done_at as f64, progress.started_at))
+        })
+        .fold(
+            (0.0, 0.0, None),
+            |(done, total, started_at), (step, done_at, task_started_at)| {
+                (done + step, total + done_at, earliest(started_at, task_started_at))
+            },
+        )
+}
+
+/// Returns the earlier of both times, or the one that is known.
+pub(crate) fn earliest(lhs: Option<SystemTime>, rhs: Option<SystemTime>) -> Option<SystemTime> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs.min(rhs)),
+        (lhs, rhs) => lhs.or(rhs),
+    }
+}
+
+/// Returns the keys of the chain of tasks in the `sorted` snapshot of all tasks that is expected to determine how long
+/// it takes until all work is done, ordered from the first task of the chain to the last, or an empty chain if there
+/// are no tasks.
+///
+/// The time left for each task is estimated from its [elapsed time][Value::elapsed()], assuming the rest of its work
+/// is done at the same rate as the work done so far. Tasks that didn't make any progress yet, including unbounded
+/// ones, are assumed to take as long again as they ran so far, and tasks that didn't start or whose progress is
+/// [aggregated][Value::aggregated] aren't expected to take any time. Ties, like between chains of tasks that didn't
+/// start, are broken by the fraction of work not yet done, with tasks that didn't make any progress counting as not
+/// done at all.
+///
+/// The chain of a task consists of the chain of the task it is [blocked by][Value::blocked_by] followed by the task
+/// itself. If it isn't blocked by anything present in `sorted`, its ancestors start the chain instead, making the
+/// result the leaf with the most time left along with its ancestors. The chain with the most time left is returned.
+///
+/// It takes time proportional to the amount of tasks, so renderers may want to compute it only when the tree changed.
+pub fn critical_path(sorted: &[(Key, Task)]) -> Vec<Key> {
+    let index_of: HashMap<Key, usize> = sorted
+        .iter()
+        .enumerate()
+        .map(|(index, (key, _))| (*key, index))
+        .collect();
+    let left: Vec<_> = sorted.iter().map(|(_key, task)| time_and_work_left(task)).collect();
+
+    // As parents come before their children, the ancestors of each task are on the stack when it's reached.
+    let mut parent = vec![None; sorted.len()];
+    let mut ancestors_left = vec![(Duration::ZERO, 0.0); sorted.len()];
+    let mut stack = Vec::<usize>::new();
+    for (index, (key, _task)) in sorted.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            let other = sorted[top].0;
+            if other.level() < key.level() && key.shares_parent_with(&other, other.level()) {
+                parent[index] = Some(top);
+                ancestors_left[index] = add_left(ancestors_left[top], left[top]);
+                break;
+            }
+            stack.pop();
+        }
+        stack.push(index);
+    }
+
+    // Follow the blockers of each task until reaching one whose chain is known, and compute the chains on the way back.
+    let mut next = vec![None; sorted.len()];
+    let mut chain_left = vec![None; sorted.len()];
+    let mut on_path = vec![false; sorted.len()];
+    let mut path = Vec::new();
+    for start in 0..sorted.len() {
+        let mut index = start;
+        while chain_left[index].is_none() {
+            on_path[index] = true;
+            path.push(index);
+            let blocker = sorted[index]
+                .1
+                .progress
+                .as_ref()
+                .and_then(|progress| progress.blocked_by)
+                .and_then(|key| index_of.get(&key).copied())
+                // Tasks blocking each other in a cycle are treated as if the last of them wasn't blocked.
+                .filter(|blocker| !on_path[*blocker]);
+            match blocker {
+                Some(blocker) => {
+                    next[index] = Some(blocker);
+                    index = blocker;
+                }
+                None => break,
+            }
+        }
+        while let Some(index) = path.pop() {
+            on_path[index] = false;
+            let rest = match next[index] {
+                Some(blocker) => chain_left[blocker].expect("blockers are computed first"),
+                None => ancestors_left[index],
+            };
+            chain_left[index] = Some(add_left(rest, left[index]));
+        }
+    }
+
+    let Some(mut index) = chain_left
+        .iter()
+        .enumerate()
+        .filter_map(|(index, left)| left.map(|left| (index, left)))
+        .fold(
+            None,
+            |best: Option<(usize, (Duration, f32))>, (index, left)| match best {
+                Some((_, best_left)) if best_left >= left => best,
+                _ => Some((index, left)),
+            },
+        )
+        .map(|(index, _left)| index)
+    else {
+        return Vec::new();
+    };
+    let mut chain = vec![sorted[index].0];
+    while let Some(blocker) = next[index] {
+        chain.push(sorted[blocker].0);
+        index = blocker;
+    }
+    while let Some(ancestor) = parent[index] {
+        chain.push(sorted[ancestor].0);
+        index = ancestor;
+    }
+    chain.reverse();
+    chain
+}
+
+/// Returns the estimated time left for `task` along with the fraction of its work not yet done, see [`critical_path()`].
+fn time_and_work_left(task: &Task) -> (Duration, f32) {
+    let Some(progress) = task.progress.as_ref().filter(|progress| !progress.aggregated) else {
+        return (Duration::ZERO, 0.0);
+    };
+    let fraction = progress.fraction().unwrap_or(0.0).clamp(0.0, 1.0);
+    let elapsed = progress.elapsed().unwrap_or_default();
+    let time_left = if fraction > 0.0 {
+        Duration::try_from_secs_f64(elapsed.as_secs_f64() * f64::from(1.0 - fraction) / f64::from(fraction))
+            .unwrap_or(Duration::MAX)
+    } else {
+        elapsed
+    };
+    (time_left, 1.0 - fraction)
+}
+
+fn add_left((time, work): (Duration, f32), (other_time, other_work): (Duration, f32)) -> (Duration, f32) {
+    (time.saturating_add(other_time), work + other_work)
+}
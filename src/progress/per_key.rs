@@ -0,0 +1,51 @@
+use crate::progress::{Key, Task};
+
+/// State kept per task by utilities like [`Throughput`][crate::Throughput] which observe tasks across frames,
+/// sorted by key for fast lookups.
+pub(crate) struct PerKey<T> {
+    sorted_by_key: Vec<(Key, T)>,
+}
+
+impl<T> Default for PerKey<T> {
+    fn default() -> Self {
+        PerKey {
+            sorted_by_key: Vec::new(),
+        }
+    }
+}
+
+impl<T> PerKey<T> {
+    /// Return the state at `key`, if there is one.
+    pub fn get(&self, key: &Key) -> Option<&T> {
+        self.sorted_by_key
+            .binary_search_by_key(key, |t| t.0)
+            .ok()
+            .map(|index| &self.sorted_by_key[index].1)
+    }
+
+    /// Return the state at `key`, if there is one, for updating it.
+    pub fn get_mut(&mut self, key: &Key) -> Option<&mut T> {
+        self.sorted_by_key
+            .binary_search_by_key(key, |t| t.0)
+            .ok()
+            .map(|index| &mut self.sorted_by_key[index].1)
+    }
+
+    /// Return the state at `key`, after creating it with `create()` if there is none yet.
+    pub fn get_or_insert_with(&mut self, key: &Key, create: impl FnOnce() -> T) -> &mut T {
+        let index = match self.sorted_by_key.binary_search_by_key(key, |t| t.0) {
+            Ok(index) => index,
+            Err(index) => {
+                self.sorted_by_key.insert(index, (*key, create()));
+                index
+            }
+        };
+        &mut self.sorted_by_key[index].1
+    }
+
+    /// Compare the keys in `sorted_values` with our internal state and remove all missing tasks from it.
+    pub fn reconcile(&mut self, sorted_values: &[(Key, Task)]) {
+        self.sorted_by_key
+            .retain(|(key, _)| sorted_values.binary_search_by_key(key, |e| e.0).is_ok());
+    }
+}
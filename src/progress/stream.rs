@@ -0,0 +1,43 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::Count;
+
+/// Extends all [`Stream`]s with a way to count their items as progress.
+pub trait StreamExt: Stream + Sized {
+    /// Count each item produced by this stream as a step of `progress`.
+    fn inform_progress<P: Count>(self, progress: P) -> InformProgress<Self, P> {
+        InformProgress { inner: self, progress }
+    }
+}
+
+impl<S: Stream> StreamExt for S {}
+
+/// A stream counting each item of the `inner` stream as progress, created by [`StreamExt::inform_progress()`].
+pub struct InformProgress<S, P> {
+    /// The stream producing the items.
+    pub inner: S,
+    /// The progress to count items with.
+    pub progress: P,
+}
+
+impl<S: Stream + Unpin, P: Count + Unpin> Stream for InformProgress<S, P> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_next(cx);
+        if let Poll::Ready(Some(_)) = res {
+            this.progress.inc();
+        }
+        res
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
@@ -3,6 +3,20 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
 
 /// An implementation of [`NestedProgress`] which discards all calls.
+///
+/// As it has no state, all calls compile down to nothing, so libraries can always take a progress handle while callers
+/// that don't want to show progress pass this one without runtime overhead.
+///
+/// ```rust
+/// use prodash::{NestedProgress, Progress};
+///
+/// fn work(mut progress: impl NestedProgress) {
+///     progress.init(Some(10), None);
+///     progress.add_child("child").info("no one will see this".into());
+/// }
+///
+/// work(prodash::progress::Discard);
+/// ```
 pub struct Discard;
 
 impl Count for Discard {
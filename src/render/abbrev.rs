@@ -0,0 +1,105 @@
+use std::borrow::Cow;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The way task names that don't fit into their column are shortened by the renderers.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum NameAbbrev {
+    /// Names are never shortened, and are cut off by the terminal or the following columns instead.
+    None,
+    /// Keep the start of the name and end it with an ellipsis, as in `a-very-lo…`.
+    #[default]
+    TruncateEnd,
+    /// Keep the start and the end of the name and put an ellipsis in the middle, as in `a-ver…name`.
+    TruncateMiddle,
+    /// Shorten all but the last component of a path to their first character, as in `s/r/l/draw.rs`,
+    /// and truncate it in the middle if that isn't enough.
+    ShortenPath,
+    /// Keep the first and the last word of the name and put an ellipsis in place of all words in between,
+    /// as in `compiling … done`, and truncate it in the middle if that isn't enough.
+    HeadTail,
+}
+
+impl NameAbbrev {
+    /// Return `name` shortened to be at most `max_width` cells wide, or `name` itself if it fits or if shortening is disabled.
+    pub fn abbreviate<'a>(&self, name: &'a str, max_width: usize) -> Cow<'a, str> {
+        if *self == NameAbbrev::None || name.width() <= max_width {
+            return Cow::Borrowed(name);
+        }
+        if max_width == 0 {
+            return Cow::Borrowed("");
+        }
+        Cow::Owned(match self {
+            NameAbbrev::None => unreachable!("handled above"),
+            NameAbbrev::TruncateEnd => format!("{}…", head(name, max_width - 1)),
+            NameAbbrev::TruncateMiddle => truncate_middle(name, max_width),
+            NameAbbrev::ShortenPath => {
+                let shortened = shorten_path(name, max_width);
+                if shortened.width() <= max_width {
+                    shortened
+                } else {
+                    truncate_middle(&shortened, max_width)
+                }
+            }
+            NameAbbrev::HeadTail => {
+                let words: Vec<_> = name.split_whitespace().collect();
+                match words.as_slice() {
+                    [first, _, .., last] if first.width() + last.width() + 3 <= max_width => {
+                        format!("{first} … {last}")
+                    }
+                    _ => truncate_middle(name, max_width),
+                }
+            }
+        })
+    }
+}
+
+/// Return the longest start of `text` that is at most `max_width` cells wide.
+fn head(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    let end = text
+        .char_indices()
+        .find(|(_, c)| {
+            width += c.width().unwrap_or(0);
+            width > max_width
+        })
+        .map_or(text.len(), |(idx, _)| idx);
+    &text[..end]
+}
+
+/// Return the longest end of `text` that is at most `max_width` cells wide.
+fn tail(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    let start = text
+        .char_indices()
+        .rev()
+        .find(|(_, c)| {
+            width += c.width().unwrap_or(0);
+            width > max_width
+        })
+        .map_or(0, |(idx, c)| idx + c.len_utf8());
+    &text[start..]
+}
+
+fn truncate_middle(text: &str, max_width: usize) -> String {
+    let available = max_width.saturating_sub(1);
+    let head_width = available.div_ceil(2);
+    format!("{}…{}", head(text, head_width), tail(text, available - head_width))
+}
+
+/// Shorten the leading components of the path in `name` one by one until it fits into `max_width`.
+fn shorten_path(name: &str, max_width: usize) -> String {
+    let mut components: Vec<_> = name.split('/').collect();
+    let mut width = name.width();
+    let last = components.len().saturating_sub(1);
+    for component in &mut components[..last] {
+        if width <= max_width {
+            break;
+        }
+        let shortened = head(component, 1);
+        let shortened = if shortened.is_empty() { *component } else { shortened };
+        width -= component.width() - shortened.width();
+        *component = shortened;
+    }
+    components.join("/")
+}
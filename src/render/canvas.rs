@@ -0,0 +1,210 @@
+use crate::{progress::Step, render::BarGlyphs};
+
+/// A surface to draw a single line of styled text onto, implemented by each renderer.
+///
+/// Columns are relative to the start of the area assigned to the drawing code, and are measured in terminal cells.
+pub(crate) trait Canvas {
+    /// The style as understood by the renderer.
+    type Style: Copy;
+
+    /// Put `text` at `column` with `style`, using at most `width` cells, and return the amount of cells drawn.
+    fn put(&mut self, column: u16, width: u16, text: &str, style: Self::Style) -> u16;
+}
+
+/// The symbols to draw progress bars with.
+pub(crate) struct Glyphs {
+    /// The symbol filling a cell that is entirely done.
+    pub full: &'static str,
+    /// Symbols for a partially filled cell, from the least to the most filled one. Must not be empty.
+    pub partial: &'static [&'static str],
+    /// The symbol for cells without progress.
+    pub empty: &'static str,
+}
+
+/// Block elements with a resolution of an eighth of a cell.
+pub(crate) const BLOCKS: Glyphs = Glyphs {
+    full: "█",
+    partial: &[" ", "▏", "▎", "▍", "▌", "▋", "▊", "▉", "█"],
+    empty: " ",
+};
+
+/// Characters available in any terminal, as in `===>---`.
+#[cfg_attr(not(feature = "render-line"), allow(dead_code))]
+pub(crate) const ASCII: Glyphs = Glyphs {
+    full: "=",
+    partial: &[">"],
+    empty: "-",
+};
+
+/// Braille patterns filling a cell column by column from the bottom, with a resolution of an eighth of a cell.
+pub(crate) const BRAILLE: Glyphs = Glyphs {
+    full: "⣿",
+    partial: &["⠀", "⡀", "⡄", "⡆", "⡇", "⣇", "⣧", "⣷", "⣿"],
+    empty: "⠀",
+};
+
+impl BarGlyphs {
+    /// Return the glyphs to draw bars with, which are the `native` ones of the renderer unless configured otherwise.
+    pub(crate) fn resolve(self, native: &'static Glyphs) -> &'static Glyphs {
+        match self {
+            BarGlyphs::Native => native,
+            BarGlyphs::Blocks => &BLOCKS,
+            BarGlyphs::Braille => &BRAILLE,
+        }
+    }
+}
+
+/// The amount of cells occupied by a spinner.
+pub(crate) const SPINNER_WIDTH: u16 = 5;
+
+/// Return how much of a bar to fill for `fraction` after applying the user-provided `mapping`, if any.
+pub(crate) fn fill(fraction: f32, mapping: Option<fn(f32) -> f32>) -> f32 {
+    mapping.map_or(fraction, |mapping| mapping(fraction.clamp(0.0, 1.0)))
+}
+
+/// Draw a bar `width` cells wide at `column` which is filled according to `fraction`, and return the amount of
+/// cells drawn with the `filled` style, including the partially filled one.
+///
+/// If `empty` is set, the remaining cells are drawn as well.
+pub(crate) fn bar<C: Canvas>(
+    canvas: &mut C,
+    column: u16,
+    width: u16,
+    fraction: f32,
+    glyphs: &Glyphs,
+    filled: C::Style,
+    empty: Option<C::Style>,
+) -> u16 {
+    if width == 0 {
+        return 0;
+    }
+    let exact = width as f32 * fraction.clamp(0.0, 1.0);
+    let mut filled_width = (exact.floor() as u16).min(width);
+    canvas.put(column, filled_width, &glyphs.full.repeat(filled_width as usize), filled);
+    if filled_width < width {
+        // Get the index based on how filled the remaining part is
+        let index = ((exact - filled_width as f32) * (glyphs.partial.len() - 1) as f32).round() as usize;
+        canvas.put(column + filled_width, 1, glyphs.partial[index], filled);
+        filled_width += 1;
+    }
+    if let Some(empty) = empty {
+        let empty_width = width - filled_width;
+        canvas.put(
+            column + filled_width,
+            empty_width,
+            &glyphs.empty.repeat(empty_width as usize),
+            empty,
+        );
+    }
+    filled_width
+}
+
+/// Format `fraction` as percentage, with the amount of decimal places chosen as described in
+/// [`BarStyle::adaptive_precision`](crate::render::BarStyle::adaptive_precision) if `adaptive` is set,
+/// or without any decimal places otherwise.
+///
+/// `total` is the amount of steps at which the task is done, if known.
+pub(crate) fn percentage(fraction: f32, total: Option<Step>, adaptive: bool) -> String {
+    let percent = fraction.clamp(0.0, 1.0) as f64 * 100.0;
+    if !adaptive {
+        return format!("{percent:.0}%");
+    }
+    let decimals = match total {
+        Some(total) if total <= 100 => 0,
+        _ if (10.0..=90.0).contains(&percent) => 0,
+        Some(total) if total >= 10_000 => 2,
+        _ => 1,
+    };
+    // Round down to never show 100% before all is done, and to avoid flickering between two values.
+    let scale = 10f64.powi(decimals as i32);
+    format!("{:.decimals$}%", (percent * scale).floor() / scale)
+}
+
+/// Return the `label` along with the column at which it is centered in a bar `width` cells wide,
+/// or `None` if the bar is too narrow to hold it.
+pub(crate) fn overlay_label(width: u16, label: String) -> (String, Option<u16>) {
+    let label_width = label.len() as u16;
+    let column = (width >= label_width + 2).then(|| (width - label_width) / 2);
+    (label, column)
+}
+
+/// Draw a spinner made of `glyph` into the `width` cells at `column`, moving along as `step` changes.
+///
+/// `seed` is used to offset spinners of different tasks from each other.
+pub(crate) fn spinner<C: Canvas>(
+    canvas: &mut C,
+    column: u16,
+    width: u16,
+    step: usize,
+    seed: usize,
+    glyph: &str,
+    style: C::Style,
+) {
+    if width == 0 {
+        return;
+    }
+    let x = (step.wrapping_add(seed) % width as usize) as u16;
+    let spinner_width = SPINNER_WIDTH.min(width - x);
+    canvas.put(column + x, spinner_width, &glyph.repeat(spinner_width as usize), style);
+}
+
+/// Return `text` with all block elements, box-drawing characters, Braille patterns and other symbols drawn by the
+/// renderers replaced by a single ASCII character each, like `#`, `-`, `|` and `+`.
+pub(crate) fn to_ascii(text: &str) -> std::borrow::Cow<'_, str> {
+    if text.is_ascii() {
+        return text.into();
+    }
+    text.chars()
+        .map(|c| match c {
+            '│' | '┃' | '║' | '▐' => '|',
+            '─' | '━' | '═' | '▬' => '-',
+            '\u{2500}'..='\u{257F}' => '+',
+            '\u{2580}'..='\u{259F}' => '#',
+            '\u{2800}' => ' ',
+            '\u{2801}'..='\u{28FF}' => '#',
+            '▲' => '^',
+            '▼' => 'v',
+            '▸' | '→' => '>',
+            '●' | '⏳' => '*',
+            '·' | '…' => '.',
+            c => c,
+        })
+        .collect::<String>()
+        .into()
+}
+
+/// Format `eta`, the estimated time until something is done, like `~1m 5s left`.
+pub(crate) fn format_eta(eta: std::time::Duration) -> String {
+    format!(
+        "~{:#} left",
+        jiff::SignedDuration::from_secs(eta.as_secs().try_into().unwrap_or(i64::MAX))
+    )
+}
+
+/// Format `elapsed`, the time something has been running for, like `2m 13s`.
+pub(crate) fn format_elapsed(elapsed: std::time::Duration) -> String {
+    format!(
+        "{:#}",
+        jiff::SignedDuration::from_secs(elapsed.as_secs().try_into().unwrap_or(i64::MAX))
+    )
+}
+
+/// Format `reason` for which a task is blocked or halted along with how long it has been `since` then,
+/// like `waiting for lock for 12s`.
+pub(crate) fn format_stopped(reason: &str, since: Option<std::time::SystemTime>) -> String {
+    match since.and_then(|since| since.elapsed().ok()) {
+        Some(elapsed) => format!("{reason} for {}", format_elapsed(elapsed)),
+        None => reason.to_owned(),
+    }
+}
+
+/// Draw `values` as a sparkline of one cell per value, scaled so the largest one fills a whole cell, like `▁▃▅█`.
+#[cfg_attr(not(feature = "render-tui"), allow(dead_code))]
+pub(crate) fn sparkline(values: &[Step]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or_default().max(1);
+    values
+        .iter()
+        .map(|value| LEVELS[value * (LEVELS.len() - 1) / max])
+        .collect()
+}
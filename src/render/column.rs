@@ -0,0 +1,44 @@
+use crosstermion::nu_ansi_term::AnsiString;
+
+use crate::{
+    progress::{Key, Value},
+    render::canvas,
+};
+
+/// A user-defined column to show additional information for each task, drawn after all built-in columns.
+///
+/// Register it with [`line::Options::columns`][crate::render::line::Options::columns] or
+/// [`tui::Options::columns`][crate::render::tui::Options::columns], as both renderers draw the same columns.
+/// The terminal user interface translates the styles of the rendered text into its own.
+pub trait Column {
+    /// The title of the column, shown in lines of tasks without progress which serve as headings.
+    fn header(&self) -> &str;
+    /// The amount of terminal columns to reserve, making all lines align.
+    ///
+    /// Rendered text is padded or cut to fit.
+    fn width(&self) -> u16;
+    /// Produce the text of the column for the task at `key` and its `value`.
+    fn render(&self, key: &Key, value: &Value) -> Vec<AnsiString<'static>>;
+}
+
+/// A column showing how long each task has been running, like `2m 13s`, enabled in the line renderer by
+/// [`line::Options::show_elapsed`][crate::render::line::Options::show_elapsed].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Elapsed;
+
+impl Column for Elapsed {
+    fn header(&self) -> &str {
+        "elapsed"
+    }
+
+    fn width(&self) -> u16 {
+        9
+    }
+
+    fn render(&self, _key: &Key, value: &Value) -> Vec<AnsiString<'static>> {
+        value
+            .elapsed()
+            .map(|elapsed| vec![canvas::format_elapsed(elapsed).into()])
+            .unwrap_or_default()
+    }
+}
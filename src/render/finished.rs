@@ -0,0 +1,28 @@
+use crate::{
+    progress::{self, Key, Task},
+    render::FinishedTasks,
+};
+
+/// Return `entries` with finished tasks shown as configured by `mode`.
+///
+/// Tasks are finished if they are [done](progress::Value::is_done()), counting failed steps, without being halted.
+pub fn apply(entries: &[(Key, Task)], mode: FinishedTasks) -> Vec<(Key, Task)> {
+    let mut out: Vec<_> = entries.iter().filter(|(_, task)| !is_finished(task)).cloned().collect();
+    let finished = entries.len() - out.len();
+    if mode == FinishedTasks::Summarize && finished > 0 {
+        out.push((
+            Key::default().add_child(u16::MAX),
+            Task {
+                name: format!("{finished} tasks finished"),
+                ..Task::default()
+            },
+        ));
+    }
+    out
+}
+
+fn is_finished(task: &Task) -> bool {
+    task.progress
+        .as_ref()
+        .is_some_and(|progress| !matches!(progress.state, progress::State::Halted(_, _)) && progress.is_done())
+}
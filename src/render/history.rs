@@ -0,0 +1,187 @@
+/*!
+* A module to record the history of a progress tree to a file, to [replay][replay()] it later for finding out what
+* happened after the fact.
+*
+* **Please note** that it is behind the `render-history` feature toggle, and replaying needs the `render-tui`
+* feature toggle as well.
+*
+* Recordings are [JSON lines][crate::render::jsonl], with each frame preceded by the amount of seconds since
+* recording started, as in `{"at":1.250}`. To keep them compact, frames are only written if something changed.
+*
+* ```no_run
+* use prodash::Root;
+* let root = prodash::tree::Root::new();
+* let recording = prodash::render::history::record("progress.jsonl", root.downgrade(), Default::default())?;
+* // …work with `root`, and once done, write the last frame
+* recording.shutdown_and_wait()?;
+* # Ok::<_, std::io::Error>(())
+* ```
+*/
+use std::{
+    fs::File,
+    io,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{Root, WeakRoot, render::jsonl};
+
+/// The line preceding each frame, with the amount of seconds since recording started.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct At {
+    at: f64,
+}
+
+/// Options used for configuring the [recording][record()] of a progress tree.
+#[derive(Clone)]
+pub struct Options {
+    /// The amount of times per second at which the progress tree is checked for changes, each of which produces
+    /// a frame. If below 1.0, it determines the amount of seconds between them.
+    pub frames_per_second: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { frames_per_second: 4.0 }
+    }
+}
+
+/// Record the history of `progress` to a new file at `path` on a thread, until `progress` is dropped, writing fails
+/// or the returned handle is dropped.
+///
+/// Failure occurs if the file can't be created.
+pub fn record(
+    path: impl AsRef<Path>,
+    progress: impl WeakRoot + Send + 'static,
+    Options { frames_per_second }: Options,
+) -> io::Result<jsonl::JoinHandle> {
+    let mut out = io::BufWriter::new(File::create(path)?);
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = std::thread::Builder::new().name("render-history".into()).spawn({
+        let stop = Arc::clone(&stop);
+        move || write_until_stopped(&mut out, &progress, frames_per_second, &stop)
+    })?;
+    Ok(jsonl::JoinHandle {
+        inner: Some(handle),
+        stop,
+    })
+}
+
+/// Write timestamped frames of `progress` whenever it changed, along with its new messages, until `progress` is
+/// dropped, writing fails or `stop` is set, after which one last check for changes is made.
+fn write_until_stopped(
+    out: &mut impl io::Write,
+    progress: &impl WeakRoot,
+    frames_per_second: f32,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    let start = Instant::now();
+    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
+    let mut tasks = Vec::new();
+    let mut messages = Vec::new();
+    let mut copy_state = None;
+    let mut previous_changes = None;
+    for frame in 0.. {
+        let stopping = stop.load(Ordering::SeqCst);
+        let Some(progress) = progress.upgrade() else {
+            break;
+        };
+        copy_state = Some(progress.copy_new_messages(&mut messages, copy_state));
+        let changes = progress.changes();
+        let changed = changes.is_none() || changes != previous_changes;
+        previous_changes = changes;
+        if changed || !messages.is_empty() {
+            serde_json::to_writer(
+                &mut *out,
+                &At {
+                    at: start.elapsed().as_secs_f64(),
+                },
+            )?;
+            writeln!(out)?;
+            for message in &messages {
+                jsonl::write_message(out, message)?;
+            }
+            if changed {
+                progress.sorted_snapshot(&mut tasks);
+                jsonl::write_snapshot(out, frame, &tasks)?;
+            }
+            out.flush()?;
+        }
+        if stopping {
+            break;
+        }
+        std::thread::sleep(duration_per_frame);
+    }
+    Ok(())
+}
+
+/// Read the recording at `path`, returning each frame as the time at which it was recorded along with its lines.
+#[cfg(feature = "render-tui")]
+fn read_frames(path: &Path) -> io::Result<Vec<(Duration, Vec<String>)>> {
+    use std::io::BufRead;
+
+    let mut frames = vec![(Duration::ZERO, Vec::new())];
+    for line in io::BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        match serde_json::from_str::<At>(&line).ok().map(|At { at }| at) {
+            Some(at) => frames.push((Duration::from_secs_f64(at.max(0.0)), Vec::new())),
+            None => frames.last_mut().expect("at least one frame").1.push(line),
+        }
+    }
+    Ok(frames)
+}
+
+/// Returns a future that draws the terminal user interface of the recording at `path` like
+/// [`render_with_input(…)`][crate::render::tui::render_with_input()], showing each frame `speed` times as fast as
+/// it was recorded, so `2.0` plays it twice as fast while `0.5` plays it at half the speed.
+///
+/// Once the recording ends, its last frame stays visible until the user interface is closed, which is why
+/// [`Options::stop_if_progress_missing`][crate::render::tui::Options::stop_if_progress_missing] is ignored.
+/// Lines that can't be understood are ignored.
+///
+/// Failure may occur if the file can't be read, if `speed` isn't positive or if there is no terminal to draw into.
+#[cfg(feature = "render-tui")]
+pub fn replay(
+    path: impl AsRef<Path>,
+    out: impl std::io::Write,
+    speed: f32,
+    options: crate::render::tui::Options,
+) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+    use crate::render::{remote::Mirror, tui};
+
+    if !(speed > 0.0 && speed.is_finite()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the replay speed must be positive, got {speed}"),
+        ));
+    }
+    let frames = read_frames(path.as_ref())?;
+    let mirror = Arc::new(Mirror::default());
+    let progress = Arc::downgrade(&mirror);
+    let start = Instant::now();
+    // The stream owns the mirror to keep it alive for as long as the user interface runs.
+    let events = futures_lite::stream::unfold((mirror, frames.into_iter()), move |(mirror, mut frames)| async move {
+        let Some((at, lines)) = frames.next() else {
+            futures_lite::future::pending::<()>().await;
+            return None;
+        };
+        async_io::Timer::at(start + at.div_f32(speed)).await;
+        for line in &lines {
+            mirror.apply(line);
+        }
+        Some((tui::Event::Tick, (mirror, frames)))
+    });
+    tui::render_with_input(
+        out,
+        progress,
+        tui::Options {
+            stop_if_progress_missing: false,
+            ..options
+        },
+        Box::pin(events),
+    )
+}
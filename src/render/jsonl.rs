@@ -0,0 +1,170 @@
+/*!
+* A module to write progress as [JSON lines](https://jsonlines.org), for consumption by machines like CI systems
+* or graphical wrappers.
+*
+* **Please note** that it is behind the `render-jsonl` feature toggle.
+*
+* Each frame produces one line per task, numbered by frame and serialized as [`snapshot::Task`], followed by a line
+* with the amount of tasks of the frame so consumers can tell when a snapshot is complete, as in
+*
+* ```text
+* {"frame":3,"task":{"key":[0,null,null,null,null,null],"name":"task","id":[0,0,0,0],"progress":{"step":5,…}}}
+* {"frame":3,"tasks":1}
+* ```
+*
+* and each message sent to the tree produces a line like
+* `{"message":{"time":{"secs_since_epoch":1700000000,"nanos_since_epoch":0},"level":"Info",…}}`.
+*/
+use std::{
+    borrow::Cow,
+    io,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::{
+    Root, WeakRoot,
+    messages::Message,
+    progress::{Key, Task},
+    tree::snapshot,
+};
+
+/// A single line as written by this module.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Line<'a> {
+    /// A task that is part of `frame`.
+    Task { frame: u64, task: Box<snapshot::Task> },
+    /// The end of `frame`, which consisted of `tasks` tasks.
+    FrameEnd { frame: u64, tasks: usize },
+    /// A message sent to the tree.
+    Message { message: Cow<'a, Message> },
+}
+
+impl Line<'_> {
+    fn write_to(&self, out: &mut impl io::Write) -> io::Result<()> {
+        serde_json::to_writer(&mut *out, self)?;
+        writeln!(out)
+    }
+}
+
+/// Options used for configuring the [JSON lines renderer][render()].
+#[derive(Clone)]
+pub struct Options {
+    /// The amount of snapshots to write per second. If below 1.0, it determines the amount of seconds between them.
+    pub frames_per_second: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { frames_per_second: 1.0 }
+    }
+}
+
+/// A handle to the render thread, which when dropped will instruct it to stop and wait for it.
+pub struct JoinHandle {
+    pub(crate) inner: Option<std::thread::JoinHandle<io::Result<()>>>,
+    pub(crate) stop: Arc<AtomicBool>,
+}
+
+impl JoinHandle {
+    /// Instruct the render thread to stop after writing one last snapshot, and wait for it to finish.
+    pub fn shutdown_and_wait(mut self) -> io::Result<()> {
+        self.stop.store(true, Ordering::SeqCst);
+        self.inner
+            .take()
+            .map(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err(io::Error::other("render thread panicked")))
+            })
+            .unwrap_or(Ok(()))
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+}
+
+/// Write snapshots of `progress` and all of its messages as JSON lines to `out`, until `progress`
+/// is dropped, writing fails or the returned handle is dropped.
+pub fn render(
+    mut out: impl io::Write + Send + 'static,
+    progress: impl WeakRoot + Send + 'static,
+    Options { frames_per_second }: Options,
+) -> JoinHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = std::thread::Builder::new()
+        .name("render-jsonl".into())
+        .spawn({
+            let stop = Arc::clone(&stop);
+            move || write_until_stopped(&mut out, &progress, frames_per_second, &stop)
+        })
+        .expect("starting a thread works");
+    JoinHandle {
+        inner: Some(handle),
+        stop,
+    }
+}
+
+/// Write snapshots of `progress` and its new messages to `out` every frame, until `progress` is dropped, writing
+/// fails or `stop` is set, after which one last snapshot is written.
+pub(crate) fn write_until_stopped(
+    out: &mut impl io::Write,
+    progress: &impl WeakRoot,
+    frames_per_second: f32,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
+    let mut tasks = Vec::new();
+    let mut messages = Vec::new();
+    let mut copy_state = None;
+    for frame in 0.. {
+        let stopping = stop.load(Ordering::SeqCst);
+        let Some(progress) = progress.upgrade() else {
+            break;
+        };
+        copy_state = Some(progress.copy_new_messages(&mut messages, copy_state));
+        for message in &messages {
+            write_message(out, message)?;
+        }
+        progress.sorted_snapshot(&mut tasks);
+        write_snapshot(out, frame, &tasks)?;
+        out.flush()?;
+        if stopping {
+            break;
+        }
+        std::thread::sleep(duration_per_frame);
+    }
+    Ok(())
+}
+
+/// Write the sorted snapshot `tasks` as one line per task to `out`, marking each with the number of the `frame`,
+/// followed by a line marking the end of the frame.
+pub fn write_snapshot(out: &mut impl io::Write, frame: u64, tasks: &[(Key, Task)]) -> io::Result<()> {
+    for (key, task) in tasks {
+        Line::Task {
+            frame,
+            task: Box::new((*key, task.clone()).into()),
+        }
+        .write_to(out)?;
+    }
+    Line::FrameEnd {
+        frame,
+        tasks: tasks.len(),
+    }
+    .write_to(out)
+}
+
+/// Write `message` as a single line to `out`.
+pub fn write_message(out: &mut impl io::Write, message: &Message) -> io::Result<()> {
+    Line::Message {
+        message: Cow::Borrowed(message),
+    }
+    .write_to(out)
+}
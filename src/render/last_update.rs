@@ -0,0 +1,29 @@
+use std::{sync::atomic::Ordering, time::SystemTime};
+
+use crate::progress;
+
+/// A utility to remember the wall-clock time at which the progress of each task was last seen to change.
+#[derive(Default)]
+pub struct LastUpdate {
+    per_key: progress::PerKey<(progress::Step, SystemTime)>,
+}
+
+impl LastUpdate {
+    /// Lookup or create the state at `key`, update it with `progress` and return the time of its last change.
+    ///
+    /// Tasks seen for the first time are considered updated just now.
+    pub fn update_and_get(&mut self, key: &progress::Key, progress: Option<&progress::Value>) -> Option<SystemTime> {
+        let step = progress?.step.load(Ordering::SeqCst);
+        let (last_step, updated_at) = self.per_key.get_or_insert_with(key, || (step, SystemTime::now()));
+        if *last_step != step {
+            *last_step = step;
+            *updated_at = SystemTime::now();
+        }
+        Some(*updated_at)
+    }
+
+    /// Compare the keys in `sorted_values` with our internal state and remove all missing tasks from it.
+    pub fn reconcile(&mut self, sorted_values: &[(progress::Key, progress::Task)]) {
+        self.per_key.reconcile(sorted_values);
+    }
+}
@@ -0,0 +1,125 @@
+use std::{io, ops::RangeInclusive, time::Duration};
+
+use crate::{
+    WeakRoot, progress,
+    render::line::{ColorChoice, JoinHandle, Options, StreamKind, render},
+};
+
+/// An entry point to configure the [line renderer][render()] step by step, as alternative to filling in [`Options`].
+///
+/// ```rust,no_run
+/// use prodash::{Root, render::line::LineRenderer};
+/// let root = prodash::tree::Root::new();
+/// let handle = LineRenderer::builder()
+///     .level_range(1..=2)
+///     .timestamps(true)
+///     .fps(6.0)
+///     .build(root.downgrade());
+/// // …make progress, and once done, draw one last frame
+/// handle.shutdown_and_wait();
+/// ```
+pub struct LineRenderer;
+
+impl LineRenderer {
+    /// Return a builder which draws to standard error with the default [`Options`] unless configured otherwise.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+/// A builder for the [line renderer][render()], see [`LineRenderer::builder()`].
+#[derive(Default)]
+pub struct Builder {
+    out: Option<Box<dyn io::Write + Send>>,
+    options: Options,
+}
+
+impl Builder {
+    /// Replace all options with `options`, to change those without a method of their own.
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Draw to `out` instead of standard error.
+    pub fn output(mut self, out: impl io::Write + Send + 'static) -> Self {
+        self.out = Some(Box::new(out));
+        self
+    }
+
+    /// Draw to the given standard stream, and [configure][Options::auto_configure()] the options for it.
+    pub fn auto_configure(self, output: StreamKind) -> Self {
+        let options = self.options.auto_configure(output);
+        let builder = Builder { options, ..self };
+        match output {
+            StreamKind::Stdout => builder.output(io::stdout()),
+            StreamKind::Stderr => builder.output(io::stderr()),
+        }
+    }
+
+    /// Only show tasks at the levels in `range`, see [`Options::level_filter`].
+    pub fn level_range(mut self, range: RangeInclusive<progress::key::Level>) -> Self {
+        self.options.level_filter = Some(range);
+        self
+    }
+
+    /// Show the time at which messages were sent if `enabled`, see [`Options::timestamp`].
+    pub fn timestamps(mut self, enabled: bool) -> Self {
+        self.options.timestamp = enabled;
+        self
+    }
+
+    /// Draw `frames_per_second` frames each second, see [`Options::frames_per_second`].
+    pub fn fps(mut self, frames_per_second: f32) -> Self {
+        self.options.frames_per_second = frames_per_second;
+        self
+    }
+
+    /// Set whether or not to use color, see [`Options::colored`].
+    pub fn colored(mut self, choice: ColorChoice) -> Self {
+        self.options.colored = choice;
+        self
+    }
+
+    /// Set whether the output is a terminal, see [`Options::output_is_terminal`].
+    pub fn output_is_terminal(mut self, yes: bool) -> Self {
+        self.options.output_is_terminal = yes;
+        self
+    }
+
+    /// Assume a terminal of `columns` × `rows`, see [`Options::terminal_dimensions`].
+    pub fn terminal_dimensions(mut self, columns: u16, rows: u16) -> Self {
+        self.options.terminal_dimensions = (columns, rows);
+        self
+    }
+
+    /// Only start drawing progress after `delay`, see [`Options::initial_delay`].
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.options.initial_delay = Some(delay);
+        self
+    }
+
+    /// Hide the cursor while drawing if `yes`, see [`Options::hide_cursor`].
+    pub fn hide_cursor(mut self, yes: bool) -> Self {
+        self.options.hide_cursor = yes;
+        self
+    }
+
+    /// Show how fast tasks progress if `enabled`, see [`Options::throughput`].
+    pub fn throughput(mut self, enabled: bool) -> Self {
+        self.options.throughput = enabled;
+        self
+    }
+
+    /// Keep drawing even if there is no task if `yes`, see [`Options::keep_running_if_progress_is_empty`].
+    pub fn keep_running_if_progress_is_empty(mut self, yes: bool) -> Self {
+        self.options.keep_running_if_progress_is_empty = yes;
+        self
+    }
+
+    /// Start drawing `progress` on a thread as configured, like [`render()`] does.
+    pub fn build(self, progress: impl WeakRoot + Send + 'static) -> JoinHandle {
+        let out = self.out.unwrap_or_else(|| Box::new(io::stderr()));
+        render(out, progress, self.options)
+    }
+}
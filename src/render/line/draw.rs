@@ -1,27 +1,42 @@
 use std::{
+    borrow::Cow,
     collections::{VecDeque, hash_map::DefaultHasher},
     hash::{Hash, Hasher},
     io,
     ops::RangeInclusive,
-    sync::atomic::Ordering,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
 };
 
 use crosstermion::{
     color,
+    crossterm::terminal::{Clear, ClearType},
     nu_ansi_term::{AnsiString, AnsiStrings, Color, Style},
 };
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::{
-    Root, Throughput,
+    Root, Throughput, Trend,
     messages::{Message, MessageCopyState, MessageLevel},
     progress::{self, Value},
+    render::{
+        BarStyle, FinishedTasks, NameAbbrev,
+        canvas::{self, Canvas},
+        finished,
+        last_update::LastUpdate,
+        line::{Column, Layout, redraw::Pacer},
+        message_log::MessageLog,
+        order,
+    },
+    time::{TimestampFormat, Timezone},
     unit,
 };
 
 #[derive(Default)]
 pub struct State {
     tree: Vec<(progress::Key, progress::Task)>,
+    /// The generation of the progress tree `tree` was taken at, to avoid taking snapshots if nothing changed.
+    tree_generation: Option<usize>,
     tree_hash: u64,
     messages: Vec<Message>,
     for_next_copy: Option<MessageCopyState>,
@@ -32,24 +47,60 @@ pub struct State {
     /// The amount of blocks per line we have written last time.
     blocks_per_line: VecDeque<u16>,
     pub throughput: Option<Throughput>,
+    pub last_update: Option<LastUpdate>,
+    /// The keys of the tasks on the critical path, if it's highlighted.
+    pub critical_path: Option<Vec<progress::Key>>,
+    pub pacer: Option<Pacer>,
+    pub message_log: Option<MessageLog>,
+    /// The time at which the `pv`-style line was first drawn.
+    #[cfg(feature = "unit-bytes")]
+    pv_started_at: Option<std::time::Instant>,
 }
 
 impl State {
     pub(crate) fn update_from_progress(&mut self, progress: &impl Root) -> bool {
-        progress.sorted_snapshot(&mut self.tree);
+        progress.sorted_snapshot_if_changed(&mut self.tree, &mut self.tree_generation);
         let mut hasher = DefaultHasher::new();
         self.tree.hash(&mut hasher);
         let cur_hash = hasher.finish();
 
+        self.log_new_messages(progress).ok();
+        if self.tree_hash == cur_hash {
+            return false;
+        }
+        let draw = self.pacer.as_mut().is_none_or(|pacer| pacer.should_draw(&self.tree));
+        // Remember the state only once it's drawn so skipped changes are drawn eventually.
+        if draw {
+            self.tree_hash = cur_hash;
+            // Steps change which task holds up completion, so follow them on every frame.
+            if let Some(critical_path) = self.critical_path.as_mut() {
+                *critical_path = progress::critical_path(&self.tree);
+            }
+        }
+        draw
+    }
+    /// Copy messages sent since the last call into `messages`, and write them to the message log if there is one.
+    pub(crate) fn log_new_messages(&mut self, progress: &impl Root) -> io::Result<()> {
         self.for_next_copy = progress
             .copy_new_messages(&mut self.messages, self.for_next_copy.take())
             .into();
-        let changed = self.tree_hash != cur_hash;
-        self.tree_hash = cur_hash;
-        changed
+        match self.message_log.as_mut() {
+            Some(log) => {
+                log.append(&self.messages)?;
+                log.flush()
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Return true if there is no task to draw.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tree.is_empty()
     }
+
     pub(crate) fn clear(&mut self) {
         self.tree.clear();
+        self.tree_generation = None;
         self.messages.clear();
         self.for_next_copy.take();
     }
@@ -57,21 +108,55 @@ impl State {
 
 pub struct Options {
     pub level_filter: Option<RangeInclusive<progress::key::Level>>,
+    pub explicit_order: Option<Vec<String>>,
     pub terminal_dimensions: (u16, u16),
     pub keep_running_if_progress_is_empty: bool,
     pub output_is_terminal: bool,
     pub colored: bool,
     pub timestamp: bool,
+    pub timezone: Timezone,
+    pub timestamp_format: TimestampFormat,
+    pub message_level: Option<MessageLevel>,
+    pub max_messages_per_frame: Option<usize>,
     pub hide_cursor: bool,
+    pub depth_palette: Vec<Color>,
+    pub columns: Vec<Arc<dyn Column + Send + Sync>>,
+    pub bar_style: BarStyle,
+    pub fill_mapping: Option<fn(f32) -> f32>,
+    pub name_abbrev: NameAbbrev,
+    pub show_trend: bool,
+    pub show_eta: bool,
+    pub show_total_throughput: bool,
+    pub ascii: bool,
+    pub finished_tasks: FinishedTasks,
+    #[cfg_attr(not(feature = "unit-bytes"), allow(dead_code))]
+    pub layout: Layout,
 }
 
-fn messages(
-    out: &mut impl io::Write,
-    state: &mut State,
-    colored: bool,
-    max_height: usize,
-    timestamp: bool,
-) -> io::Result<()> {
+impl Options {
+    fn depth_color(&self, level: progress::key::Level) -> Option<Color> {
+        if self.depth_palette.is_empty() {
+            return None;
+        }
+        Some(self.depth_palette[(level as usize).saturating_sub(1) % self.depth_palette.len()])
+    }
+}
+
+fn messages(out: &mut impl io::Write, state: &mut State, config: &Options) -> io::Result<()> {
+    let Options {
+        colored,
+        timestamp,
+        timestamp_format,
+        timezone,
+        max_messages_per_frame,
+        ..
+    } = *config;
+    let (max_width, max_height) = if config.output_is_terminal {
+        config.terminal_dimensions
+    } else {
+        (u16::MAX, config.terminal_dimensions.1)
+    };
+    let max_height = max_height as usize;
     let mut brush = color::Brush::new(colored);
     fn to_color(level: MessageLevel) -> Color {
         use crate::messages::MessageLevel::*;
@@ -79,16 +164,35 @@ fn messages(
             Info => Color::White,
             Success => Color::Green,
             Failure => Color::Red,
+            Warning => Color::Yellow,
+            Debug | Trace => Color::DarkGray,
         }
     }
     let mut tokens: Vec<AnsiString<'_>> = Vec::with_capacity(6);
     let mut current_maximum = state.message_origin_size.iter().max().cloned().unwrap_or(0);
+    let skipped = state
+        .messages
+        .len()
+        .saturating_sub(max_messages_per_frame.unwrap_or(usize::MAX));
+    if skipped > 0 {
+        let blocks_drawn_during_previous_tick = state.blocks_per_line.pop_front().unwrap_or(0);
+        tokens.push(" ".into());
+        tokens.push(
+            brush
+                .style(Style::default().dimmed())
+                .paint(format!("{skipped} older messages not shown")),
+        );
+        truncate_tokens(&mut tokens, max_width);
+        write!(out, "{}", AnsiStrings(tokens.as_slice()))?;
+        newline_with_overdraw(out, &tokens, blocks_drawn_during_previous_tick)?;
+    }
     for Message {
         time,
         level,
         origin,
         message,
-    } in &state.messages
+        ..
+    } in &state.messages[skipped..]
     {
         tokens.clear();
         let blocks_drawn_during_previous_tick = state.blocks_per_line.pop_front().unwrap_or(0);
@@ -105,7 +209,7 @@ fn messages(
             tokens.push(
                 brush
                     .style(color.dimmed().on(Color::Yellow))
-                    .paint(crate::time::format_time_for_messages(*time)),
+                    .paint(timestamp_format.format(*time, timezone)),
             );
             tokens.push(Style::default().paint(" "));
         } else {
@@ -119,6 +223,7 @@ fn messages(
         )));
         tokens.push(" ".into());
         tokens.push(brush.style(color.bold()).paint(message));
+        truncate_tokens(&mut tokens, max_width);
         let message_block_count = block_count_sans_ansi_codes(&tokens);
         write!(out, "{}", AnsiStrings(tokens.as_slice()))?;
 
@@ -132,17 +237,41 @@ fn messages(
 }
 
 pub fn all(out: &mut impl io::Write, show_progress: bool, state: &mut State, config: &Options) -> io::Result<()> {
-    if !config.keep_running_if_progress_is_empty && state.tree.is_empty() {
-        return Err(io::Error::other("stop as progress is empty"));
+    if let Some(level) = config.message_level {
+        state
+            .messages
+            .retain(|message| message.level.severity() >= level.severity());
     }
-    messages(
-        out,
-        state,
-        config.colored,
-        config.terminal_dimensions.1 as usize,
-        config.timestamp,
-    )?;
+    messages(out, state, config)?;
 
+    #[cfg(feature = "unit-bytes")]
+    if show_progress && config.output_is_terminal && config.layout == Layout::PvStyle {
+        let started_at = *state.pv_started_at.get_or_insert_with(std::time::Instant::now);
+        // The rate is always shown, so throughput is measured even if it wasn't asked for.
+        let throughput = state.throughput.get_or_insert_with(Throughput::default);
+        throughput.update_elapsed();
+        let mut rate: Option<progress::Step> = None;
+        for (key, task) in &state.tree {
+            let progress = task.progress.as_ref().filter(|progress| !progress.aggregated);
+            if let Some(tp) = throughput.update_and_get(key, progress) {
+                rate = Some(rate.unwrap_or(0).saturating_add(tp.value_change_in_timespan));
+            }
+        }
+        throughput.reconcile(&state.tree);
+        let line = super::pv::line(&state.tree, started_at.elapsed(), rate, config.terminal_dimensions.0);
+        let width = line.width() as u16;
+        let previous_width = state.blocks_per_line.pop_front().unwrap_or(0);
+        // Return to the start of the line so messages or the next frame overwrite it in place.
+        write!(
+            out,
+            "{line}{:>padding$}\r",
+            "",
+            padding = previous_width.saturating_sub(width) as usize
+        )?;
+        state.blocks_per_line.clear();
+        state.blocks_per_line.push_back(width);
+        return Ok(());
+    }
     if show_progress && config.output_is_terminal {
         if let Some(tp) = state.throughput.as_mut() {
             tp.update_elapsed();
@@ -151,44 +280,118 @@ pub fn all(out: &mut impl io::Write, show_progress: bool, state: &mut State, con
             .level_filter
             .clone()
             .unwrap_or(RangeInclusive::new(0, progress::key::Level::MAX));
-        let lines_to_be_drawn = state
-            .tree
-            .iter()
-            .filter(|(k, _)| level_range.contains(&k.level()))
-            .count();
+        let ordered_tree;
+        let tree = match config.explicit_order.as_deref() {
+            Some(names) => {
+                ordered_tree = order::by_names(&state.tree, names);
+                ordered_tree.as_slice()
+            }
+            None => state.tree.as_slice(),
+        };
+        let shown_tree;
+        let tree = match config.finished_tasks {
+            FinishedTasks::Show => tree,
+            mode => {
+                shown_tree = finished::apply(tree, mode);
+                shown_tree.as_slice()
+            }
+        };
+        let show_total_throughput = config.show_total_throughput && state.throughput.is_some();
+        let lines_to_be_drawn =
+            tree.iter().filter(|(k, _)| level_range.contains(&k.level())).count() + usize::from(show_total_throughput);
         if state.blocks_per_line.len() < lines_to_be_drawn {
             state.blocks_per_line.resize(lines_to_be_drawn, 0);
         }
         let mut tokens: Vec<AnsiString<'_>> = Vec::with_capacity(4);
+        let frame = Frame {
+            config,
+            midpoint: state.last_progress_midpoint,
+        };
         let mut max_midpoint = 0;
-        for ((key, value), ref mut blocks_in_last_iteration) in state
-            .tree
+        let (mut total_throughput, mut total_throughput_unit) = (0, None);
+        for ((key, value), ref mut blocks_in_last_iteration) in tree
             .iter()
             .filter(|(k, _)| level_range.contains(&k.level()))
             .zip(state.blocks_per_line.iter_mut())
         {
+            let trend = state
+                .throughput
+                .as_ref()
+                .filter(|_| config.show_trend)
+                .and_then(|tp| tp.trend(key));
+            let eta = state
+                .throughput
+                .as_ref()
+                .filter(|_| config.show_eta)
+                .zip(value.progress.as_ref())
+                .and_then(|(tp, progress)| tp.eta(key, progress))
+                .map(canvas::format_eta);
+            let last_update = state
+                .last_update
+                .as_mut()
+                .and_then(|lu| lu.update_and_get(key, value.progress.as_ref()))
+                .map(|time| config.timestamp_format.format(time, config.timezone));
+            let throughput = state
+                .throughput
+                .as_mut()
+                .and_then(|tp| tp.update_and_get(key, value.progress.as_ref()));
+            if let Some((throughput, unit)) = throughput
+                .as_ref()
+                .zip(value.progress.as_ref().and_then(|p| p.unit.as_ref()))
+                .filter(|(_, unit)| unit.shows_throughput())
+            {
+                total_throughput += throughput.value_change_in_timespan;
+                total_throughput_unit.get_or_insert(unit);
+            }
             max_midpoint = max_midpoint.max(
                 format_progress(
                     key,
                     value,
-                    config.terminal_dimensions.0,
-                    config.colored,
-                    state.last_progress_midpoint,
-                    state
-                        .throughput
-                        .as_mut()
-                        .and_then(|tp| tp.update_and_get(key, value.progress.as_ref())),
+                    &frame,
+                    Annotations {
+                        throughput,
+                        trend,
+                        eta,
+                        last_update,
+                        blocker: value
+                            .progress
+                            .as_ref()
+                            .and_then(|p| p.blocker(&state.tree))
+                            .map(|blocker| blocker.name.as_str()),
+                        on_critical_path: state.critical_path.as_ref().is_some_and(|path| path.contains(key)),
+                    },
                     &mut tokens,
                 )
                 .unwrap_or(0),
             );
-            write!(out, "{}", AnsiStrings(tokens.as_slice()))?;
-
-            **blocks_in_last_iteration = newline_with_overdraw(out, &tokens, **blocks_in_last_iteration)?;
+            **blocks_in_last_iteration = write_line(out, &mut tokens, config, **blocks_in_last_iteration)?;
+        }
+        if show_total_throughput {
+            tokens.clear();
+            tokens.push(
+                color::Brush::new(config.colored)
+                    .style(Color::White.bold())
+                    .paint(" total"),
+            );
+            if let Some(unit) = total_throughput_unit {
+                let mut throughput = String::from(" ");
+                unit.as_display_value()
+                    .display_throughput(
+                        &mut throughput,
+                        &unit::display::Throughput::new(total_throughput, Duration::from_secs(1)),
+                    )
+                    .ok();
+                tokens.push(throughput.into());
+            }
+            let blocks_in_last_iteration = &mut state.blocks_per_line[lines_to_be_drawn - 1];
+            *blocks_in_last_iteration = write_line(out, &mut tokens, config, *blocks_in_last_iteration)?;
         }
         if let Some(tp) = state.throughput.as_mut() {
             tp.reconcile(&state.tree);
         }
+        if let Some(lu) = state.last_update.as_mut() {
+            lu.reconcile(&state.tree);
+        }
         state.last_progress_midpoint = Some(max_midpoint);
         // overwrite remaining lines that we didn't touch naturally
         let lines_drawn = lines_to_be_drawn;
@@ -206,6 +409,36 @@ pub fn all(out: &mut impl io::Write, show_progress: bool, state: &mut State, con
     Ok(())
 }
 
+/// Erase all progress drawn below the cursor and forget the width of its lines, as the terminal may have reflowed them
+/// after it was resized.
+pub fn clear_progress(out: &mut impl io::Write, state: &mut State) -> io::Result<()> {
+    crosstermion::execute!(out, Clear(ClearType::FromCursorDown))?;
+    state.blocks_per_line.clear();
+    Ok(())
+}
+
+/// Write `tokens` as line fitting the terminal as configured in `config`, overwriting the `blocks_in_last_iteration`
+/// written in its place before, and return the amount of blocks written.
+fn write_line(
+    out: &mut impl io::Write,
+    tokens: &mut Vec<AnsiString<'_>>,
+    config: &Options,
+    blocks_in_last_iteration: u16,
+) -> io::Result<u16> {
+    truncate_tokens(tokens, config.terminal_dimensions.0);
+    if config.ascii {
+        for token in tokens.iter_mut() {
+            let text = match canvas::to_ascii(token.as_str()) {
+                Cow::Owned(text) => text,
+                Cow::Borrowed(_) => continue,
+            };
+            *token = token.style_ref().paint(text);
+        }
+    }
+    write!(out, "{}", AnsiStrings(tokens.as_slice()))?;
+    newline_with_overdraw(out, tokens, blocks_in_last_iteration)
+}
+
 /// Must be called directly after `tokens` were drawn, without newline. Takes care of adding the newline.
 fn newline_with_overdraw(
     out: &mut impl io::Write,
@@ -227,49 +460,263 @@ fn newline_with_overdraw(
     Ok(current_block_count)
 }
 
+/// Cut `text` so that it takes no more than `max_width` columns, marking the cut with an ellipsis.
+pub(super) fn truncate_to_width(mut text: String, max_width: u16) -> String {
+    if text.width() <= max_width as usize {
+        return text;
+    }
+    let mut width = 0;
+    let cut_at = text
+        .char_indices()
+        .find(|(_, c)| {
+            width += c.width().unwrap_or(0);
+            width >= max_width as usize
+        })
+        .map_or(text.len(), |(idx, _)| idx);
+    text.truncate(cut_at);
+    if max_width > 0 {
+        text.push('…');
+    }
+    text
+}
+
+/// Cut `tokens` so that they take no more than `max_width` columns, marking the cut with an ellipsis.
+///
+/// This keeps lines from wrapping, which would break overwriting them in place with the next frame.
+fn truncate_tokens(tokens: &mut Vec<AnsiString<'_>>, max_width: u16) {
+    if max_width == 0 || block_count_sans_ansi_codes(tokens) <= max_width {
+        return;
+    }
+    let budget = max_width - 1;
+    let mut width = 0u16;
+    for index in 0..tokens.len() {
+        let token_width = tokens[index].as_str().width() as u16;
+        if width.saturating_add(token_width) <= budget {
+            width += token_width;
+            continue;
+        }
+        let mut text = String::new();
+        for c in tokens[index].as_str().chars() {
+            let c_width = c.width().unwrap_or(0) as u16;
+            if width.saturating_add(c_width) > budget {
+                break;
+            }
+            width += c_width;
+            text.push(c);
+        }
+        text.push('…');
+        tokens[index] = tokens[index].style_ref().paint(text);
+        tokens.truncate(index + 1);
+        return;
+    }
+}
+
+/// The width of all `columns`, including the space separating each of them.
+fn columns_width(columns: &[Arc<dyn Column + Send + Sync>]) -> u16 {
+    columns.iter().map(|c| c.width() + 1).sum()
+}
+
 fn block_count_sans_ansi_codes(strings: &[AnsiString<'_>]) -> u16 {
     strings.iter().map(|s| s.as_str().width() as u16).sum()
 }
 
-fn draw_progress_bar(p: &Value, style: Style, mut blocks_available: u16, colored: bool, buf: &mut Vec<AnsiString<'_>>) {
-    let mut brush = color::Brush::new(colored);
-    let styled_brush = brush.style(style);
+/// The tokens of a single line, with columns counted from the point at which drawing started.
+struct Tokens<'a, 'b> {
+    buf: &'b mut Vec<AnsiString<'a>>,
+    brush: color::Brush,
+    column: u16,
+}
+
+impl<'a, 'b> Tokens<'a, 'b> {
+    fn new(buf: &'b mut Vec<AnsiString<'a>>, colored: bool) -> Self {
+        Tokens {
+            buf,
+            brush: color::Brush::new(colored),
+            column: 0,
+        }
+    }
+}
+
+impl Canvas for Tokens<'_, '_> {
+    type Style = Style;
+
+    fn put(&mut self, column: u16, width: u16, text: &str, style: Style) -> u16 {
+        if column > self.column {
+            self.buf.push(" ".repeat((column - self.column) as usize).into());
+            self.column = column;
+        }
+        let mut blocks = 0;
+        let text: String = text
+            .chars()
+            .take_while(|c| {
+                blocks += c.width().unwrap_or(0) as u16;
+                blocks <= width
+            })
+            .collect();
+        let blocks = text.width() as u16;
+        self.buf.push(self.brush.style(style).paint(text));
+        self.column += blocks;
+        blocks
+    }
+}
+
+/// The cells of a single line of narrow glyphs, to be able to draw text on top of what was drawn before.
+#[derive(Default)]
+struct Cells(Vec<(char, Style)>);
+
+impl Cells {
+    /// Put all cells into `tokens`, with each run of cells of the same style as one token.
+    fn draw_into(self, tokens: &mut Tokens<'_, '_>) {
+        let mut column = 0;
+        for run in self.0.chunk_by(|a, b| a.1 == b.1) {
+            let text: String = run.iter().map(|(c, _)| *c).collect();
+            column += tokens.put(column, run.len() as u16, &text, run[0].1);
+        }
+    }
+}
+
+impl Canvas for Cells {
+    type Style = Style;
+
+    fn put(&mut self, column: u16, width: u16, text: &str, style: Style) -> u16 {
+        let mut drawn = 0;
+        for c in text.chars().take(width as usize) {
+            let index = (column + drawn) as usize;
+            if self.0.len() <= index {
+                self.0.resize(index + 1, (' ', Style::default()));
+            }
+            self.0[index] = (c, style);
+            drawn += 1;
+        }
+        drawn
+    }
+}
+
+/// Draw a bar `width` cells wide for `p` at the given `fraction` onto `surface` and return the amount of filled cells.
+///
+/// If steps failed, the successful ones are drawn in green and followed by the failed ones drawn in red,
+/// at the resolution of whole cells.
+fn bounded_bar<C: Canvas<Style = Style>>(
+    surface: &mut C,
+    width: u16,
+    p: &Value,
+    fraction: f32,
+    style: Style,
+    glyphs: &canvas::Glyphs,
+    fill_mapping: Option<fn(f32) -> f32>,
+) -> u16 {
+    match p.outcome_fractions() {
+        Some((succeeded, failed)) => {
+            let cells =
+                |fraction: f32| (canvas::fill(fraction, fill_mapping).clamp(0.0, 1.0) * width as f32).round() as u16;
+            let succeeded_width = cells(succeeded);
+            let failed_width = cells(succeeded + failed).saturating_sub(succeeded_width);
+            let filled_width = succeeded_width + failed_width;
+            surface.put(
+                0,
+                succeeded_width,
+                &glyphs.full.repeat(succeeded_width as usize),
+                Color::Green.normal(),
+            );
+            surface.put(
+                succeeded_width,
+                failed_width,
+                &"x".repeat(failed_width as usize),
+                Color::Red.normal(),
+            );
+            surface.put(
+                filled_width,
+                width - filled_width,
+                &glyphs.empty.repeat((width - filled_width) as usize),
+                style.dimmed(),
+            );
+            filled_width
+        }
+        None => canvas::bar(
+            surface,
+            0,
+            width,
+            canvas::fill(fraction, fill_mapping),
+            glyphs,
+            style,
+            Some(style.dimmed()),
+        ),
+    }
+}
 
+fn draw_progress_bar(
+    p: &Value,
+    style: Style,
+    mut blocks_available: u16,
+    colored: bool,
+    bar_style: BarStyle,
+    fill_mapping: Option<fn(f32) -> f32>,
+    buf: &mut Vec<AnsiString<'_>>,
+) {
     blocks_available = blocks_available.saturating_sub(3); // account for…I don't really know it's magic
     buf.push(" [".into());
+    let glyphs = bar_style.glyphs.resolve(&canvas::ASCII);
+    let mut tokens = Tokens::new(buf, colored);
     match p.fraction() {
-        Some(mut fraction) => {
-            fraction = fraction.min(1.0);
-            blocks_available = blocks_available.saturating_sub(1); // account for '>' apparently
-            let progress_blocks = (blocks_available as f32 * fraction).floor() as usize;
-            buf.push(styled_brush.paint(format!("{:=<width$}", "", width = progress_blocks)));
-            buf.push(styled_brush.paint(">"));
-            buf.push(styled_brush.style(style.dimmed()).paint(format!(
-                "{:-<width$}",
-                "",
-                width = (blocks_available - progress_blocks as u16) as usize
-            )));
+        Some(fraction) if bar_style.label_overlay => {
+            let (label, label_column) = canvas::overlay_label(
+                blocks_available,
+                canvas::percentage(fraction, p.done_at, bar_style.adaptive_precision),
+            );
+            match label_column {
+                Some(label_column) => {
+                    let mut cells = Cells::default();
+                    let filled_width =
+                        bounded_bar(&mut cells, blocks_available, p, fraction, style, glyphs, fill_mapping);
+                    for (column, c) in (label_column..).zip(label.chars()) {
+                        let label_style = if column < filled_width {
+                            style.reverse()
+                        } else {
+                            style.bold()
+                        };
+                        cells.put(column, 1, c.encode_utf8(&mut [0; 4]), label_style);
+                    }
+                    cells.draw_into(&mut tokens);
+                }
+                None => {
+                    let bar_width = blocks_available.saturating_sub(label.len() as u16 + 1);
+                    bounded_bar(&mut tokens, bar_width, p, fraction, style, glyphs, fill_mapping);
+                    tokens.put(
+                        bar_width + 1,
+                        blocks_available.saturating_sub(bar_width + 1),
+                        &label,
+                        style,
+                    );
+                    tokens.put(blocks_available, 0, "", style);
+                }
+            }
+        }
+        Some(fraction) => {
+            bounded_bar(&mut tokens, blocks_available, p, fraction, style, glyphs, fill_mapping);
         }
         None => {
-            const CHARS: [char; 6] = ['=', '=', '=', ' ', ' ', ' '];
-            buf.push(
-                styled_brush.paint(
-                    (p.step.load(Ordering::SeqCst)..usize::MAX)
-                        .take(blocks_available as usize)
-                        .map(|idx| CHARS[idx % CHARS.len()])
-                        .rev()
-                        .collect::<String>(),
-                ),
+            canvas::spinner(
+                &mut tokens,
+                0,
+                blocks_available,
+                p.step.load(Ordering::SeqCst),
+                0,
+                "=",
+                style,
             );
+            // pad the remaining space to keep the closing bracket in place
+            tokens.put(blocks_available, 0, "", style);
         }
     }
     buf.push("]".into());
 }
 
-fn progress_style(p: &Value) -> Style {
+fn progress_style(p: &Value, depth_color: Option<Color>) -> Style {
     use crate::progress::State::*;
-    match p.state {
-        Running => if let Some(fraction) = p.fraction() {
+    let style = match p.state {
+        Running => if let Some(color) = depth_color {
+            color
+        } else if let Some(fraction) = p.fraction() {
             if fraction > 0.8 { Color::Green } else { Color::Yellow }
         } else {
             Color::White
@@ -277,26 +724,65 @@ fn progress_style(p: &Value) -> Style {
         .normal(),
         Halted(_, _) => Color::Red.dimmed(),
         Blocked(_, _) => Color::Red.normal(),
+    };
+    if p.paused_since.is_some() {
+        style.dimmed()
+    } else {
+        style
     }
 }
 
+/// What's the same for all tasks drawn in one frame.
+struct Frame<'c> {
+    config: &'c Options,
+    /// The column at which progress bars start, as determined by the previous frame.
+    midpoint: Option<u16>,
+}
+
+/// What's shown about a task in addition to its progress, as computed by the trackers of the renderer.
+struct Annotations<'a> {
+    throughput: Option<unit::display::Throughput>,
+    trend: Option<Trend>,
+    eta: Option<String>,
+    last_update: Option<String>,
+    /// The name of the task it waits on.
+    blocker: Option<&'a str>,
+    on_critical_path: bool,
+}
+
 fn format_progress<'a>(
     key: &progress::Key,
     value: &'a progress::Task,
-    column_count: u16,
-    colored: bool,
-    midpoint: Option<u16>,
-    throughput: Option<unit::display::Throughput>,
+    Frame { config, midpoint }: &Frame<'_>,
+    Annotations {
+        throughput,
+        trend,
+        eta,
+        last_update,
+        blocker,
+        on_critical_path,
+    }: Annotations<'_>,
     buf: &mut Vec<AnsiString<'a>>,
 ) -> Option<u16> {
+    let Options {
+        colored,
+        terminal_dimensions: (column_count, _),
+        ref columns,
+        bar_style,
+        fill_mapping,
+        name_abbrev,
+        ..
+    } = **config;
     let mut brush = color::Brush::new(colored);
     buf.clear();
 
+    let name_style = |style: Style| if on_critical_path { style.underline() } else { style };
     buf.push(Style::new().paint(format!("{:>level$}", "", level = key.level() as usize)));
     match value.progress.as_ref() {
         Some(progress) => {
-            let style = progress_style(progress);
-            buf.push(brush.style(Color::Cyan.bold()).paint(&value.name));
+            let style = progress_style(progress, config.depth_color(key.level()));
+            let name = name_abbrev.abbreviate(&value.name, column_count as usize / 2);
+            buf.push(brush.style(name_style(Color::Cyan.bold())).paint(name));
             buf.push(" ".into());
 
             let pre_unit = buf.len();
@@ -307,6 +793,10 @@ fn format_progress<'a>(
                     buf.push(values_brush.paint(display.values().to_string()));
                     buf.push(" ".into());
                     buf.push(display.unit().to_string().into());
+                    if let Some(trend) = trend.filter(|_| unit.shows_throughput()) {
+                        buf.push(" ".into());
+                        buf.push(values_brush.paint(trend.symbol()));
+                    }
                 }
                 None => {
                     buf.push(values_brush.paint(match progress.done_at {
@@ -315,6 +805,10 @@ fn format_progress<'a>(
                     }));
                 }
             }
+            if let Some(eta) = eta {
+                buf.push(" ".into());
+                buf.push(values_brush.paint(eta));
+            }
             let desired_midpoint = block_count_sans_ansi_codes(buf.as_slice());
             let actual_midpoint = if let Some(midpoint) = midpoint {
                 let padding = midpoint.saturating_sub(desired_midpoint);
@@ -325,15 +819,78 @@ fn format_progress<'a>(
             } else {
                 desired_midpoint
             };
-            let blocks_left = column_count.saturating_sub(actual_midpoint);
+            let blocks_left = column_count
+                .saturating_sub(actual_midpoint)
+                .saturating_sub(columns_width(columns));
+            let detail = progress
+                .detail
+                .as_deref()
+                .map(|detail| truncate_to_width(format!(" {detail}"), blocks_left / 2));
+            let mut stopped = String::new();
+            if let Some(since) = progress.paused_since {
+                stopped.push_str(&format!(" [{}]", canvas::format_stopped("paused", Some(since))));
+            }
+            if let progress::State::Blocked(reason, _) | progress::State::Halted(reason, _) = progress.state {
+                stopped.push_str(&format!(
+                    " [{}]",
+                    canvas::format_stopped(reason, progress.stopped_since)
+                ));
+            }
+            let stopped = (!stopped.is_empty()).then(|| truncate_to_width(stopped, blocks_left / 3));
+            let waiting = blocker.map(|name| truncate_to_width(format!(" ⏳ waiting on {name}"), blocks_left / 3));
+            let last_update = last_update.map(|time| format!(" {time}"));
+            let blocks_left = blocks_left
+                .saturating_sub(detail.as_deref().map_or(0, |d| d.width() as u16))
+                .saturating_sub(stopped.as_deref().map_or(0, |s| s.width() as u16))
+                .saturating_sub(waiting.as_deref().map_or(0, |w| w.width() as u16))
+                .saturating_sub(last_update.as_deref().map_or(0, |t| t.width() as u16));
             if blocks_left > 0 {
-                draw_progress_bar(progress, style, blocks_left, colored, buf);
+                draw_progress_bar(progress, style, blocks_left, colored, bar_style, fill_mapping, buf);
+            }
+            if let Some(stopped) = stopped {
+                buf.push(brush.style(style).paint(stopped));
+            }
+            if let Some(detail) = detail {
+                buf.push(brush.style(Color::Red.bold()).paint(detail));
+            }
+            if let Some(waiting) = waiting {
+                buf.push(brush.style(Color::Yellow.normal()).paint(waiting));
+            }
+            if let Some(last_update) = last_update {
+                buf.push(brush.style(Style::new().dimmed()).paint(last_update));
+            }
+            for column in columns {
+                let rendered = column.render(key, progress);
+                let mut tokens = Tokens::new(buf, colored);
+                tokens.put(1, column.width(), "", Style::default());
+                for text in &rendered {
+                    let remaining = (column.width() + 1).saturating_sub(tokens.column);
+                    tokens.put(tokens.column, remaining, text.as_str(), *text.style_ref());
+                }
+                tokens.put(column.width() + 1, 0, "", Style::default());
             }
             Some(desired_midpoint)
         }
         None => {
-            // headline only - FIXME: would have to truncate it if it is too long for the line…
-            buf.push(brush.style(Color::White.bold()).paint(&value.name));
+            // headline only
+            let name = name_abbrev.abbreviate(
+                &value.name,
+                column_count
+                    .saturating_sub(key.level() as u16)
+                    .saturating_sub(columns_width(columns)) as usize,
+            );
+            buf.push(brush.style(name_style(Color::White.bold())).paint(name));
+            if !columns.is_empty() {
+                let padding = column_count
+                    .saturating_sub(block_count_sans_ansi_codes(buf.as_slice()))
+                    .saturating_sub(columns_width(columns));
+                buf.push(" ".repeat(padding as usize).into());
+                for column in columns {
+                    let mut tokens = Tokens::new(buf, colored);
+                    tokens.put(1, column.width(), column.header(), Style::new().dimmed());
+                    tokens.put(column.width() + 1, 0, "", Style::default());
+                }
+            }
             None
         }
     }
@@ -1,13 +1,28 @@
-#[cfg(feature = "signal-hook")]
-use std::sync::Arc;
 use std::{
     io,
     ops::RangeInclusive,
-    sync::atomic::{AtomicBool, Ordering},
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
-use crate::{Throughput, WeakRoot, progress, render::line::draw};
+use crosstermion::nu_ansi_term::Color;
+
+use crate::{
+    Root, Throughput, WeakRoot,
+    messages::MessageLevel,
+    progress,
+    render::{
+        BarStyle, FinishedTasks, NameAbbrev, Profile,
+        last_update::LastUpdate,
+        line::{AdaptiveRedraw, Column, Elapsed, draw, redraw::Pacer},
+        message_log::MessageLog,
+    },
+    time::{TimestampFormat, Timezone},
+};
 
 /// Options used for configuring a [line renderer][render()].
 #[derive(Clone)]
@@ -17,17 +32,49 @@ pub struct Options {
     /// If false, we won't print any live progress, only log messages.
     pub output_is_terminal: bool,
 
-    /// If true, _(default: true)_ we will display color. You should use `output_is_terminal && crosstermion::should_colorize()`
-    /// to determine this value.
+    /// Whether to display color _(default: [`ColorChoice::Always`])_.
     ///
-    /// Please note that you can enforce color even if the output stream is not connected to a terminal by setting
-    /// this field to true.
-    pub colored: bool,
+    /// Use [`ColorChoice::Auto`] to decide based on `output_is_terminal` and the environment, or
+    /// [`ColorChoice::Always`] to enforce color even if the output stream is not connected to a terminal.
+    pub colored: ColorChoice,
 
     /// If true, _(default: false)_, a timestamp will be shown before each message.
     pub timestamp: bool,
 
+    /// The timezone in which to display the timestamp of messages, if enabled (default: [`Timezone::default()`]).
+    ///
+    /// Set it to [`Timezone::from_env()`] to let users choose it with the `PRODASH_TIMEZONE` environment variable.
+    pub timezone: Timezone,
+
+    /// The way the timestamp of messages is shown, if enabled, and the time at which tasks last made progress
+    /// (default: [`TimestampFormat::Time`]).
+    pub timestamp_format: TimestampFormat,
+
+    /// If set (default: `None`), only messages at least as severe as the given level are shown,
+    /// as determined by [`MessageLevel::severity()`].
+    pub message_level: Option<MessageLevel>,
+
+    /// If set (default: `None`), at most the given amount of the newest messages that arrived since the last frame are
+    /// written with each frame, preceded by a line with the amount of older ones that were left out.
+    ///
+    /// This keeps bursts of thousands of messages from holding up the display of progress. The `message_log` still
+    /// receives all messages.
+    pub max_messages_per_frame: Option<usize>,
+
+    /// If set (default: `None`), all messages are written to a file at the given path as they arrive, one per line,
+    /// independently of `message_level`, to keep the full history of messages for later inspection.
+    /// The file is complete once the renderer stops.
+    ///
+    /// Note that messages sent faster than frames are drawn may be overwritten in the ring buffer before they are seen.
+    pub message_log: Option<PathBuf>,
+
     /// The amount of columns and rows to use for drawing. Defaults to (80, 20).
+    ///
+    /// If the output stream is a terminal, lines wider than the amount of columns are truncated with an ellipsis
+    /// instead of wrapping, which would break redrawing them in place. Use [`auto_configure()`](Options::auto_configure())
+    /// to query the size of the terminal. If the output stream is a terminal, its size is updated when it's resized,
+    /// which is detected with the `SIGWINCH` signal if the `signal-hook` feature is enabled on Unix, and by querying
+    /// the size with each frame otherwise.
     pub terminal_dimensions: (u16, u16),
 
     /// If true, _(default: false)_, the cursor will be hidden for a more visually appealing display.
@@ -38,16 +85,58 @@ pub struct Options {
 
     /// If true, (default false), we will keep track of the previous progress state to derive
     /// continuous throughput information from. Throughput will only show for units which have
-    /// explicitly enabled it, it is opt-in.
+    /// explicitly enabled it, it is opt-in, like with [`unit::display::Mode::with_throughput()`](crate::unit::display::Mode::with_throughput()).
+    /// It is shown as amount per second next to the progress of each task, as in `14.2 MB/s`.
     ///
     /// This comes at the cost of additional memory and CPU time.
     pub throughput: bool,
 
+    /// If true (default: false), show whether the throughput of tasks is increasing (`▲`), decreasing (`▼`) or
+    /// steady (`▬`) next to it.
+    ///
+    /// Has no effect unless `throughput` is enabled.
+    pub show_trend: bool,
+
+    /// If true (default: false), show the estimated time until each bounded task is done next to its progress,
+    /// as in `~1m 5s left`.
+    ///
+    /// See [`Throughput::eta()`](crate::Throughput::eta()) for how it is computed.
+    /// Has no effect unless `throughput` is enabled.
+    pub show_eta: bool,
+
+    /// If true (default: false), add a line below all tasks showing the sum of their throughput.
+    ///
+    /// It's formatted with the unit of the first task showing throughput, so it's most useful if all tasks count
+    /// the same unit, like bytes. Has no effect unless `throughput` is enabled.
+    pub show_total_throughput: bool,
+
+    /// If true (default: false), show how long each task has been running in a column, as in `2m 13s`.
+    ///
+    /// It's drawn as [`Elapsed`] column before all user-defined `columns`.
+    pub show_elapsed: bool,
+
+    /// If true (default: false), show the time at which each task last made progress at the end of its line.
+    ///
+    /// The time is formatted like message timestamps, with the configured `timestamp_format` and `timezone`.
+    pub show_last_update: bool,
+
+    /// If true (default: false), the names of the tasks on the [critical path][crate::progress::critical_path()] are
+    /// underlined, to show which tasks hold up completion the most. It's updated whenever the tree changes other than
+    /// by steps. Without colors, this has no effect.
+    pub highlight_critical_path: bool,
+
     /// If set, specify all levels that should be shown. Otherwise all available levels are shown.
     ///
     /// This is useful to filter out high-noise lower level progress items in the tree.
     pub level_filter: Option<RangeInclusive<progress::key::Level>>,
 
+    /// How tasks that are done are shown (default: [`FinishedTasks::Show`]).
+    pub finished_tasks: FinishedTasks,
+
+    /// If set (default: `None`), tasks are shown in the order of their names in this list, instead of the order in
+    /// which they were added. Tasks not in the list follow in their natural order, and each task remains below its parent.
+    pub explicit_order: Option<Vec<String>>,
+
     /// If set, progress will only actually be shown after the given duration. Log messages will always be shown without delay.
     ///
     /// This option can be useful to not enforce progress for short actions, causing it to flicker.
@@ -64,9 +153,106 @@ pub struct Options {
     /// Please note that you should add at least one item to the `prodash::Tree` before launching the application or else
     /// risk a race causing nothing to be rendered at all.
     pub keep_running_if_progress_is_empty: bool,
+
+    /// If true (default: false), only ASCII characters are written, replacing Braille patterns of bars and other
+    /// symbols with characters like `#`, `-` and `|`.
+    ///
+    /// Useful for terminals lacking the glyphs, and for capturing the output with tools that mangle them.
+    pub ascii: bool,
+
+    /// If not empty (default: empty), running progress bars are colored by their depth in the tree, cycling through
+    /// the given colors. The first color is used for top-level tasks.
+    ///
+    /// Has no effect if color is disabled, see `colored`.
+    pub depth_palette: Vec<Color>,
+
+    /// User-defined columns to draw at the end of each line, in order, after all built-in ones (default: none).
+    pub columns: Vec<Arc<dyn Column + Send + Sync>>,
+
+    /// Configure how progress bars are drawn.
+    pub bar_style: BarStyle,
+
+    /// If set (default: `None`), the fraction of completion of each task is passed through this function to determine
+    /// how much of its bar to fill, while the percentage shown remains the true fraction.
+    ///
+    /// Use it for work that doesn't progress linearly. The function must be monotonic and map `[0, 1]` onto `[0, 1]`.
+    pub fill_mapping: Option<fn(f32) -> f32>,
+
+    /// The way task names are shortened if they take more than half of the line (default: [`NameAbbrev::None`]).
+    ///
+    /// Names of groups may take the whole line before they are shortened.
+    pub name_abbrev: NameAbbrev,
+
+    /// If set (default: `None`), changes to progress that are small are drawn less often, with the time between
+    /// redraws growing while changes remain small and shrinking back to one frame once a significant change happens.
+    ///
+    /// This reduces CPU time spent on drawing when many tasks make tiny progress all the time.
+    pub adaptive_redraw: Option<AdaptiveRedraw>,
+
+    /// The way progress is laid out (default: [`Layout::Tree`]).
+    pub layout: Layout,
+}
+
+/// The way progress is laid out by the line renderer, see [`Options::layout`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Layout {
+    /// Draw one line for each task, in the hierarchy they were added in.
+    #[default]
+    Tree,
+    /// Draw a single line summarizing all tasks in the style of `pv`, as in
+    /// `1.2GB 0:00:45 [27.3MB/s] [=====>    ] 42% ETA 0:01:02`.
+    ///
+    /// The steps of all tasks are counted as bytes, while the bar, the percentage and the ETA are derived from bounded tasks.
+    /// The rate is the [throughput](Options::throughput) of all tasks together, measured even if not enabled.
+    #[cfg(feature = "unit-bytes")]
+    PvStyle,
+}
+
+/// Whether to use color, see [`Options::colored`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Use color if the output stream is a terminal and the environment doesn't say otherwise, see
+    /// [`ColorChoice::enabled()`].
+    Auto,
+    /// Always use color.
+    #[default]
+    Always,
+    /// Never use color.
+    Never,
+}
+
+impl ColorChoice {
+    /// Return true if color should be used when writing to a stream that is a terminal if `output_is_terminal` is true.
+    ///
+    /// With [`ColorChoice::Auto`], the following environment variables are honored, in order:
+    ///
+    /// * `CLICOLOR_FORCE` set to anything but `0` enables color, even if the output isn't a terminal.
+    /// * `NO_COLOR` set to anything but the empty string disables color.
+    /// * `TERM` set to `dumb` disables color.
+    /// * `CLICOLOR` set to `0` disables color.
+    pub fn enabled(self, output_is_terminal: bool) -> bool {
+        let var = |name: &str| std::env::var_os(name);
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    return true;
+                }
+                if var("NO_COLOR").is_some_and(|value| !value.is_empty())
+                    || var("TERM").is_some_and(|value| value == "dumb")
+                    || var("CLICOLOR").is_some_and(|value| value == "0")
+                {
+                    return false;
+                }
+                output_is_terminal
+            }
+        }
+    }
 }
 
 /// The kind of stream to use for auto-configuration.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum StreamKind {
     /// Standard output
     Stdout,
@@ -79,7 +265,7 @@ impl Options {
     /// Automatically configure (and overwrite) the following fields based on terminal configuration.
     ///
     /// * output_is_terminal
-    /// * colored
+    /// * colored, which is set to [`ColorChoice::Auto`]
     /// * terminal_dimensions
     /// * hide-cursor (based on presence of 'signal-hook' feature.
     #[cfg(feature = "render-line-autoconfigure")]
@@ -88,7 +274,7 @@ impl Options {
             StreamKind::Stdout => is_terminal::is_terminal(std::io::stdout()),
             StreamKind::Stderr => is_terminal::is_terminal(std::io::stderr()),
         };
-        self.colored = self.output_is_terminal && crosstermion::color::allowed();
+        self.colored = ColorChoice::Auto;
         self.terminal_dimensions = crosstermion::terminal::size().unwrap_or((80, 20));
         #[cfg(feature = "signal-hook")]
         self.auto_hide_cursor();
@@ -109,16 +295,189 @@ impl Default for Options {
     fn default() -> Self {
         Options {
             output_is_terminal: true,
-            colored: true,
+            colored: ColorChoice::Always,
             timestamp: false,
+            timezone: Timezone::default(),
+            timestamp_format: TimestampFormat::default(),
+            message_level: None,
+            max_messages_per_frame: None,
+            message_log: None,
             terminal_dimensions: (80, 20),
             hide_cursor: false,
             level_filter: None,
+            explicit_order: None,
+            finished_tasks: FinishedTasks::default(),
             initial_delay: None,
             frames_per_second: 6.0,
             throughput: false,
+            show_trend: false,
+            show_eta: false,
+            show_total_throughput: false,
+            show_elapsed: false,
+            show_last_update: false,
+            highlight_critical_path: false,
             keep_running_if_progress_is_empty: true,
+            ascii: false,
+            depth_palette: Vec::new(),
+            columns: Vec::new(),
+            bar_style: BarStyle::default(),
+            fill_mapping: None,
+            name_abbrev: NameAbbrev::None,
+            adaptive_redraw: None,
+            layout: Layout::default(),
+        }
+    }
+}
+
+impl Options {
+    /// Create options bundled as `profile`:
+    ///
+    /// * [`Profile::Ci`] draws once per second without colors, redrawing small changes less often, and timestamps messages.
+    /// * [`Profile::Minimal`] shows top-level tasks only.
+    /// * [`Profile::Detailed`] shows throughput, its trend and the time left, how long tasks are running,
+    ///   when they last made progress, and percentages on bars.
+    pub fn from_profile(profile: Profile) -> Self {
+        let default = Options::default();
+        match profile {
+            Profile::Default => default,
+            Profile::Ci => Options {
+                colored: ColorChoice::Never,
+                timestamp: true,
+                frames_per_second: 1.0,
+                adaptive_redraw: Some(AdaptiveRedraw::default()),
+                ..default
+            },
+            Profile::Minimal => Options {
+                level_filter: Some(RangeInclusive::new(1, 1)),
+                ..default
+            },
+            Profile::Detailed => Options {
+                timestamp: true,
+                throughput: true,
+                show_trend: true,
+                show_eta: true,
+                show_elapsed: true,
+                show_last_update: true,
+                bar_style: BarStyle {
+                    label_overlay: true,
+                    adaptive_precision: true,
+                    ..BarStyle::default()
+                },
+                ..default
+            },
+        }
+    }
+
+    /// Create options bundled as the profile named in the environment, see [`Profile::from_env()`].
+    pub fn from_env_profile() -> Self {
+        Options::from_profile(Profile::from_env())
+    }
+}
+
+/// The parts of [`Options`] which only matter to the render loop, as opposed to drawing a single frame.
+struct LoopOptions {
+    initial_delay: Option<Duration>,
+    frames_per_second: f32,
+    message_log: Option<PathBuf>,
+    throughput: bool,
+    show_last_update: bool,
+    highlight_critical_path: bool,
+    adaptive_redraw: Option<AdaptiveRedraw>,
+}
+
+impl Options {
+    /// Separate the options for drawing frames from those for the render loop.
+    fn split(self) -> (draw::Options, LoopOptions) {
+        let Options {
+            output_is_terminal,
+            colored,
+            timestamp,
+            timezone,
+            timestamp_format,
+            message_level,
+            max_messages_per_frame,
+            message_log,
+            level_filter,
+            explicit_order,
+            finished_tasks,
+            terminal_dimensions,
+            initial_delay,
+            frames_per_second,
+            keep_running_if_progress_is_empty,
+            hide_cursor,
+            throughput,
+            show_trend,
+            show_eta,
+            show_total_throughput,
+            show_elapsed,
+            show_last_update,
+            highlight_critical_path,
+            ascii,
+            depth_palette,
+            mut columns,
+            bar_style,
+            fill_mapping,
+            name_abbrev,
+            adaptive_redraw,
+            layout,
+        } = self;
+        if show_elapsed {
+            columns.insert(0, Arc::new(Elapsed));
         }
+        let config = draw::Options {
+            level_filter,
+            explicit_order,
+            terminal_dimensions,
+            keep_running_if_progress_is_empty,
+            output_is_terminal,
+            colored: colored.enabled(output_is_terminal),
+            timestamp,
+            timezone,
+            timestamp_format,
+            message_level,
+            max_messages_per_frame,
+            hide_cursor,
+            depth_palette,
+            columns,
+            bar_style,
+            fill_mapping,
+            name_abbrev,
+            show_trend,
+            show_eta,
+            show_total_throughput,
+            ascii,
+            finished_tasks,
+            layout,
+        };
+        (
+            config,
+            LoopOptions {
+                initial_delay,
+                frames_per_second,
+                message_log,
+                throughput,
+                show_last_update,
+                highlight_critical_path,
+                adaptive_redraw,
+            },
+        )
+    }
+}
+
+impl LoopOptions {
+    /// Create the state to draw frames with, keeping track of what's needed for the information to show.
+    fn draw_state(&self) -> draw::State {
+        let mut state = draw::State::default();
+        if self.throughput {
+            state.throughput = Some(Throughput::default());
+        }
+        if self.show_last_update {
+            state.last_update = Some(LastUpdate::default());
+        }
+        if self.highlight_critical_path {
+            state.critical_path = Some(Vec::new());
+        }
+        state
     }
 }
 
@@ -158,10 +517,25 @@ impl JoinHandle {
         }
     }
     /// Send the signal to shutdown and wait for the thread to be shutdown.
+    ///
+    /// One last frame is drawn, and the cursor is shown again if it was hidden, even if there is no more progress
+    /// to display.
     pub fn shutdown_and_wait(mut self) {
         self.shutdown();
         self.wait();
     }
+    /// Like [`shutdown_and_wait()`][JoinHandle::shutdown_and_wait()], but return the error that stopped the render
+    /// thread early, if writing failed.
+    pub fn shutdown_and_join(mut self) -> io::Result<()> {
+        self.shutdown();
+        self.inner
+            .take()
+            .map(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err(io::Error::other("render thread panicked")))
+            })
+            .unwrap_or(Ok(()))
+    }
 }
 
 impl Drop for JoinHandle {
@@ -175,7 +549,6 @@ impl Drop for JoinHandle {
 enum Event {
     Tick,
     Quit,
-    #[cfg(feature = "signal-hook")]
     Resize(u16, u16),
 }
 
@@ -185,30 +558,18 @@ enum Event {
 pub fn render(
     mut out: impl io::Write + Send + 'static,
     progress: impl WeakRoot + Send + 'static,
-    Options {
-        output_is_terminal,
-        colored,
-        timestamp,
-        level_filter,
-        terminal_dimensions,
+    options: Options,
+) -> JoinHandle {
+    let (mut config, loop_options) = options.split();
+    let mut state = loop_options.draw_state();
+    let LoopOptions {
         initial_delay,
         frames_per_second,
-        keep_running_if_progress_is_empty,
-        hide_cursor,
-        throughput,
-    }: Options,
-) -> JoinHandle {
-    #[cfg_attr(not(feature = "signal-hook"), allow(unused_mut))]
-    let mut config = draw::Options {
-        level_filter,
-        terminal_dimensions,
-        keep_running_if_progress_is_empty,
-        output_is_terminal,
-        colored,
-        timestamp,
-        hide_cursor,
-    };
-
+        message_log,
+        adaptive_redraw,
+        ..
+    } = loop_options;
+    let (output_is_terminal, hide_cursor, timezone) = (config.output_is_terminal, config.hide_cursor, config.timezone);
     let (event_send, event_recv) = std::sync::mpsc::sync_channel::<Event>(1);
     let show_cursor = possibly_hide_cursor(&mut out, hide_cursor && output_is_terminal);
     static SHOW_PROGRESS: AtomicBool = AtomicBool::new(false);
@@ -245,14 +606,18 @@ pub fn render(
                     }
                 }
 
-                let mut state = draw::State::default();
-                if throughput {
-                    state.throughput = Some(Throughput::default());
+                if let Some(path) = message_log {
+                    state.message_log = Some(MessageLog::create(&path, timezone)?);
                 }
                 let secs = 1.0 / frames_per_second;
+                if let Some(adaptive_redraw) = adaptive_redraw {
+                    state.pacer = Some(Pacer::new(adaptive_redraw, Duration::from_secs_f32(secs)));
+                }
                 let _ticker = std::thread::Builder::new()
                     .name("render-line-ticker".into())
                     .spawn(move || {
+                        #[cfg(not(all(unix, feature = "signal-hook")))]
+                        let mut last_size = None;
                         loop {
                             #[cfg(feature = "signal-hook")]
                             {
@@ -267,6 +632,14 @@ pub fn render(
                                     }
                                 }
                             }
+                            #[cfg(not(all(unix, feature = "signal-hook")))]
+                            if output_is_terminal {
+                                if let Ok(size) = crosstermion::terminal::size() {
+                                    if last_size.replace(size).is_some_and(|last_size| last_size != size) {
+                                        tick_send.send(Event::Resize(size.0, size.1)).ok();
+                                    }
+                                }
+                            }
                             if tick_send.send(Event::Tick).is_err() {
                                 break;
                             }
@@ -275,37 +648,51 @@ pub fn render(
                     })
                     .expect("starting a thread works");
 
+                let mut result = Ok(());
                 for event in event_recv {
-                    match event {
-                        #[cfg(feature = "signal-hook")]
+                    let show_progress = SHOW_PROGRESS.load(Ordering::Relaxed);
+                    let stop;
+                    (result, stop) = match event {
                         Event::Resize(x, y) => {
+                            let cleared = if output_is_terminal {
+                                draw::clear_progress(&mut out, &mut state)
+                            } else {
+                                Ok(())
+                            };
                             config.terminal_dimensions = (x, y);
-                            draw::all(&mut out, SHOW_PROGRESS.load(Ordering::Relaxed), &mut state, &config)?;
+                            (
+                                cleared.and_then(|()| draw::all(&mut out, show_progress, &mut state, &config)),
+                                false,
+                            )
                         }
                         Event::Tick => match progress.upgrade() {
                             Some(progress) => {
                                 let has_changed = state.update_from_progress(&progress);
-                                draw::all(
-                                    &mut out,
-                                    SHOW_PROGRESS.load(Ordering::Relaxed) && has_changed,
-                                    &mut state,
-                                    &config,
-                                )?;
+                                (
+                                    draw::all(&mut out, show_progress && has_changed, &mut state, &config),
+                                    !config.keep_running_if_progress_is_empty && state.is_empty(),
+                                )
                             }
                             None => {
                                 state.clear();
-                                draw::all(&mut out, SHOW_PROGRESS.load(Ordering::Relaxed), &mut state, &config)?;
-                                break;
+                                (draw::all(&mut out, show_progress, &mut state, &config), true)
                             }
                         },
                         Event::Quit => {
+                            if let Some(progress) = progress.upgrade() {
+                                // Messages sent since the last tick wouldn't be logged otherwise.
+                                state.log_new_messages(&progress).ok();
+                            }
                             state.clear();
-                            draw::all(&mut out, SHOW_PROGRESS.load(Ordering::Relaxed), &mut state, &config)?;
-                            break;
+                            (draw::all(&mut out, show_progress, &mut state, &config), true)
                         }
+                    };
+                    if stop || result.is_err() {
+                        break;
                     }
                 }
 
+                // Restore the terminal even if drawing failed, as it's unusable otherwise.
                 if show_cursor {
                     crosstermion::execute!(out, crosstermion::cursor::Show).ok();
                 }
@@ -313,7 +700,8 @@ pub fn render(
                 // One day we might try this out on windows, but let's not risk it now.
                 #[cfg(unix)]
                 write!(out, "\x1b[2K\r").ok(); // clear the last line.
-                Ok(())
+                out.flush().ok();
+                result
             }
         })
         .expect("starting a thread works");
@@ -325,6 +713,54 @@ pub fn render(
     }
 }
 
+/// Draw a single frame of `progress` as configured by `options` without any terminal, and return it as plain text
+/// without colors, cursor movements or trailing whitespace.
+///
+/// Progress is drawn as if the output was a terminal of `options.terminal_dimensions`, and options only affecting
+/// the render loop, like `frames_per_second` or `message_log`, have no effect.
+/// This is useful for snapshot tests.
+pub fn render_to_string(progress: &impl Root, options: Options) -> String {
+    let (config, loop_options) = Options {
+        output_is_terminal: true,
+        colored: ColorChoice::Never,
+        ..options
+    }
+    .split();
+    let mut state = loop_options.draw_state();
+    state.update_from_progress(progress);
+    let mut out = Vec::new();
+    draw::all(&mut out, true, &mut state, &config).expect("writing to memory works");
+    let mut text = String::new();
+    for line in strip_escape_sequences(&String::from_utf8_lossy(&out)).lines() {
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Remove all control sequences like `ESC [ 1 A` from `text`.
+fn strip_escape_sequences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if chars.next() == Some('[') {
+                    // Parameters and intermediate bytes are followed by a single final byte.
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 // Not all configurations actually need it to be mut, but those with the 'signal-hook' feature do
 #[allow(unused_mut)]
 fn possibly_hide_cursor(out: &mut impl io::Write, mut hide_cursor: bool) -> bool {
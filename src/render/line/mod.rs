@@ -1,7 +1,14 @@
 #[cfg(all(feature = "render-line", not(any(feature = "render-line-crossterm"))))]
 compile_error!("Please use the 'render-line-crossterm' feature");
 
+mod builder;
 mod draw;
 mod engine;
+#[cfg(feature = "unit-bytes")]
+mod pv;
+mod redraw;
 
-pub use engine::{JoinHandle, Options, StreamKind, render};
+pub use crate::render::{Column, Elapsed};
+pub use builder::{Builder, LineRenderer};
+pub use engine::{ColorChoice, JoinHandle, Layout, Options, StreamKind, render, render_to_string};
+pub use redraw::AdaptiveRedraw;
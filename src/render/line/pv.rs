@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    progress::{Key, Step, Task},
+    unit::{self, DisplayValue},
+};
+
+/// Return a line no wider than `width` summarizing all tasks in `tree` like `pv` does, as in
+/// `1.2GB 0:00:45 [27.3MB/s] [=====>    ] 42% ETA 0:01:02`, after `elapsed` time.
+///
+/// The `rate` is the amount of bytes per second made lately, and until it's known the average since the start is shown.
+/// All steps are counted as bytes, and the bar, percentage and ETA are derived from bounded tasks only.
+/// Without any bounded task, an indicator moves back and forth in the bar instead.
+/// [Aggregated](crate::progress::Value::aggregated) progress is ignored so steps are only counted once.
+pub(crate) fn line(tree: &[(Key, Task)], elapsed: Duration, rate: Option<Step>, width: u16) -> String {
+    let (bytes, done, total) = tree
+        .iter()
+        .filter_map(|(_key, task)| task.progress.as_ref().filter(|progress| !progress.aggregated))
+        .fold((0, 0, 0), |(bytes, done, total), progress| {
+            let step = progress.steps_done();
+            match progress.done_at {
+                Some(done_at) => (bytes + step, done + step.min(done_at), total + done_at),
+                None => (bytes + step, done, total),
+            }
+        });
+    let fraction = (total > 0).then(|| done as f64 / total as f64);
+    let seconds = elapsed.as_secs_f64();
+    let rate = rate.unwrap_or_else(|| {
+        if seconds > 0.0 {
+            (bytes as f64 / seconds) as Step
+        } else {
+            0
+        }
+    });
+
+    let left = format!(
+        "{} {} [{}/s] [",
+        format_bytes(bytes),
+        format_duration(elapsed.as_secs()),
+        format_bytes(rate)
+    );
+    let right = match fraction {
+        Some(fraction) => {
+            let eta = (fraction > 0.0).then(|| format_duration((seconds * (1.0 - fraction) / fraction) as u64));
+            format!(
+                "] {:.0}%{}",
+                fraction * 100.0,
+                eta.map(|eta| format!(" ETA {eta}")).unwrap_or_default()
+            )
+        }
+        None => "]".into(),
+    };
+    let bar_width = (width as usize).saturating_sub(left.width() + right.width());
+    let bar = match fraction {
+        Some(fraction) => {
+            let filled = ((bar_width as f64 * fraction).round() as usize).min(bar_width);
+            match filled {
+                0 => " ".repeat(bar_width),
+                _ => format!("{}>{}", "=".repeat(filled - 1), " ".repeat(bar_width - filled)),
+            }
+        }
+        None => {
+            const INDICATOR: &str = "<=>";
+            let travel = bar_width.saturating_sub(INDICATOR.len());
+            let position = match travel {
+                0 => 0,
+                _ => {
+                    let step = elapsed.as_secs() as usize % (travel * 2);
+                    if step > travel { travel * 2 - step } else { step }
+                }
+            };
+            let mut bar = " ".repeat(bar_width);
+            if bar_width >= INDICATOR.len() {
+                bar.replace_range(position..position + INDICATOR.len(), INDICATOR);
+            }
+            bar
+        }
+    };
+    super::draw::truncate_to_width(format!("{left}{bar}{right}"), width)
+}
+
+fn format_bytes(bytes: Step) -> String {
+    let mut out = String::new();
+    unit::Bytes
+        .display_current_value(&mut out, bytes, None)
+        .expect("in-memory writes never fail");
+    out
+}
+
+/// Format `seconds` as `H:MM:SS`.
+fn format_duration(seconds: u64) -> String {
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
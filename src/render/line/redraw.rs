@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use crate::progress::{Key, Task};
+
+/// Configure how the line renderer reduces redraws while progress changes only a little, see
+/// [`Options::adaptive_redraw`][crate::render::line::Options::adaptive_redraw].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AdaptiveRedraw {
+    /// The change in average completion of all bounded tasks, between `0.0` and `1.0`, at which a change is considered
+    /// significant and redrawn right away (default: `0.01`).
+    ///
+    /// Adding or removing tasks is always significant.
+    pub significant_change: f32,
+    /// The longest time to wait before drawing changes that are not significant (default: 1 second).
+    pub max_interval: Duration,
+}
+
+impl Default for AdaptiveRedraw {
+    fn default() -> Self {
+        AdaptiveRedraw {
+            significant_change: 0.01,
+            max_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+struct Drawn {
+    at: Instant,
+    num_tasks: usize,
+    completion: f32,
+}
+
+/// Decide whether a change should be drawn, doubling the time between redraws for each insignificant change
+/// and resetting it with each significant one.
+pub(crate) struct Pacer {
+    config: AdaptiveRedraw,
+    min_interval: Duration,
+    interval: Duration,
+    last_drawn: Option<Drawn>,
+}
+
+impl Pacer {
+    pub fn new(config: AdaptiveRedraw, min_interval: Duration) -> Self {
+        Pacer {
+            config,
+            min_interval,
+            interval: min_interval,
+            last_drawn: None,
+        }
+    }
+
+    /// Return true if the changed `tree` should be drawn now.
+    pub fn should_draw(&mut self, tree: &[(Key, Task)]) -> bool {
+        let now = Instant::now();
+        let num_tasks = tree.len();
+        let completion = average_completion(tree);
+        let draw = match self.last_drawn.as_ref() {
+            None => true,
+            Some(last) => {
+                if last.num_tasks != num_tasks || (completion - last.completion).abs() >= self.config.significant_change
+                {
+                    self.interval = self.min_interval;
+                    true
+                } else if now.saturating_duration_since(last.at) >= self.interval {
+                    self.interval = (self.interval * 2).min(self.config.max_interval.max(self.min_interval));
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        if draw {
+            self.last_drawn = Some(Drawn {
+                at: now,
+                num_tasks,
+                completion,
+            });
+        }
+        draw
+    }
+}
+
+fn average_completion(tree: &[(Key, Task)]) -> f32 {
+    let (sum, count) = tree
+        .iter()
+        .filter_map(|(_key, task)| task.progress.as_ref().and_then(|p| p.fraction()))
+        .fold((0f32, 0usize), |(sum, count), fraction| {
+            (sum + fraction.min(1.0), count + 1)
+        });
+    if count == 0 { 0.0 } else { sum / count as f32 }
+}
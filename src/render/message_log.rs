@@ -0,0 +1,69 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    messages::{Message, MessageLevel},
+    time::Timezone,
+};
+
+/// Writes every message a renderer sees to a file, one per line, to keep them after they left the ring buffer.
+pub(crate) struct MessageLog {
+    out: BufWriter<File>,
+    timezone: Timezone,
+}
+
+impl MessageLog {
+    pub fn create(path: &Path, timezone: Timezone) -> io::Result<Self> {
+        Ok(MessageLog {
+            out: BufWriter::new(File::create(path)?),
+            timezone,
+        })
+    }
+
+    /// Write all `messages` as lines formatted with [`format_message()`].
+    pub fn append(&mut self, messages: &[Message]) -> io::Result<()> {
+        for message in messages {
+            writeln!(self.out, "{}", format_message(message, self.timezone))?;
+        }
+        Ok(())
+    }
+
+    /// Write all buffered messages to the file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Return `message` on a single line like `2024-05-01 12:03:04 warning origin → message key=value`, with its time
+/// in `timezone`.
+pub(crate) fn format_message(
+    Message {
+        time,
+        level,
+        origin,
+        message,
+        fields,
+    }: &Message,
+    timezone: Timezone,
+) -> String {
+    let level = match level {
+        MessageLevel::Info => "info",
+        MessageLevel::Failure => "failure",
+        MessageLevel::Success => "success",
+        MessageLevel::Warning => "warning",
+        MessageLevel::Debug => "debug",
+        MessageLevel::Trace => "trace",
+    };
+    let mut line = format!(
+        "{} {level:<7} {origin} → {message}",
+        timezone.format_datetime_seconds(*time)
+    );
+    for (name, value) in fields {
+        write!(line, " {name}={value}").ok();
+    }
+    line
+}
@@ -1,3 +1,80 @@
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod abbrev;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub use abbrev::NameAbbrev;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub(crate) mod canvas;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod column;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub(crate) mod finished;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub use column::{Column, Elapsed};
+/// Useful for implementing a [`Column`] without bringing in `nu_ansi_term` yourself
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub use crosstermion::nu_ansi_term;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub(crate) mod last_update;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub(crate) mod message_log;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub(crate) mod order;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod profile;
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+pub use profile::{PROFILE_ENV_VAR, Profile};
+
+/// Configure how progress bars are drawn by the renderers.
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct BarStyle {
+    /// If true (default: false), the percentage of completion is drawn centered on top of bounded progress bars.
+    ///
+    /// If the bar is too narrow to hold it, it's shown next to the bar instead.
+    pub label_overlay: bool,
+    /// If true (default: false), percentages drawn on or next to bars get as many decimal places as are informative,
+    /// instead of none:
+    ///
+    /// * between 10% and 90%, and for tasks with at most 100 steps, there are none,
+    /// * below 10% and above 90%, there is one, or two for tasks with at least 10,000 steps.
+    ///
+    /// Percentages are rounded down, so 100% is only shown once all is done.
+    pub adaptive_precision: bool,
+    /// The symbols to draw bars with.
+    pub glyphs: BarGlyphs,
+}
+
+/// How the renderers show tasks that are done.
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum FinishedTasks {
+    /// Show finished tasks like all others.
+    #[default]
+    Show,
+    /// Don't show finished tasks at all.
+    Hide,
+    /// Don't show finished tasks, but add a single row like `12 tasks finished` after all others.
+    Summarize,
+}
+
+/// The symbols to draw progress bars with, see [`BarStyle::glyphs`].
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum BarGlyphs {
+    /// The symbols native to each renderer, block elements for the terminal user interface and ASCII characters
+    /// for the line renderer.
+    #[default]
+    Native,
+    /// Block elements like `▏▎▍▌`, which resolve eight levels of fill per cell for smooth bars.
+    ///
+    /// These are the native symbols of the terminal user interface, and need a font supporting them in the line renderer.
+    Blocks,
+    /// Braille patterns, which resolve eight levels of fill per cell for smoother bars where space is scarce.
+    ///
+    /// A bar of 10 cells can thus show 80 distinct levels of fill.
+    Braille,
+}
+
 #[cfg(feature = "render-tui")]
 ///
 pub mod tui;
@@ -9,3 +86,21 @@ pub use self::tui::render as tui;
 pub mod line;
 #[cfg(feature = "render-line")]
 pub use self::line::render as line;
+
+#[cfg(feature = "render-sse")]
+///
+pub mod sse;
+
+#[cfg(feature = "render-jsonl")]
+///
+pub mod jsonl;
+
+#[cfg(feature = "render-remote")]
+///
+pub mod remote;
+
+#[cfg(feature = "render-history")]
+///
+pub mod history;
+#[cfg(all(feature = "render-line", not(any(feature = "render-line-crossterm"))))]
+compile_error!("Please use the 'render-line-crossterm' feature");
@@ -0,0 +1,32 @@
+use crate::progress::{Key, Task};
+
+/// Return a copy of `sorted` with siblings ordered by the position of their name in `names`, followed by all siblings
+/// whose name isn't listed in their natural order.
+///
+/// Each task remains placed below its parent, along with all of its children.
+pub fn by_names(sorted: &[(Key, Task)], names: &[String]) -> Vec<(Key, Task)> {
+    by_rank(sorted, |_key, task| {
+        names.iter().position(|name| *name == task.name).unwrap_or(names.len())
+    })
+}
+
+/// Return a copy of `sorted` with siblings ordered by the `rank` of each task, keeping the natural order of siblings
+/// with equal rank.
+///
+/// Each task remains placed below its parent, along with all of its children.
+pub fn by_rank<R: Ord + Clone>(sorted: &[(Key, Task)], rank: impl Fn(&Key, &Task) -> R) -> Vec<(Key, Task)> {
+    let mut ancestors: Vec<(Key, (R, usize))> = Vec::new();
+    let mut paths: Vec<(Vec<(R, usize)>, usize)> = Vec::with_capacity(sorted.len());
+    for (index, (key, task)) in sorted.iter().enumerate() {
+        while ancestors
+            .last()
+            .is_some_and(|(ancestor, _)| ancestor.level() >= key.level())
+        {
+            ancestors.pop();
+        }
+        ancestors.push((*key, (rank(key, task), index)));
+        paths.push((ancestors.iter().map(|(_, position)| position.clone()).collect(), index));
+    }
+    paths.sort();
+    paths.into_iter().map(|(_, index)| sorted[index].clone()).collect()
+}
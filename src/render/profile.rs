@@ -0,0 +1,41 @@
+/// The environment variable naming the [`Profile`] to use, as in `PRODASH_PROFILE=ci`.
+pub const PROFILE_ENV_VAR: &str = "PRODASH_PROFILE";
+
+/// A named bundle of renderer options, to give all tools using them a consistent look that can be switched at once.
+///
+/// Use it as base for the options of a renderer, like `Options { title: "…".into(), ..Options::from_env_profile() }`,
+/// so options set in code override those of the profile.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Profile {
+    /// The default options of each renderer.
+    #[default]
+    Default,
+    /// Options suitable for continuous integration, with few updates, no colors and no cursor movement beyond what's needed.
+    Ci,
+    /// Options showing as little as possible besides the progress itself.
+    Minimal,
+    /// Options showing everything that is available, like throughput and when tasks last made progress.
+    Detailed,
+}
+
+impl Profile {
+    /// Return the profile with the given `name`, ignoring case, or `None` if there is no such profile.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "default" => Profile::Default,
+            "ci" => Profile::Ci,
+            "minimal" => Profile::Minimal,
+            "detailed" => Profile::Detailed,
+            _ => return None,
+        })
+    }
+
+    /// Return the profile named by the [`PRODASH_PROFILE`](PROFILE_ENV_VAR) environment variable,
+    /// or the default profile if it's unset or names no known profile.
+    pub fn from_env() -> Self {
+        std::env::var(PROFILE_ENV_VAR)
+            .ok()
+            .and_then(|name| Profile::from_name(&name))
+            .unwrap_or_default()
+    }
+}
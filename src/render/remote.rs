@@ -0,0 +1,266 @@
+/*!
+* A module to render progress trees of other processes, by streaming them over the network.
+*
+* **Please note** that it is behind the `render-remote` feature toggle.
+*
+* The producing process [serves][serve()] its progress tree as [JSON lines][crate::render::jsonl], and the viewing
+* process [connects][connect()] to it to obtain a [`Mirror`] of it, which can be rendered like any other tree.
+*
+* ```no_run
+* // in the producing process
+* let root = prodash::tree::Root::new();
+* let listener = std::net::TcpListener::bind("0.0.0.0:7878")?;
+* std::thread::spawn({
+*     let progress = std::sync::Arc::downgrade(&root);
+*     move || prodash::render::remote::serve(listener, progress, Default::default())
+* });
+*
+* // in the viewing process, pass `mirror.downgrade()` to any renderer
+* use prodash::Root;
+* let mirror = prodash::render::remote::connect("server:7878")?;
+* let progress = mirror.downgrade();
+* # Ok::<_, std::io::Error>(())
+* ```
+*/
+use std::{
+    collections::HashSet,
+    io::{self, BufRead},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Weak, atomic::AtomicBool},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    messages::{Message, MessageCopyState, MessageRingBuffer},
+    progress::{Key, State, Step, Task, Value},
+    render::jsonl::{self, Line},
+    tree::snapshot,
+    unit::{self, DisplayValue},
+};
+
+/// The amount of messages a [`Mirror`] can hold before overwriting old ones.
+const MESSAGES_CAPACITY: usize = 100;
+/// The amount of distinct reasons for which tasks are blocked or halted that are kept, see [`intern()`].
+const REASONS_CAPACITY: usize = 256;
+
+/// Stream the progress tree `progress` to each client connecting to `listener`, blocking until accepting
+/// connections fails.
+///
+/// Each client is served on its own thread until it disconnects or `progress` is dropped.
+pub fn serve(
+    listener: TcpListener,
+    progress: impl crate::WeakRoot + Clone + Send + 'static,
+    jsonl::Options { frames_per_second }: jsonl::Options,
+) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let progress = progress.clone();
+        std::thread::Builder::new()
+            .name("render-remote-client".into())
+            .spawn(move || {
+                jsonl::write_until_stopped(&mut stream, &progress, frames_per_second, &AtomicBool::new(false))
+            })?;
+    }
+    Ok(())
+}
+
+/// Connect to a progress tree [served][serve()] at `addr` and return a mirror of it that follows all of its changes.
+pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Arc<Mirror>> {
+    Ok(follow(TcpStream::connect(addr)?))
+}
+
+/// Return a mirror of the progress tree written as [JSON lines][crate::render::jsonl] to `input`, which follows
+/// all of its changes on a thread until `input` ends or the mirror is dropped.
+pub fn follow(input: impl io::Read + Send + 'static) -> Arc<Mirror> {
+    let mirror = Arc::new(Mirror::default());
+    std::thread::Builder::new()
+        .name("render-remote-mirror".into())
+        .spawn({
+            let mirror = Arc::downgrade(&mirror);
+            move || {
+                for line in io::BufReader::new(input).lines() {
+                    let (Ok(line), Some(mirror)) = (line, mirror.upgrade()) else {
+                        break;
+                    };
+                    mirror.apply(&line);
+                }
+            }
+        })
+        .expect("starting a thread works");
+    mirror
+}
+
+/// A progress tree that reflects the one of another process as closely as possible.
+///
+/// Tasks are updated once a frame was received completely, and units are only known by how their values are
+/// displayed.
+pub struct Mirror {
+    state: Mutex<MirrorState>,
+}
+
+struct MirrorState {
+    tasks: Vec<(Key, Task)>,
+    incoming_tasks: Vec<(Key, Task)>,
+    frame: Option<u64>,
+    messages: MessageRingBuffer,
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Mirror {
+            state: Mutex::new(MirrorState {
+                tasks: Vec::new(),
+                incoming_tasks: Vec::new(),
+                frame: None,
+                messages: MessageRingBuffer::with_capacity(MESSAGES_CAPACITY),
+            }),
+        }
+    }
+}
+
+impl Mirror {
+    /// Apply a single `line` of [JSON lines][crate::render::jsonl] output, and return `false` if it couldn't be understood.
+    ///
+    /// Tasks are collected until the line marking the end of their frame arrives, at which point they replace
+    /// all tasks, unless lines of the frame went missing.
+    pub fn apply(&self, line: &str) -> bool {
+        let Ok(line) = serde_json::from_str::<Line<'_>>(line) else {
+            return false;
+        };
+        let mut state = self.state.lock();
+        match line {
+            Line::Message { message } => state.messages.push(message.into_owned()),
+            Line::Task { frame, task } => {
+                if state.frame != Some(frame) {
+                    state.frame = Some(frame);
+                    state.incoming_tasks.clear();
+                }
+                state.incoming_tasks.push(mirror_task(*task));
+            }
+            Line::FrameEnd { frame, tasks } => {
+                let incoming = std::mem::take(&mut state.incoming_tasks);
+                let complete = incoming.len() == tasks && (tasks == 0 || state.frame == Some(frame));
+                if complete {
+                    state.tasks = incoming;
+                }
+            }
+        }
+        true
+    }
+}
+
+impl crate::WeakRoot for Weak<Mirror> {
+    type Root = Arc<Mirror>;
+
+    fn upgrade(&self) -> Option<Self::Root> {
+        Weak::upgrade(self)
+    }
+}
+
+impl crate::Root for Arc<Mirror> {
+    type WeakRoot = Weak<Mirror>;
+
+    fn messages_capacity(&self) -> usize {
+        MESSAGES_CAPACITY
+    }
+
+    fn num_tasks(&self) -> usize {
+        self.state.lock().tasks.len()
+    }
+
+    fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
+        out.clear();
+        out.extend_from_slice(&self.state.lock().tasks);
+    }
+
+    fn copy_messages(&self, out: &mut Vec<Message>) {
+        self.state.lock().messages.copy_all(out)
+    }
+
+    fn copy_new_messages(&self, out: &mut Vec<Message>, prev: Option<MessageCopyState>) -> MessageCopyState {
+        self.state.lock().messages.copy_new(out, prev)
+    }
+
+    fn downgrade(&self) -> Self::WeakRoot {
+        Arc::downgrade(self)
+    }
+}
+
+fn mirror_task(task: snapshot::Task) -> (Key, Task) {
+    let progress = task.progress.map(|progress| Value {
+        step: Arc::new(progress.step.into()),
+        failed: progress.failed,
+        done_at: progress.done_at,
+        unit: Some(unit::dynamic(Displayed(progress.display))),
+        state: match progress.state {
+            snapshot::State::Blocked { reason, until } => State::Blocked(intern(&reason), until),
+            snapshot::State::Halted { reason, until } => State::Halted(intern(&reason), until),
+            snapshot::State::Running => State::Running,
+        },
+        detail: progress.detail.map(Into::into),
+        blocked_by: progress.blocked_by,
+        started_at: progress.started_at,
+        stopped_since: progress.stopped_since,
+        paused_since: progress.paused_since,
+        paused_for: progress.paused_for,
+        aggregated: progress.aggregated,
+    });
+    (
+        task.key,
+        Task {
+            name: task.name,
+            id: task.id,
+            progress,
+        },
+    )
+}
+
+/// Return `reason` with a static lifetime as required by [`State`], leaking each distinct reason once.
+///
+/// To bound the memory this takes, once [`REASONS_CAPACITY`] distinct reasons were seen, all others are replaced by
+/// a generic one.
+fn intern(reason: &str) -> &'static str {
+    static REASONS: std::sync::Mutex<Option<HashSet<&'static str>>> = std::sync::Mutex::new(None);
+    let mut reasons = REASONS.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let reasons = reasons.get_or_insert_with(HashSet::new);
+    match reasons.get(reason) {
+        Some(reason) => reason,
+        None if reasons.len() >= REASONS_CAPACITY => "unknown reason",
+        None => {
+            let reason: &'static str = Box::leak(reason.into());
+            reasons.insert(reason);
+            reason
+        }
+    }
+}
+
+/// A unit showing values exactly as they were displayed by the process that produced them.
+struct Displayed(String);
+
+impl DisplayValue for Displayed {
+    fn display_current_value(
+        &self,
+        w: &mut dyn std::fmt::Write,
+        _value: Step,
+        _upper: Option<Step>,
+    ) -> std::fmt::Result {
+        w.write_str(&self.0)
+    }
+
+    fn separator(&self, _w: &mut dyn std::fmt::Write, _value: Step, _upper: Option<Step>) -> std::fmt::Result {
+        Ok(())
+    }
+
+    fn display_upper_bound(&self, _w: &mut dyn std::fmt::Write, _upper_bound: Step, _value: Step) -> std::fmt::Result {
+        Ok(())
+    }
+
+    fn dyn_hash(&self, state: &mut dyn std::hash::Hasher) {
+        state.write(self.0.as_bytes())
+    }
+
+    fn display_unit(&self, _w: &mut dyn std::fmt::Write, _value: Step) -> std::fmt::Result {
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+/*!
+* A module to write progress as a stream of [Server-Sent Events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+*
+* It takes care of the framing, so the output can be piped into the response of any web server to be consumed
+* by an `EventSource` in a browser. Alternatively, [`serve()`] acts as minimal web server with a page to show the
+* dashboard in a browser, useful for watching jobs on headless machines.
+*
+* **Please note** that it is behind the `render-sse` feature toggle.
+*
+* Each frame produces an `event: progress` whose data is a JSON snapshot of all tasks, each serialized as
+* [`snapshot::Task`], as in
+*
+* ```text
+* event: progress
+* data: {"tasks":[{"key":[0,null,null,null,null,null],"name":"task","id":[0,0,0,0],"progress":{"step":5,…}}]}
+*
+* ```
+*
+* and each message sent to the tree produces an `event: message` with data like
+* `{"time":{"secs_since_epoch":1700000000,"nanos_since_epoch":0},"level":"Info","origin":"task","message":"hello"}`.
+*/
+use std::{
+    io::{self, BufRead, Write},
+    net::TcpListener,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::{
+    Root, WeakRoot,
+    messages::Message,
+    progress::{Key, Task},
+    tree::snapshot,
+};
+
+/// Options used for configuring the [SSE renderer][render()].
+#[derive(Clone)]
+pub struct Options {
+    /// The amount of snapshots to write per second. If below 1.0, it determines the amount of seconds between them.
+    pub frames_per_second: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { frames_per_second: 4.0 }
+    }
+}
+
+/// A handle to the render thread, which when dropped will instruct it to stop and wait for it.
+pub struct JoinHandle {
+    inner: Option<std::thread::JoinHandle<io::Result<()>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl JoinHandle {
+    /// Instruct the render thread to stop after writing one last snapshot, and wait for it to finish.
+    pub fn shutdown_and_wait(mut self) -> io::Result<()> {
+        self.stop.store(true, Ordering::SeqCst);
+        self.inner
+            .take()
+            .map(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err(io::Error::other("render thread panicked")))
+            })
+            .unwrap_or(Ok(()))
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.inner.take().and_then(|h| h.join().ok());
+    }
+}
+
+/// Write snapshots of `progress` and all of its messages as Server-Sent Events to `out`, until `progress`
+/// is dropped, writing fails or the returned handle is dropped.
+pub fn render(
+    mut out: impl io::Write + Send + 'static,
+    progress: impl WeakRoot + Send + 'static,
+    Options { frames_per_second }: Options,
+) -> JoinHandle {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = std::thread::Builder::new()
+        .name("render-sse".into())
+        .spawn({
+            let stop = Arc::clone(&stop);
+            move || write_until_stopped(&mut out, &progress, frames_per_second, &stop)
+        })
+        .expect("starting a thread works");
+    JoinHandle {
+        inner: Some(handle),
+        stop,
+    }
+}
+
+/// Serve the dashboard of `progress` over HTTP to each client connecting to `listener`, blocking until accepting
+/// connections fails.
+///
+/// `GET /` returns a page showing all tasks and messages, and `GET /events` the Server-Sent Events it's fed by, like
+/// they are written by [`render()`]. Each client is served on its own thread until it disconnects or `progress` is dropped.
+///
+/// ```no_run
+/// let root = prodash::tree::Root::new();
+/// let listener = std::net::TcpListener::bind("127.0.0.1:8080")?;
+/// std::thread::spawn({
+///     let progress = std::sync::Arc::downgrade(&root);
+///     move || prodash::render::sse::serve(listener, progress, Default::default())
+/// });
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn serve(
+    listener: TcpListener,
+    progress: impl WeakRoot + Clone + Send + 'static,
+    Options { frames_per_second }: Options,
+) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let progress = progress.clone();
+        std::thread::Builder::new()
+            .name("render-sse-client".into())
+            .spawn(move || -> io::Result<()> {
+                let mut request_line = String::new();
+                io::BufReader::new(&stream).read_line(&mut request_line)?;
+                match request_line.split_whitespace().nth(1) {
+                    Some("/") => write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\
+                         Connection: close\r\n\r\n{PAGE}",
+                        PAGE.len()
+                    ),
+                    Some("/events") => {
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n"
+                        )?;
+                        write_until_stopped(&mut stream, &progress, frames_per_second, &AtomicBool::new(false))
+                    }
+                    _ => write!(
+                        stream,
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    ),
+                }
+            })?;
+    }
+    Ok(())
+}
+
+/// A page rendering the events of `/events` as text.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>prodash</title></head>
+<body><pre id="tasks"></pre><pre id="messages"></pre>
+<script>
+const events = new EventSource("/events");
+const stateName = state => typeof state === "string" ? state : Object.keys(state)[0];
+events.addEventListener("progress", e => {
+  document.getElementById("tasks").textContent = JSON.parse(e.data).tasks
+    .map(t => "  ".repeat(t.key.filter(id => id !== null).length - 1) + t.name
+      + (t.progress ? " " + t.progress.display + " " + stateName(t.progress.state) : ""))
+    .join("\n");
+});
+events.addEventListener("message", e => {
+  const m = JSON.parse(e.data);
+  document.getElementById("messages").textContent += `[${m.level}] ${m.origin}: ${m.message}\n`;
+});
+</script></body></html>
+"#;
+
+/// Write snapshots of `progress` and its new messages to `out` every frame, until `progress` is dropped, writing
+/// fails or `stop` is set, after which one last snapshot is written.
+fn write_until_stopped(
+    out: &mut impl io::Write,
+    progress: &impl WeakRoot,
+    frames_per_second: f32,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
+    let mut tasks = Vec::new();
+    let mut messages = Vec::new();
+    let mut copy_state = None;
+    loop {
+        let stopping = stop.load(Ordering::SeqCst);
+        let Some(progress) = progress.upgrade() else {
+            break;
+        };
+        copy_state = Some(progress.copy_new_messages(&mut messages, copy_state));
+        for message in &messages {
+            write_message(out, message)?;
+        }
+        progress.sorted_snapshot(&mut tasks);
+        write_snapshot(out, &tasks)?;
+        out.flush()?;
+        if stopping {
+            break;
+        }
+        std::thread::sleep(duration_per_frame);
+    }
+    Ok(())
+}
+
+/// Write a single event named `event` with `data` to `out`, splitting multi-line data as required by the format.
+pub fn write_event(out: &mut impl io::Write, event: &str, data: &str) -> io::Result<()> {
+    writeln!(out, "event: {event}")?;
+    for line in data.split('\n') {
+        writeln!(out, "data: {line}")?;
+    }
+    writeln!(out)
+}
+
+/// Write the sorted snapshot `tasks` as `progress` event to `out`.
+pub fn write_snapshot(out: &mut impl io::Write, tasks: &[(Key, Task)]) -> io::Result<()> {
+    #[derive(serde::Serialize)]
+    struct Progress {
+        tasks: Vec<snapshot::Task>,
+    }
+    let progress = Progress {
+        tasks: tasks.iter().map(|(key, task)| (*key, task.clone()).into()).collect(),
+    };
+    write_event(out, "progress", &serde_json::to_string(&progress)?)
+}
+
+/// Write `message` as `message` event to `out`.
+pub fn write_message(out: &mut impl io::Write, message: &Message) -> io::Result<()> {
+    write_event(out, "message", &serde_json::to_string(message)?)
+}
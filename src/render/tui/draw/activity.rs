@@ -0,0 +1,67 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use tui::style::Color;
+
+use crate::progress;
+
+/// The time it takes for the heat of a task to drop to about a third once it stops making progress.
+const COOL_DOWN: Duration = Duration::from_secs(3);
+/// The heat a task gains each time its progress changed between two observations.
+const HEAT_PER_UPDATE: f32 = 0.4;
+
+const COLD: (u8, u8, u8) = (70, 70, 90);
+const HOT: (u8, u8, u8) = (255, 80, 0);
+
+struct State {
+    last_step: progress::Step,
+    heat: f32,
+    observed_at: Instant,
+}
+
+/// A utility to track how recently and how frequently tasks made progress, expressed as heat between 0.0 and 1.0.
+#[derive(Default)]
+pub struct Activity {
+    per_key: progress::PerKey<State>,
+    now: Option<Instant>,
+}
+
+impl Activity {
+    /// Called at the beginning of the drawing of a frame to remember the time at which progress values are observed.
+    pub fn update_time(&mut self) {
+        self.now = Some(Instant::now());
+    }
+
+    /// Lookup or create the state at `key`, update it with `progress` and return its heat.
+    pub fn update_and_get(&mut self, key: &progress::Key, progress: Option<&progress::Value>) -> Option<f32> {
+        let step = progress?.step.load(Ordering::SeqCst);
+        let now = self.now.unwrap_or_else(Instant::now);
+        let state = self.per_key.get_or_insert_with(key, || State {
+            last_step: step,
+            heat: 0.0,
+            observed_at: now,
+        });
+        let elapsed = now.saturating_duration_since(state.observed_at);
+        state.heat *= (-elapsed.as_secs_f32() / COOL_DOWN.as_secs_f32()).exp();
+        state.observed_at = now;
+        if state.last_step != step {
+            state.last_step = step;
+            state.heat = (state.heat + HEAT_PER_UPDATE).min(1.0);
+        }
+        Some(state.heat)
+    }
+
+    /// Compare the keys in `sorted_values` with our internal state and remove all missing tasks from it.
+    pub fn reconcile(&mut self, sorted_values: &[(progress::Key, progress::Task)]) {
+        self.per_key.reconcile(sorted_values);
+    }
+}
+
+/// Return a color blending from cold to hot according to `heat`.
+pub fn heat_color(heat: f32) -> Color {
+    let heat = heat.clamp(0.0, 1.0);
+    let mix = |cold: u8, hot: u8| (cold as f32 + (hot as f32 - cold as f32) * heat).round() as u8;
+    Color::Rgb(mix(COLD.0, HOT.0), mix(COLD.1, HOT.1), mix(COLD.2, HOT.2))
+}
@@ -1,38 +1,122 @@
-use std::time::Duration;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use tui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Modifier, Style},
     text::Span,
     widgets::{Block, Borders, Widget},
 };
 
 use crate::{
     Throughput,
-    messages::Message,
+    messages::{Message, MessageLevel},
     progress::{Key, Task},
-    render::tui::{
-        InterruptDrawInfo, Line, draw,
-        utils::{block_width, rect},
+    render::{
+        BarStyle, Column, FinishedTasks, NameAbbrev, canvas,
+        last_update::LastUpdate,
+        message_log,
+        tui::{
+            CustomPane, InterruptDrawInfo, KeyBindings, Layout, Line, SortOrder, Theme, draw,
+            utils::{block_width, rect},
+        },
     },
+    time::{TimestampFormat, Timezone},
 };
 
 #[derive(Default)]
 pub struct State {
     pub title: String,
     pub task_offset: u16,
+    pub folded: HashSet<Key>,
+    pub fold_requested: Option<u16>,
+    pub filter: Option<String>,
+    pub editing_filter: bool,
+    pub follow_new_tasks: bool,
+    pub tasks_at_bottom: bool,
+    pub num_tasks_seen: usize,
     pub message_offset: u16,
     pub hide_messages: bool,
     pub messages_fullscreen: bool,
+    pub message_groups: Option<draw::MessageGroups>,
+    pub message_level: Option<MessageLevel>,
+    pub max_messages_per_frame: Option<usize>,
+    pub show_message_fields: bool,
+    pub message_follow: draw::MessageFollow,
+    pub message_search: Option<draw::MessageSearch>,
+    pub copy_message_requested: bool,
+    pub copied_message: Option<String>,
     pub user_provided_window_size: Option<Rect>,
+    pub last_task_pane: Rect,
+    pub last_messages_pane: Option<Rect>,
+    pub last_info_pane: Option<Rect>,
+    pub last_minimap: Option<Rect>,
     pub duration_per_frame: Duration,
     pub information: Vec<Line>,
     pub hide_info: bool,
     pub maximize_info: bool,
+    pub show_help: bool,
+    pub show_legend: bool,
+    pub show_minimap: bool,
+    pub show_child_count: bool,
+    pub disambiguate_duplicate_names: bool,
+    pub critical_path: Option<Vec<Key>>,
+    pub explicit_order: Option<Vec<String>>,
+    pub layout: Layout,
+    pub finished_tasks: FinishedTasks,
+    pub sort_order: SortOrder,
+    pub ascii: bool,
+    pub collapsed: bool,
+    pub overall_eta: Option<Duration>,
+    pub overall_eta_at: Option<Instant>,
+    pub show_global_bar: bool,
+    pub overall_fraction: Option<f32>,
+    pub global_bar_step: usize,
+    pub show_task_fraction: bool,
     pub last_tree_column_width: Option<u16>,
     pub next_tree_column_width: Option<u16>,
     pub throughput: Option<Throughput>,
+    pub show_trend: bool,
+    pub show_sparkline: bool,
+    pub show_eta: bool,
+    pub show_elapsed: bool,
+    pub activity: Option<draw::Activity>,
+    pub custom_pane: Option<CustomPane>,
+    pub system_stats: Option<draw::SystemStats>,
+    pub last_update: Option<LastUpdate>,
+    pub columns: Vec<Arc<dyn Column + Send + Sync>>,
+    pub theme: Theme,
+    pub key_bindings: KeyBindings,
+    pub bar_style: BarStyle,
+    pub fill_mapping: Option<fn(f32) -> f32>,
+    pub name_abbrev: NameAbbrev,
+    pub timezone: Timezone,
+    pub timestamp_format: TimestampFormat,
+}
+
+impl State {
+    /// Blend the `estimate` of the time until all tasks are done with the previous one, to avoid jumps as tasks are
+    /// added and removed, and make it the new [`overall_eta`][State::overall_eta].
+    pub fn update_overall_eta(&mut self, estimate: Option<Duration>) {
+        /// The weight of the most recent estimate when blending it with the previous one.
+        const SMOOTHING: f64 = 0.2;
+
+        let now = Instant::now();
+        self.overall_eta = estimate.map(|estimate| match self.overall_eta.zip(self.overall_eta_at) {
+            Some((previous, then)) => {
+                previous
+                    .saturating_sub(now.saturating_duration_since(then))
+                    .mul_f64(1.0 - SMOOTHING)
+                    + estimate.mul_f64(SMOOTHING)
+            }
+            None => estimate,
+        });
+        self.overall_eta_at = Some(now);
+    }
 }
 
 pub(crate) fn all(
@@ -43,14 +127,53 @@ pub(crate) fn all(
     bound: Rect,
     buf: &mut Buffer,
 ) {
+    panes(state, interrupt_mode, entries, messages, bound, buf);
+    if state.ascii {
+        replace_with_ascii(buf, bound);
+    }
+}
+
+fn panes(
+    state: &mut State,
+    interrupt_mode: InterruptDrawInfo,
+    entries: &[(Key, Task)],
+    messages: &[Message],
+    bound: Rect,
+    buf: &mut Buffer,
+) {
+    state.last_task_pane = Rect::default();
+    state.last_messages_pane = None;
+    state.last_info_pane = None;
+    state.last_minimap = None;
+    let copy_message_requested = std::mem::take(&mut state.copy_message_requested);
+    if state.collapsed {
+        draw::progress::summary(
+            &state.title,
+            entries,
+            state.theme.title,
+            buf,
+            Rect { height: 1, ..bound },
+        );
+        return;
+    }
+    let window_bound = bound;
+    let information = state
+        .system_stats
+        .as_mut()
+        .filter(|_| !state.hide_info)
+        .map(|stats| stats.information(&state.information));
     let (bound, info_pane) = compute_info_bound(
         bound,
-        if state.hide_info { &[] } else { &state.information },
+        if state.hide_info {
+            &[]
+        } else {
+            information.as_deref().unwrap_or(&state.information)
+        },
         state.maximize_info,
     );
-    let bold = Style::default().add_modifier(Modifier::BOLD);
+    state.last_info_pane = info_pane;
     let window = Block::default()
-        .title(Span::styled(state.title.as_str(), bold))
+        .title(Span::styled(state.title.as_str(), state.theme.title))
         .borders(Borders::ALL);
     let inner_area = window.inner(bound);
     window.render(bound, buf);
@@ -62,7 +185,7 @@ pub(crate) fn all(
     draw::progress::headline(
         entries,
         interrupt_mode,
-        state.duration_per_frame,
+        state,
         buf,
         rect::offset_x(
             Rect {
@@ -74,11 +197,75 @@ pub(crate) fn all(
         ),
     );
 
-    let (progress_pane, messages_pane) = compute_pane_bounds(
-        if state.hide_messages { &[] } else { messages },
-        inner_area,
-        state.messages_fullscreen,
-    );
+    let inner_area = if state.show_global_bar && inner_area.height > 1 {
+        draw::progress::global_bar(
+            state.overall_fraction,
+            state.overall_eta,
+            state.global_bar_step,
+            state.bar_style,
+            &state.theme,
+            buf,
+            Rect {
+                height: 1,
+                ..inner_area
+            },
+        );
+        state.global_bar_step = state.global_bar_step.wrapping_add(1);
+        Rect {
+            y: inner_area.y + 1,
+            height: inner_area.height - 1,
+            ..inner_area
+        }
+    } else {
+        inner_area
+    };
+    let min_tasks_height = 2;
+    let inner_area = match state.custom_pane.as_ref() {
+        Some(custom) if custom.height > 0 && inner_area.height >= custom.height + min_tasks_height => {
+            let tasks_height = inner_area.height - custom.height;
+            (custom.draw)(
+                buf,
+                Rect {
+                    y: inner_area.y + tasks_height,
+                    height: custom.height,
+                    ..inner_area
+                },
+                entries,
+            );
+            Rect {
+                height: tasks_height,
+                ..inner_area
+            }
+        }
+        _ => inner_area,
+    };
+    let mut filtered_messages: Vec<_>;
+    let origin_filter = state.filter.as_deref().filter(|filter| !filter.is_empty());
+    let max_messages = state.max_messages_per_frame.unwrap_or(usize::MAX);
+    let messages = if state.hide_messages {
+        &[]
+    } else if origin_filter.is_some() || state.message_level.is_some() {
+        // Search from the newest message to stop as soon as enough were found.
+        filtered_messages = messages
+            .iter()
+            .rev()
+            .filter(|message| origin_filter.is_none_or(|filter| draw::filter::matches(&message.origin, filter)))
+            .filter(|message| {
+                state
+                    .message_level
+                    .is_none_or(|level| message.level.severity() >= level.severity())
+            })
+            .take(max_messages)
+            .cloned()
+            .collect();
+        filtered_messages.reverse();
+        filtered_messages.as_slice()
+    } else {
+        &messages[messages.len().saturating_sub(max_messages)..]
+    };
+    let (progress_pane, messages_pane) = compute_pane_bounds(messages, inner_area, state.messages_fullscreen);
+    state.last_task_pane = progress_pane;
+    state.last_messages_pane = messages_pane;
 
     draw::progress::pane(entries, progress_pane, buf, state);
     if let Some(messages_pane) = messages_pane {
@@ -89,13 +276,32 @@ pub(crate) fn all(
                 width: messages_pane.width + 2,
                 ..rect::line_bound(bound, bound.height.saturating_sub(1) as usize)
             },
-            &mut state.message_offset,
+            state,
             buf,
         );
     }
 
+    if copy_message_requested && messages_pane.is_some() {
+        state.copied_message = draw::messages::topmost(
+            messages,
+            state.message_offset,
+            state.message_groups.as_ref(),
+            state.show_message_fields,
+        )
+        .map(|message| message_log::format_message(message, state.timezone));
+    }
+
     if let Some(info_pane) = info_pane {
-        draw::information::pane(&state.information, info_pane, buf);
+        draw::information::pane(
+            information.as_deref().unwrap_or(&state.information),
+            info_pane,
+            &state.theme,
+            buf,
+        );
+    }
+
+    if state.show_help {
+        draw::help::overlay(state, buf, window_bound);
     }
 }
 
@@ -120,7 +326,7 @@ fn compute_pane_bounds(messages: &[Message], inner: Rect, messages_fullscreen: b
                 },
                 Some(rect::intersect(
                     Rect {
-                        y: tasks_height + messages_title,
+                        y: inner.y + tasks_height,
                         height: messages_height,
                         ..inner
                     },
@@ -161,3 +367,18 @@ fn compute_info_bound(bound: Rect, info: &[Line], maximize: bool) -> (Rect, Opti
         Some(rect::snap_to_right(bound, pane_width)),
     )
 }
+
+/// Replace all symbols within `bound` that aren't ASCII with similar ASCII characters.
+fn replace_with_ascii(buf: &mut Buffer, bound: Rect) {
+    let bound = bound.intersection(buf.area);
+    for y in bound.top()..bound.bottom() {
+        for x in bound.left()..bound.right() {
+            let cell = &mut buf[(x, y)];
+            let symbol = match canvas::to_ascii(cell.symbol()) {
+                Cow::Owned(symbol) => symbol,
+                Cow::Borrowed(_) => continue,
+            };
+            cell.set_symbol(&symbol);
+        }
+    }
+}
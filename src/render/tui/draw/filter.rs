@@ -0,0 +1,30 @@
+use crate::progress::{Key, Task};
+
+/// Return true if all characters of `filter` appear in `name` in the same order, ignoring case.
+///
+/// This matches substrings as well as abbreviations, like `dlidx` for `download index`.
+pub(crate) fn matches(name: &str, filter: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    filter
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|wanted| name.any(|c| c == wanted))
+}
+
+/// Return all tasks of `entries` whose names match `filter`, along with their ancestors to keep their place in the tree.
+pub(crate) fn tasks(entries: &[(Key, Task)], filter: &str) -> Vec<(Key, Task)> {
+    let matching: Vec<_> = entries
+        .iter()
+        .filter(|(_key, task)| matches(&task.name, filter))
+        .map(|(key, _task)| *key)
+        .collect();
+    entries
+        .iter()
+        .filter(|(key, _task)| {
+            matching.iter().any(|other| {
+                other == key || (other.level() > key.level() && other.shares_parent_with(key, key.level()))
+            })
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,65 @@
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Span,
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+use crate::render::tui::{
+    Action,
+    draw::State,
+    utils::{block_width, draw_text_with_ellipsis_nowrap, rect},
+};
+
+/// Draw a box centered in `bound` listing all key bindings, along with whether the toggles among them are on.
+pub(crate) fn overlay(state: &State, buf: &mut Buffer, bound: Rect) {
+    let mut lines: Vec<_> = Action::ALL
+        .iter()
+        .filter_map(|action| {
+            let keys = state.key_bindings.keys(*action);
+            if keys.is_empty() {
+                return None;
+            }
+            let toggle = match action {
+                Action::Filter => Some(state.filter.is_some()),
+                Action::SearchMessages => Some(state.message_search.is_some()),
+                Action::ToggleFollowMessages => Some(state.message_follow.enabled),
+                Action::ToggleMessages => Some(state.hide_messages),
+                Action::MaximizeMessages => Some(state.messages_fullscreen),
+                Action::ToggleMessageFields => Some(state.show_message_fields),
+                Action::ToggleInformation => Some(state.hide_info),
+                Action::MaximizeInformation => Some(state.maximize_info),
+                Action::ToggleLegend => Some(state.show_legend),
+                _ => None,
+            };
+            let toggle = match toggle {
+                Some(true) => " [on]",
+                Some(false) => " [off]",
+                None => "",
+            };
+            Some(format!(" {:>7}  {}{toggle} ", keys.join(" "), action.description()))
+        })
+        .collect();
+    lines.push(String::new());
+    lines.push(" press Esc to close".into());
+    let width = (lines.iter().map(|line| block_width(line)).max().unwrap_or(0) + 2).min(bound.width);
+    let height = (lines.len() as u16 + 2).min(bound.height);
+    let bound = rect::intersect(
+        Rect {
+            x: bound.x + (bound.width - width) / 2,
+            y: bound.y + (bound.height - height) / 2,
+            width,
+            height,
+        },
+        bound,
+    );
+    Clear.render(bound, buf);
+    let block = Block::default()
+        .title(Span::styled("Help", state.theme.title))
+        .borders(Borders::ALL);
+    let inner = block.inner(bound);
+    block.render(bound, buf);
+    for (line, text) in lines.into_iter().enumerate().take(inner.height as usize) {
+        draw_text_with_ellipsis_nowrap(rect::line_bound(inner, line), buf, text, None);
+    }
+}
@@ -1,18 +1,17 @@
 use tui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Modifier, Style},
     text::Span,
     widgets::{Block, Borders, Widget},
 };
 
 use crate::render::tui::{
-    Line,
+    Line, Theme,
     utils::{block_width, draw_text_with_ellipsis_nowrap, rect},
 };
 
-pub fn pane(lines: &[Line], bound: Rect, buf: &mut Buffer) {
-    let bold = Style::default().add_modifier(Modifier::BOLD);
+pub fn pane(lines: &[Line], bound: Rect, theme: &Theme, buf: &mut Buffer) {
+    let bold = theme.title;
     let block = Block::default()
         .title(Span::styled("Information", bold))
         .borders(Borders::TOP | Borders::BOTTOM);
@@ -44,7 +43,7 @@ pub fn pane(lines: &[Line], bound: Rect, buf: &mut Buffer) {
                 offset += 1;
             }
             Line::Text(text) => {
-                draw_text_with_ellipsis_nowrap(rect::offset_x(line_bound, 1), buf, text, None);
+                draw_text_with_ellipsis_nowrap(rect::offset_x(line_bound, 1), buf, text, theme.information);
             }
         };
         if let Line::Title(_) = next_info {
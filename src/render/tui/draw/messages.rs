@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::{collections::HashSet, time::SystemTime};
 
 use tui::{
     buffer::Buffer,
@@ -11,54 +11,321 @@ use unicode_width::UnicodeWidthStr;
 
 use crate::{
     messages::{Message, MessageLevel},
-    render::tui::utils::{VERTICAL_LINE, block_width, draw_text_with_ellipsis_nowrap, rect, sanitize_offset},
-    time::{DATE_TIME_HMS, format_time_for_messages},
+    render::tui::{
+        Theme,
+        draw::State,
+        utils::{VERTICAL_LINE, block_width, draw_text_with_ellipsis_nowrap, rect, sanitize_offset},
+    },
+    time::{TimestampFormat, Timezone},
 };
 
-pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mut u16, buf: &mut Buffer) {
-    let bold = Style::default().add_modifier(Modifier::BOLD);
+/// Runs of consecutive messages of the same origin which are longer than a threshold, shown collapsed into one line
+/// unless expanded.
+pub struct MessageGroups {
+    /// Runs with more messages than this are collapsed.
+    pub threshold: usize,
+    /// The groups which were expanded, identified by the time of their oldest message.
+    pub expanded: HashSet<SystemTime>,
+    /// If true, expand or collapse the group in the topmost visible row with the next frame.
+    pub toggle_requested: bool,
+}
+
+impl MessageGroups {
+    pub fn new(threshold: usize) -> Self {
+        MessageGroups {
+            threshold,
+            expanded: HashSet::new(),
+            toggle_requested: false,
+        }
+    }
+}
+
+/// Whether the messages pane sticks to the newest message, or keeps showing the same messages while new ones arrive.
+#[derive(Default)]
+pub struct MessageFollow {
+    /// If true, the newest message is shown in the topmost row.
+    pub enabled: bool,
+    /// The time of the message in the topmost row along with the offset it was shown at, to find it again once new
+    /// messages arrived unless the pane was scrolled in the meantime.
+    anchor: Option<(SystemTime, u16)>,
+}
+
+impl MessageFollow {
+    pub fn new(enabled: bool) -> Self {
+        MessageFollow { enabled, anchor: None }
+    }
+}
+
+/// An incremental search for text in messages, whose matches are highlighted and can be jumped to.
+#[derive(Default)]
+pub struct MessageSearch {
+    /// The text to find in messages, ignoring case.
+    pub query: String,
+    /// If true, the query is still being typed.
+    pub editing: bool,
+    /// If set, scroll to a match with the next frame.
+    pub jump_requested: Option<SearchJump>,
+}
+
+/// The match of a [`MessageSearch`] to scroll to, relative to the topmost row.
+#[derive(Debug, Clone, Copy)]
+pub enum SearchJump {
+    /// The topmost row if it matches, or the first match below it.
+    Nearest,
+    /// The first match below the topmost row.
+    Next,
+    /// The first match above the topmost row.
+    Previous,
+}
+
+/// Return the range of bytes of the first occurrence of `query` in `text`, ignoring case.
+fn find_ignore_case(text: &str, query: &str) -> Option<std::ops::Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    text.char_indices().find_map(|(start, _)| {
+        let mut wanted = query.chars().flat_map(char::to_lowercase).peekable();
+        let mut end = start;
+        for (index, c) in text[start..].char_indices() {
+            if wanted.peek().is_none() {
+                break;
+            }
+            for c in c.to_lowercase() {
+                if wanted.next() != Some(c) {
+                    return None;
+                }
+            }
+            end = start + index + c.len_utf8();
+        }
+        wanted.peek().is_none().then_some(start..end)
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Row<'a> {
+    Message(&'a Message),
+    Group { newest: &'a Message, len: usize },
+    Field { name: &'a str, value: &'a str },
+}
+
+/// Return the rows to display for `messages` from newest to oldest, along with the identifier of the group
+/// they belong to, if any. If `show_fields` is true, each message is followed by a row for each of its fields.
+///
+/// Rows are produced lazily, so only those which are looked at are computed.
+fn rows<'a>(
+    messages: &'a [Message],
+    groups: Option<&MessageGroups>,
+    show_fields: bool,
+) -> impl Iterator<Item = (Row<'a>, Option<SystemTime>)> {
+    message_rows(messages, groups).flat_map(move |(row, id)| {
+        let fields: &[(String, String)] = match row {
+            Row::Message(message) if show_fields => &message.fields,
+            Row::Message(_) | Row::Group { .. } | Row::Field { .. } => &[],
+        };
+        std::iter::once((row, id)).chain(fields.iter().map(move |(name, value)| (Row::Field { name, value }, id)))
+    })
+}
+
+/// Return the message shown in `row`, which is `None` for fields.
+fn row_message<'a>(row: &Row<'a>) -> Option<&'a Message> {
+    match row {
+        Row::Message(message) | Row::Group { newest: message, .. } => Some(*message),
+        Row::Field { .. } => None,
+    }
+}
+
+/// Return the message in the topmost row at `offset`, or the message a field in that row belongs to.
+pub fn topmost<'a>(
+    messages: &'a [Message],
+    offset: u16,
+    groups: Option<&MessageGroups>,
+    show_fields: bool,
+) -> Option<&'a Message> {
+    rows(messages, groups, show_fields)
+        .take(offset as usize + 1)
+        .filter_map(|(row, _id)| row_message(&row))
+        .last()
+}
+
+fn message_rows<'a>(
+    messages: &'a [Message],
+    groups: Option<&MessageGroups>,
+) -> impl Iterator<Item = (Row<'a>, Option<SystemTime>)> {
+    messages
+        .chunk_by(move |a, b| groups.is_some() && a.origin == b.origin)
+        .rev()
+        .flat_map(move |run| {
+            let group = groups
+                .filter(|groups| run.len() > groups.threshold)
+                .map(|groups| (run[0].time, !groups.expanded.contains(&run[0].time)));
+            let id = group.map(|(id, _collapsed)| id);
+            let collapsed = group.is_some_and(|(_id, collapsed)| collapsed);
+            let group = collapsed.then(|| {
+                (
+                    Row::Group {
+                        newest: &run[run.len() - 1],
+                        len: run.len(),
+                    },
+                    id,
+                )
+            });
+            let messages = if collapsed { &[] } else { run };
+            group
+                .into_iter()
+                .chain(messages.iter().rev().map(move |m| (Row::Message(m), id)))
+        })
+}
+
+pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, state: &mut State, buf: &mut Buffer) {
+    let State {
+        message_offset: ref mut offset,
+        ref mut message_groups,
+        show_message_fields: show_fields,
+        message_follow: ref mut follow,
+        ref mut message_search,
+        timestamp_format,
+        timezone,
+        ref theme,
+        ..
+    } = *state;
+    let mut groups = message_groups.as_mut();
+    let mut search = message_search.as_mut();
+    let bold = theme.title;
     let block = Block::default()
-        .title(Span::styled("Messages", bold))
+        .title(Span::styled(
+            if follow.enabled {
+                "Messages · following"
+            } else {
+                "Messages · paused"
+            },
+            bold,
+        ))
         .borders(Borders::TOP);
     let inner_bound = block.inner(bound);
     block.render(bound, buf);
     let help_text = " ⨯ = `| ▢ = ~ ";
     draw_text_with_ellipsis_nowrap(rect::snap_to_right(bound, block_width(help_text)), buf, help_text, bold);
 
-    let bound = inner_bound;
-    *offset = sanitize_offset(*offset, messages.len(), bound.height);
-    let max_origin_width = messages
+    let mut bound = inner_bound;
+    let search_bound = match search.as_ref() {
+        Some(_) if bound.height > 1 => {
+            bound.height -= 1;
+            Some(Rect {
+                y: bound.y + bound.height,
+                height: 1,
+                ..bound
+            })
+        }
+        _ => None,
+    };
+    if let Some(groups) = groups.as_deref_mut() {
+        if std::mem::take(&mut groups.toggle_requested) {
+            let top_row_group = rows(messages, Some(groups), show_fields)
+                .nth(*offset as usize)
+                .and_then(|(_row, id)| id);
+            if let Some(id) = top_row_group {
+                if !groups.expanded.remove(&id) {
+                    groups.expanded.insert(id);
+                }
+            }
+        }
+        groups
+            .expanded
+            .retain(|id| messages.iter().any(|message| message.time == *id));
+    }
+    let all_rows = || rows(messages, groups.as_deref(), show_fields);
+    let num_rows = all_rows().count();
+    let jump = search.as_mut().and_then(|search| search.jump_requested.take());
+    let query = search.as_ref().map(|search| search.query.as_str()).unwrap_or_default();
+    let matches: Vec<usize> = if query.is_empty() {
+        Vec::new()
+    } else {
+        all_rows()
+            .enumerate()
+            .filter_map(|(index, (row, _id))| {
+                row_message(&row).and_then(|message| find_ignore_case(&message.message, query).map(|_| index))
+            })
+            .collect()
+    };
+    if follow.enabled {
+        *offset = 0;
+    } else if let Some((anchor, _)) = follow.anchor.filter(|(_, anchored_offset)| anchored_offset == offset) {
+        if let Some(index) =
+            all_rows().position(|(row, _id)| row_message(&row).is_some_and(|message| message.time == anchor))
+        {
+            *offset = index as u16;
+        }
+    }
+    if let Some(jump) = jump {
+        let top = *offset as usize;
+        let target = match jump {
+            SearchJump::Nearest => matches.iter().find(|index| **index >= top).or(matches.first()),
+            SearchJump::Next => matches.iter().find(|index| **index > top).or(matches.first()),
+            SearchJump::Previous => matches.iter().rev().find(|index| **index < top).or(matches.last()),
+        };
+        if let Some(target) = target {
+            *offset = *target as u16;
+            follow.enabled = false;
+        }
+    }
+    *offset = sanitize_offset(*offset, num_rows, bound.height);
+    follow.anchor = all_rows()
+        .take(*offset as usize + 1)
+        .filter_map(|(row, _id)| row_message(&row))
+        .last()
+        .map(|message| (message.time, *offset));
+    let rows: Vec<_> = all_rows().skip(*offset as usize).take(bound.height as usize).collect();
+    let max_origin_width = rows
         .iter()
-        .rev()
-        .skip(*offset as usize)
-        .take(bound.height as usize)
-        .fold(0, |state, message| state.max(block_width(&message.origin)));
-    for (
-        line,
-        Message {
+        .filter_map(|(row, _id)| row_message(row))
+        .fold(0, |width, message| width.max(block_width(&message.origin)));
+    let time_width = timestamp_format.fixed_width().map_or_else(
+        || {
+            rows.iter()
+                .filter_map(|(row, _id)| row_message(row))
+                .fold(0, |width, message| {
+                    width.max(block_width(&timestamp_format.format(message.time, timezone)))
+                })
+        },
+        |width| width as u16,
+    );
+    for (line, (row, _id)) in rows.iter().enumerate() {
+        let line_bound = rect::line_bound(bound, line);
+        let (time_bound, level_bound, origin_bound, message_bound) =
+            compute_bounds(line_bound, time_width, max_origin_width);
+        let Message {
             time,
             message,
             level,
             origin,
-        },
-    ) in messages
-        .iter()
-        .rev()
-        .skip(*offset as usize)
-        .take(bound.height as usize)
-        .enumerate()
-    {
-        let line_bound = rect::line_bound(bound, line);
-        let (time_bound, level_bound, origin_bound, message_bound) = compute_bounds(line_bound, max_origin_width);
+            fields,
+        } = match row {
+            Row::Message(message) | Row::Group { newest: message, .. } => message,
+            Row::Field { name, value } => {
+                let written = draw_text_with_ellipsis_nowrap(
+                    message_bound,
+                    buf,
+                    format!("  {name}: "),
+                    Style::default().add_modifier(Modifier::DIM),
+                );
+                draw_text_with_ellipsis_nowrap(rect::offset_x(message_bound, written), buf, *value, None);
+                continue;
+            }
+        };
         if let Some(time_bound) = time_bound {
-            draw_text_with_ellipsis_nowrap(time_bound, buf, format_time_column(time), None);
+            draw_text_with_ellipsis_nowrap(
+                time_bound,
+                buf,
+                format_time_column(time, timestamp_format, timezone),
+                None,
+            );
         }
         if let Some(level_bound) = level_bound {
             draw_text_with_ellipsis_nowrap(
                 level_bound,
                 buf,
                 format_level_column(*level),
-                Some(level_to_style(*level)),
+                Some(level_to_style(*level, theme)),
             );
             draw_text_with_ellipsis_nowrap(rect::offset_x(level_bound, LEVEL_TEXT_WIDTH), buf, VERTICAL_LINE, None);
         }
@@ -66,16 +333,57 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
             draw_text_with_ellipsis_nowrap(origin_bound, buf, origin, None);
             draw_text_with_ellipsis_nowrap(rect::offset_x(origin_bound, max_origin_width), buf, "→", None);
         }
-        draw_text_with_ellipsis_nowrap(message_bound, buf, message, None);
+        match row {
+            Row::Message(_) => {
+                let written = match find_ignore_case(message, query) {
+                    Some(found) => {
+                        let mut written =
+                            draw_text_with_ellipsis_nowrap(message_bound, buf, &message[..found.start], None);
+                        written += draw_text_with_ellipsis_nowrap(
+                            rect::offset_x(message_bound, written),
+                            buf,
+                            &message[found.clone()],
+                            Style::default().add_modifier(Modifier::REVERSED),
+                        );
+                        written
+                            + draw_text_with_ellipsis_nowrap(
+                                rect::offset_x(message_bound, written),
+                                buf,
+                                &message[found.end..],
+                                None,
+                            )
+                    }
+                    None => draw_text_with_ellipsis_nowrap(message_bound, buf, message, None),
+                };
+                if !show_fields && !fields.is_empty() {
+                    draw_text_with_ellipsis_nowrap(
+                        rect::offset_x(message_bound, written),
+                        buf,
+                        format!(" ⊞ {} fields", fields.len()),
+                        Style::default().add_modifier(Modifier::DIM),
+                    );
+                }
+            }
+            Row::Group { len, .. } => {
+                draw_text_with_ellipsis_nowrap(
+                    message_bound,
+                    buf,
+                    format!("⊞ {len} messages · {message}"),
+                    Style::default().add_modifier(Modifier::DIM),
+                );
+            }
+            Row::Field { .. } => unreachable!("fields are drawn above"),
+        }
+    }
+
+    if let Some((search, search_bound)) = search.as_ref().zip(search_bound) {
+        let current = matches.iter().filter(|index| **index <= *offset as usize).count();
+        draw_search(buf, search, current, matches.len(), search_bound);
     }
 
-    if (bound.height as usize) < messages.len().saturating_sub(*offset as usize)
-        || (*offset).min(messages.len() as u16) > 0
-    {
-        let messages_below = messages
-            .len()
-            .saturating_sub(bound.height.saturating_add(*offset) as usize);
-        let messages_skipped = (*offset).min(messages.len() as u16);
+    if (bound.height as usize) < num_rows.saturating_sub(*offset as usize) || (*offset).min(num_rows as u16) > 0 {
+        let messages_below = num_rows.saturating_sub(bound.height.saturating_add(*offset) as usize);
+        let messages_skipped = (*offset).min(num_rows as u16);
         draw_text_with_ellipsis_nowrap(
             rect::offset_x(overflow_bound, 1),
             buf,
@@ -92,6 +400,24 @@ pub fn pane(messages: &[Message], bound: Rect, overflow_bound: Rect, offset: &mu
     }
 }
 
+fn draw_search(buf: &mut Buffer, search: &MessageSearch, current: usize, num_matches: usize, bound: Rect) {
+    let text = format!(" search: {}{} ", search.query, if search.editing { "▏" } else { "" });
+    let written = draw_text_with_ellipsis_nowrap(bound, buf, text, Style::default().add_modifier(Modifier::BOLD));
+    let help_text = if search.editing {
+        format!(" {current}/{num_matches} matches · ⏎ = keep | ⨯ = Esc ")
+    } else {
+        format!(" {current}/{num_matches} matches · ↓ = n | ↑ = N ")
+    };
+    if written + block_width(&help_text) < bound.width {
+        draw_text_with_ellipsis_nowrap(
+            rect::snap_to_right(bound, block_width(&help_text)),
+            buf,
+            help_text,
+            Style::default().add_modifier(Modifier::DIM),
+        );
+    }
+}
+
 const LEVEL_TEXT_WIDTH: u16 = 4;
 fn format_level_column(level: MessageLevel) -> &'static str {
     use MessageLevel::*;
@@ -99,31 +425,41 @@ fn format_level_column(level: MessageLevel) -> &'static str {
         Info => "info",
         Failure => "fail",
         Success => "done",
+        Warning => "warn",
+        Debug => "dbug",
+        Trace => "trce",
     }
 }
 
-fn level_to_style(level: MessageLevel) -> Style {
+fn level_to_style(level: MessageLevel, theme: &Theme) -> Style {
     use MessageLevel::*;
     Style::default()
         .fg(Color::Black)
         .add_modifier(Modifier::BOLD)
         .bg(match level {
-            Info => Color::White,
-            Failure => Color::Red,
-            Success => Color::Green,
+            Info => theme.message_info,
+            Failure => theme.message_failure,
+            Success => theme.message_success,
+            Warning => theme.message_warning,
+            Debug => theme.message_debug,
+            Trace => theme.message_trace,
         })
 }
 
-fn format_time_column(time: &SystemTime) -> String {
-    format!("{}{}", format_time_for_messages(*time), VERTICAL_LINE)
+fn format_time_column(time: &SystemTime, timestamp_format: TimestampFormat, timezone: Timezone) -> String {
+    format!("{}{}", timestamp_format.format(*time, timezone), VERTICAL_LINE)
 }
 
-fn compute_bounds(line: Rect, max_origin_width: u16) -> (Option<Rect>, Option<Rect>, Option<Rect>, Rect) {
+fn compute_bounds(
+    line: Rect,
+    time_width: u16,
+    max_origin_width: u16,
+) -> (Option<Rect>, Option<Rect>, Option<Rect>, Rect) {
     let vertical_line_width = VERTICAL_LINE.width() as u16;
     let mythical_offset_we_should_not_need = 1;
 
     let time_bound = Rect {
-        width: DATE_TIME_HMS as u16 + vertical_line_width,
+        width: time_width + vertical_line_width,
         ..line
     };
 
@@ -0,0 +1,69 @@
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+};
+
+use crate::{
+    progress::{self, Key, Task},
+    render::tui::{Theme, utils::draw_text_with_ellipsis_nowrap},
+};
+
+/// Draw all `entries` compressed into the height of `bound`, one colored line per row representing the state of the
+/// tasks it covers, and highlight the rows covering the `num_visible` entries starting at `offset`.
+pub(crate) fn draw(
+    entries: &[(Key, Task)],
+    theme: &Theme,
+    offset: u16,
+    num_visible: u16,
+    buf: &mut Buffer,
+    bound: Rect,
+) {
+    let rows = num_rows(entries, bound);
+    let (visible_start, visible_end) = (offset as usize, offset as usize + num_visible as usize);
+    for row in 0..rows {
+        let start = first_entry_of_row(row, rows, entries.len());
+        let end = first_entry_of_row(row + 1, rows, entries.len()).max(start + 1);
+        let is_visible = start < visible_end && end > visible_start;
+        let mut style = Style::default().fg(color(&entries[start..end], theme));
+        if is_visible {
+            style = style.bg(Color::DarkGray);
+        }
+        draw_text_with_ellipsis_nowrap(
+            Rect {
+                y: bound.y + row,
+                height: 1,
+                ..bound
+            },
+            buf,
+            "▐",
+            style,
+        );
+    }
+}
+
+/// Return the amount of rows the minimap of `entries` takes in `bound`, each standing for at least one task.
+pub(crate) fn num_rows(entries: &[(Key, Task)], bound: Rect) -> u16 {
+    bound.height.min(u16::try_from(entries.len()).unwrap_or(u16::MAX))
+}
+
+/// Return the index of the first of `num_entries` that `row` of a minimap with `num_rows` stands for.
+pub(crate) fn first_entry_of_row(row: u16, num_rows: u16, num_entries: usize) -> usize {
+    row as usize * num_entries / (num_rows as usize).max(1)
+}
+
+/// Return the color representing the most noteworthy state among `entries`.
+fn color(entries: &[(Key, Task)], theme: &Theme) -> Color {
+    let mut color = Color::DarkGray;
+    for progress in entries.iter().filter_map(|(_key, task)| task.progress.as_ref()) {
+        let is_done = progress.is_done();
+        color = match progress.state {
+            progress::State::Halted(_, _) => return theme.bar_halted,
+            progress::State::Blocked(_, _) => theme.bar_blocked,
+            progress::State::Running if color == theme.bar_blocked => color,
+            progress::State::Running if is_done && color != theme.bar_in_progress => theme.bar_nearly_done,
+            progress::State::Running => theme.bar_in_progress,
+        };
+    }
+    color
+}
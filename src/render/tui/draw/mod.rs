@@ -1,6 +1,15 @@
+pub(crate) mod activity;
 mod all;
+mod filter;
+mod help;
 mod information;
-mod messages;
+pub(crate) mod messages;
+pub(crate) mod minimap;
 mod progress;
+mod sections;
+mod system;
 
+pub(crate) use activity::Activity;
 pub(crate) use all::{State, all};
+pub(crate) use messages::{MessageFollow, MessageGroups, MessageSearch, SearchJump};
+pub(crate) use system::SystemStats;
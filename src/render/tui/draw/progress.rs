@@ -1,4 +1,9 @@
-use std::{fmt, sync::atomic::Ordering, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
 
 use tui::{
     buffer::Buffer,
@@ -10,21 +15,108 @@ use tui_react::fill_background;
 use crate::{
     Throughput,
     progress::{self, Key, Step, Task, Value},
-    render::tui::{
-        InterruptDrawInfo,
-        draw::State,
-        utils::{
-            GraphemeCountWriter, VERTICAL_LINE, block_width, draw_text_nowrap_fn, draw_text_with_ellipsis_nowrap, rect,
-            sanitize_offset,
+    render::{
+        BarStyle, Column, FinishedTasks, canvas, finished, order,
+        tui::{
+            InterruptDrawInfo, Layout, SortOrder, Theme,
+            draw::{State, activity::heat_color, filter, minimap, sections},
+            utils::{
+                BufferLine, GraphemeCountWriter, VERTICAL_LINE, block_width, draw_text_nowrap_fn,
+                draw_text_with_ellipsis_nowrap, rect, sanitize_offset,
+            },
         },
     },
-    time::format_now_datetime_seconds,
     unit,
 };
 
 const MIN_TREE_WIDTH: u16 = 20;
 
-pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer, state: &mut State) {
+pub fn pane(sorted_entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer, state: &mut State) {
+    let ordered_entries;
+    let entries = match state.explicit_order.as_deref() {
+        Some(names) => {
+            ordered_entries = order::by_names(sorted_entries, names);
+            ordered_entries.as_slice()
+        }
+        None => sorted_entries,
+    };
+    let ranked_entries;
+    let entries = match state.sort_order {
+        SortOrder::Tree => entries,
+        order => {
+            ranked_entries = by_sort_order(entries, order, state.throughput.as_ref());
+            ranked_entries.as_slice()
+        }
+    };
+    let filtered_entries;
+    let entries = match state.filter.as_deref() {
+        Some(filter) if !filter.is_empty() => {
+            filtered_entries = filter::tasks(entries, filter);
+            filtered_entries.as_slice()
+        }
+        _ => entries,
+    };
+    let shown_entries;
+    let entries = match state.finished_tasks {
+        FinishedTasks::Show => entries,
+        mode => {
+            shown_entries = finished::apply(entries, mode);
+            shown_entries.as_slice()
+        }
+    };
+    let (sectioned_entries, mut unfolded_entries);
+    let entries = match state.layout {
+        Layout::Tree if state.folded.is_empty() && state.fold_requested.is_none() => entries,
+        Layout::Tree => {
+            unfolded_entries = without_folded(entries, &state.folded);
+            if let Some(row) = state.fold_requested.take() {
+                let index = (state.task_offset as usize + row as usize).min(unfolded_entries.len().saturating_sub(1));
+                if let Some(key) = unfolded_entries.get(index).map(|(key, _task)| *key) {
+                    if !state.folded.remove(&key) && Key::num_children(&unfolded_entries, index) > 0 {
+                        state.folded.insert(key);
+                    }
+                    unfolded_entries = without_folded(entries, &state.folded);
+                }
+            }
+            unfolded_entries.as_slice()
+        }
+        Layout::Sections => {
+            sectioned_entries = sections::by_state(entries);
+            sectioned_entries.as_slice()
+        }
+    };
+    if (state.editing_filter || state.filter.is_some()) && bound.height > 1 {
+        bound.height -= 1;
+        draw_filter(
+            buf,
+            state.filter.as_deref().unwrap_or_default(),
+            state.editing_filter,
+            entries.len(),
+            Rect {
+                y: bound.y + bound.height,
+                height: 1,
+                ..bound
+            },
+        );
+    }
+    if state.show_legend && bound.height > 1 {
+        bound.height -= 1;
+        draw_legend(
+            buf,
+            state.explicit_order.is_some(),
+            state.sort_order,
+            state.layout,
+            Rect {
+                y: bound.y + bound.height,
+                height: 1,
+                ..bound
+            },
+        );
+    }
+    if state.follow_new_tasks && state.tasks_at_bottom && entries.len() > state.num_tasks_seen {
+        // Scroll as far as possible, which is limited below.
+        state.task_offset = u16::MAX;
+    }
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
     let needs_overflow_line =
         if entries.len() > bound.height as usize || (state.task_offset).min(entries.len() as u16) > 0 {
@@ -34,10 +126,32 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             false
         };
     state.task_offset = sanitize_offset(state.task_offset, entries.len(), bound.height);
+    state.num_tasks_seen = entries.len();
+    state.tasks_at_bottom = state.task_offset as usize + bound.height as usize >= entries.len();
 
     if entries.is_empty() {
         return;
     }
+    if state.show_minimap && bound.width > MIN_TREE_WIDTH {
+        bound.width -= 1;
+        let minimap_bound = Rect {
+            x: bound.right(),
+            width: 1,
+            ..bound
+        };
+        minimap::draw(
+            entries,
+            &state.theme,
+            state.task_offset,
+            bound.height,
+            buf,
+            minimap_bound,
+        );
+        state.last_minimap = Some(Rect {
+            height: minimap::num_rows(entries, minimap_bound),
+            ..minimap_bound
+        });
+    }
 
     let initial_column_width = bound.width / 3;
     let desired_max_tree_draw_width = *state.next_tree_column_width.as_ref().unwrap_or(&initial_column_width);
@@ -47,7 +161,7 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
                 width: desired_max_tree_draw_width,
                 ..bound
             };
-            let computed = draw_tree(entries, buf, tree_bound, state.task_offset);
+            let computed = draw_tree(entries, sorted_entries, buf, tree_bound, state);
             state.last_tree_column_width = Some(computed);
         } else {
             state.last_tree_column_width = Some(0);
@@ -58,24 +172,27 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
         if let Some(tp) = state.throughput.as_mut() {
             tp.update_elapsed();
         }
+        if let Some(activity) = state.activity.as_mut() {
+            activity.update_time();
+        }
 
         let progress_area = rect::offset_x(bound, desired_max_tree_draw_width);
-        draw_progress(
-            entries,
-            buf,
-            progress_area,
-            state.task_offset,
-            state.throughput.as_mut(),
-        );
+        draw_progress(entries, sorted_entries, buf, progress_area, state);
 
         if let Some(tp) = state.throughput.as_mut() {
-            tp.reconcile(entries);
+            tp.reconcile(sorted_entries);
+        }
+        if let Some(activity) = state.activity.as_mut() {
+            activity.reconcile(sorted_entries);
+        }
+        if let Some(lu) = state.last_update.as_mut() {
+            lu.reconcile(sorted_entries);
         }
     }
 
     if needs_overflow_line {
         let overflow_rect = Rect {
-            y: bound.height + 1,
+            y: bound.y + bound.height,
             height: 1,
             ..bound
         };
@@ -86,6 +203,54 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
             desired_max_tree_draw_width,
             bound.height,
             state.task_offset,
+            &state.theme,
+        );
+    }
+}
+
+fn draw_filter(buf: &mut Buffer, filter: &str, editing: bool, num_tasks: usize, bound: Rect) {
+    let text = format!(" /{filter}{} ", if editing { "▏" } else { "" });
+    let written = draw_text_with_ellipsis_nowrap(bound, buf, text, Style::default().add_modifier(Modifier::BOLD));
+    let help_text = if editing {
+        format!(" {num_tasks} tasks · ⏎ = keep | ⨯ = Esc ")
+    } else {
+        format!(" {num_tasks} tasks · ✎ = / ")
+    };
+    if written + block_width(&help_text) < bound.width {
+        draw_text_with_ellipsis_nowrap(
+            rect::snap_to_right(bound, block_width(&help_text)),
+            buf,
+            help_text,
+            Style::default().add_modifier(Modifier::DIM),
+        );
+    }
+}
+
+fn draw_legend(buf: &mut Buffer, explicit_order: bool, sort_order: SortOrder, layout: Layout, bound: Rect) {
+    let parts = [
+        match sort_order {
+            SortOrder::Tree if explicit_order => "order: explicit".to_string(),
+            SortOrder::Tree => "order: hierarchy".to_string(),
+            SortOrder::Name => "order: name".to_string(),
+            SortOrder::StartTime => "order: start time".to_string(),
+            SortOrder::Remaining => "order: remaining".to_string(),
+            SortOrder::Throughput => "order: throughput".to_string(),
+        },
+        match layout {
+            Layout::Tree => "layout: tree".to_string(),
+            Layout::Sections => "layout: sections".to_string(),
+        },
+    ];
+    let text = format!(" {} ", parts.join(" · "));
+    let dimmed = Style::default().add_modifier(Modifier::DIM);
+    let written = draw_text_with_ellipsis_nowrap(bound, buf, text, dimmed);
+    let help_text = " ⨯ = l ";
+    if written + block_width(help_text) < bound.width {
+        draw_text_with_ellipsis_nowrap(
+            rect::snap_to_right(bound, block_width(help_text)),
+            buf,
+            help_text,
+            dimmed,
         );
     }
 }
@@ -93,25 +258,26 @@ pub fn pane(entries: &[(Key, progress::Task)], mut bound: Rect, buf: &mut Buffer
 pub(crate) fn headline(
     entries: &[(Key, Task)],
     interrupt_mode: InterruptDrawInfo,
-    duration_per_frame: Duration,
+    state: &State,
     buf: &mut Buffer,
     bound: Rect,
 ) {
-    let (num_running_tasks, num_blocked_tasks, num_groups) = entries.iter().fold(
-        (0, 0, 0),
-        |(mut running, mut blocked, mut groups), (_key, Task { progress, .. })| {
-            match progress.as_ref().map(|p| p.state) {
-                Some(progress::State::Running) => running += 1,
-                Some(progress::State::Blocked(_, _)) | Some(progress::State::Halted(_, _)) => blocked += 1,
-                None => groups += 1,
-            }
-            (running, blocked, groups)
-        },
-    );
+    let State {
+        duration_per_frame,
+        overall_eta,
+        show_task_fraction,
+        show_global_bar,
+        timezone,
+        ref theme,
+        ..
+    } = *state;
+    // The global bar shows the ETA already.
+    let overall_eta = overall_eta.filter(|_| !show_global_bar);
+    let (num_running_tasks, num_blocked_tasks, num_groups) = count_tasks(entries);
     let text = format!(
-        " {} {} {:3} running + {:3} blocked + {:3} groups = {} ",
+        " {} {}{}{} {:3} running + {:3} blocked + {:3} groups = {} ",
         match interrupt_mode {
-            InterruptDrawInfo::Instantly => "'q' or CTRL+c to quit",
+            InterruptDrawInfo::Instantly => "'q' or CTRL+c to quit, '?' for help",
             InterruptDrawInfo::Deferred(interrupt_requested) => {
                 if interrupt_requested {
                     "interrupt requested - please wait"
@@ -124,19 +290,125 @@ pub(crate) fn headline(
             format!(
                 " Every {}s → {}",
                 duration_per_frame.as_secs(),
-                format_now_datetime_seconds()
+                timezone.format_now_datetime_seconds()
             )
         } else {
             "".into()
         },
+        match overall_eta {
+            Some(eta) => format!(" {} ", canvas::format_eta(eta)),
+            None => "".into(),
+        },
+        if show_task_fraction {
+            let (num_done_tasks, num_tasks) = count_done_tasks(entries);
+            format!(" {num_done_tasks}/{num_tasks} tasks done ")
+        } else {
+            "".into()
+        },
         num_running_tasks,
         num_blocked_tasks,
         num_groups,
         entries.len()
     );
 
-    let bold = Style::default().add_modifier(Modifier::BOLD);
-    draw_text_with_ellipsis_nowrap(rect::snap_to_right(bound, block_width(&text) + 1), buf, text, bold);
+    draw_text_with_ellipsis_nowrap(
+        rect::snap_to_right(bound, block_width(&text) + 1),
+        buf,
+        text,
+        theme.title,
+    );
+}
+
+/// Draw a single bar across `bound` showing how much of all work is done according to `overall_fraction`, labelled with
+/// the percentage and `overall_eta`, or a spinner moving along with `step` if there is no bounded task.
+pub(crate) fn global_bar(
+    overall_fraction: Option<f32>,
+    overall_eta: Option<Duration>,
+    step: usize,
+    bar_style: BarStyle,
+    theme: &Theme,
+    buf: &mut Buffer,
+    bound: Rect,
+) {
+    match overall_fraction {
+        Some(fraction) => {
+            let mut label = canvas::percentage(fraction, None, bar_style.adaptive_precision);
+            if let Some(eta) = overall_eta {
+                label.push_str(" · ");
+                label.push_str(&canvas::format_eta(eta));
+            }
+            let glyphs = bar_style.glyphs.resolve(&canvas::BLOCKS);
+            let (bar, style) = draw_progress_bar_fn(buf, bound, fraction, glyphs, theme.bar_text, |fraction| {
+                theme.bar_color(fraction)
+            });
+            let label_width = block_width(&label);
+            let label_bound = if label_width < bound.width {
+                rect::offset_x(bound, (bound.width - label_width) / 2)
+            } else {
+                bound
+            };
+            draw_text_nowrap_fn(label_bound, buf, label, |_t, x, _y| {
+                if x < bar.right() {
+                    style
+                } else {
+                    Style::default().add_modifier(Modifier::BOLD)
+                }
+            });
+        }
+        None => draw_spinner(buf, bound, step, 0, theme.spinner),
+    }
+}
+
+/// Return the amount of running and blocked tasks, as well as the amount of groups.
+fn count_tasks(entries: &[(Key, Task)]) -> (usize, usize, usize) {
+    entries.iter().fold(
+        (0, 0, 0),
+        |(mut running, mut blocked, mut groups), (_key, Task { progress, .. })| {
+            // Aggregated progress belongs to groups, which aren't tasks of their own.
+            match progress.as_ref().filter(|p| !p.aggregated).map(|p| p.state) {
+                Some(progress::State::Running) => running += 1,
+                Some(progress::State::Blocked(_, _)) | Some(progress::State::Halted(_, _)) => blocked += 1,
+                None => groups += 1,
+            }
+            (running, blocked, groups)
+        },
+    )
+}
+
+/// Return the amount of tasks that are done, being bounded and at their last step, along with the amount of all tasks
+/// with progress.
+fn count_done_tasks(entries: &[(Key, Task)]) -> (usize, usize) {
+    entries
+        .iter()
+        .filter_map(|(_key, task)| task.progress.as_ref().filter(|progress| !progress.aggregated))
+        .fold((0, 0), |(done, total), progress| {
+            (done + usize::from(progress.is_done()), total + 1)
+        })
+}
+
+/// Draw a single line summarizing all `entries`, used instead of the whole dashboard when collapsed.
+pub(crate) fn summary(title: &str, entries: &[(Key, Task)], style: Style, buf: &mut Buffer, bound: Rect) {
+    let (num_running_tasks, num_blocked_tasks, num_groups) = count_tasks(entries);
+    let (sum, num_bounded) = entries
+        .iter()
+        .filter_map(|(_key, task)| {
+            task.progress
+                .as_ref()
+                .filter(|p| !p.aggregated)
+                .and_then(|p| p.fraction())
+        })
+        .fold((0f32, 0usize), |(sum, count), fraction| {
+            (sum + fraction.min(1.0), count + 1)
+        });
+    let percentage = if num_bounded == 0 {
+        String::new()
+    } else {
+        format!(" {:.0}% ·", sum / num_bounded as f32 * 100.0)
+    };
+    let text = format!(
+        " {title} ·{percentage} {num_running_tasks} running + {num_blocked_tasks} blocked + {num_groups} groups · idle "
+    );
+    draw_text_with_ellipsis_nowrap(bound, buf, text, style);
 }
 
 struct ProgressFormat<'a>(&'a Option<Value>, u16, Option<unit::display::Throughput>);
@@ -160,6 +432,12 @@ impl fmt::Display for ProgressFormat<'_> {
     }
 }
 
+fn shows_throughput(progress: Option<&Value>) -> bool {
+    progress
+        .and_then(|p| p.unit.as_ref())
+        .is_some_and(|unit| unit.shows_throughput())
+}
+
 fn has_child(entries: &[(Key, Task)], index: usize) -> bool {
     entries
         .get(index + 1)
@@ -173,11 +451,28 @@ fn has_child(entries: &[(Key, Task)], index: usize) -> bool {
 
 pub fn draw_progress(
     entries: &[(Key, Task)],
+    sorted_entries: &[(Key, Task)],
     buf: &mut Buffer,
     bound: Rect,
-    offset: u16,
-    mut throughput: Option<&mut Throughput>,
+    state: &mut State,
 ) {
+    let State {
+        task_offset: offset,
+        ref mut throughput,
+        ref mut activity,
+        ref mut last_update,
+        ref theme,
+        ref columns,
+        bar_style,
+        fill_mapping,
+        show_trend,
+        show_sparkline,
+        show_eta,
+        show_elapsed,
+        timestamp_format,
+        timezone,
+        ..
+    } = *state;
     let title_spacing = 2u16 + 1; // 2 on the left, 1 on the right
     let max_progress_label_width = entries
         .iter()
@@ -214,11 +509,43 @@ pub fn draw_progress(
         .take(bound.height as usize)
         .enumerate()
     {
+        let trend = throughput
+            .as_ref()
+            .filter(|_| show_trend)
+            .filter(|_| shows_throughput(progress.as_ref()))
+            .and_then(|tp| tp.trend(key));
+        let history = throughput
+            .as_ref()
+            .filter(|_| show_sparkline)
+            .filter(|_| shows_throughput(progress.as_ref()))
+            .map(|tp| tp.history(key))
+            .filter(|history| history.len() > 1);
+        let eta = throughput
+            .as_ref()
+            .filter(|_| show_eta)
+            .zip(progress.as_ref())
+            .and_then(|(tp, progress)| tp.eta(key, progress));
         let throughput = throughput
             .as_mut()
             .and_then(|tp| tp.update_and_get(key, progress.as_ref()));
-        let line_bound = rect::line_bound(bound, line);
-        let progress_text = format!(
+        let mut line_bound = rect::line_bound(bound, line);
+        if let Some(time) = last_update
+            .as_mut()
+            .and_then(|lu| lu.update_and_get(key, progress.as_ref()))
+            .map(|time| timestamp_format.format(time, timezone))
+            .map(|time| format!(" {time} "))
+        {
+            draw_dim_at_end(&mut line_bound, buf, time);
+        }
+        if let Some(elapsed) = progress
+            .as_ref()
+            .filter(|_| show_elapsed)
+            .and_then(|progress| progress.elapsed())
+        {
+            draw_dim_at_end(&mut line_bound, buf, format!(" {} ", canvas::format_elapsed(elapsed)));
+        }
+        draw_columns_at_end(&mut line_bound, buf, columns, key, progress.as_ref());
+        let mut progress_text = format!(
             " {progress}",
             progress = ProgressFormat(
                 progress,
@@ -230,39 +557,130 @@ pub fn draw_progress(
                 throughput
             )
         );
+        if let Some(trend) = trend {
+            progress_text.push(' ');
+            progress_text.push_str(trend.symbol());
+        }
+        if let Some(history) = history {
+            progress_text.push(' ');
+            progress_text.push_str(&canvas::sparkline(&history));
+        }
+        if let Some(eta) = eta {
+            progress_text.push(' ');
+            progress_text.push_str(&canvas::format_eta(eta));
+        }
+        if let Some(blocker) = progress.as_ref().and_then(|p| p.blocker(sorted_entries)) {
+            progress_text.push_str(" ⏳ waiting on ");
+            progress_text.push_str(&blocker.name);
+        }
 
-        draw_text_with_ellipsis_nowrap(line_bound, buf, VERTICAL_LINE, None);
+        match activity
+            .as_mut()
+            .and_then(|activity| activity.update_and_get(key, progress.as_ref()))
+        {
+            Some(heat) => draw_text_with_ellipsis_nowrap(line_bound, buf, "●", Style::default().fg(heat_color(heat))),
+            None => draw_text_with_ellipsis_nowrap(line_bound, buf, VERTICAL_LINE, None),
+        };
 
         let tree_prefix = level_prefix(entries, entry_index);
+        let depth_color = theme.depth_color(key.level());
         let progress_rect = rect::offset_x(line_bound, block_width(&tree_prefix));
         draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, None);
-        match progress
-            .as_ref()
-            .map(|p| (p.fraction(), p.state, p.step.load(Ordering::SeqCst)))
-        {
-            Some((Some(fraction), state, _step)) => {
-                let mut progress_text = progress_text;
-                add_block_eta(state, &mut progress_text);
-                let (bound, style) = draw_progress_bar_fn(buf, progress_rect, fraction, |fraction| match state {
-                    progress::State::Blocked(_, _) => Color::Red,
-                    progress::State::Halted(_, _) => Color::LightRed,
-                    progress::State::Running => {
-                        if fraction >= 0.8 {
-                            Color::Green
-                        } else {
-                            Color::Yellow
+        match progress.as_ref().map(|p| {
+            (
+                p.fraction(),
+                p.state,
+                p,
+                p.step.load(Ordering::SeqCst),
+                p.detail.as_deref(),
+                p.outcome_fractions(),
+            )
+        }) {
+            Some((Some(fraction), state, value, _step, detail, outcome)) => {
+                add_block_eta(value, &mut progress_text);
+                let overlay_label = bar_style.label_overlay.then(|| {
+                    let total = progress.as_ref().and_then(|p| p.done_at);
+                    let (label, column) = canvas::overlay_label(
+                        progress_rect.width,
+                        canvas::percentage(fraction, total, bar_style.adaptive_precision),
+                    );
+                    match column.filter(|column| *column > block_width(&progress_text) + 1) {
+                        Some(column) => Some((label, column)),
+                        None => {
+                            progress_text.push(' ');
+                            progress_text.push_str(&label);
+                            None
                         }
                     }
                 });
+                let text_width = block_width(&progress_text);
+                let glyphs = bar_style.glyphs.resolve(&canvas::BLOCKS);
+                let (bound, style, failed_bound) =
+                    match outcome {
+                        // Successful steps are followed by the failed ones, at the resolution of whole cells.
+                        Some((succeeded, failed)) => {
+                            let cells = |fraction: f32| {
+                                (canvas::fill(fraction, fill_mapping).clamp(0.0, 1.0) * progress_rect.width as f32)
+                                    .round() as u16
+                            };
+                            let (failed_bound, _) = draw_progress_bar_fn(
+                                buf,
+                                Rect {
+                                    width: cells(succeeded + failed),
+                                    ..progress_rect
+                                },
+                                1.0,
+                                glyphs,
+                                theme.bar_text,
+                                |_| theme.bar_failed,
+                            );
+                            let (bound, style) = draw_progress_bar_fn(
+                                buf,
+                                Rect {
+                                    width: cells(succeeded),
+                                    ..progress_rect
+                                },
+                                1.0,
+                                glyphs,
+                                theme.bar_text,
+                                |_| theme.bar_nearly_done,
+                            );
+                            (bound, style, Some(failed_bound))
+                        }
+                        None => {
+                            let fill = canvas::fill(fraction, fill_mapping);
+                            // The color reflects the true fraction, just like the text.
+                            let (bound, style) =
+                                draw_progress_bar_fn(buf, progress_rect, fill, glyphs, theme.bar_text, |_fill| {
+                                    match state {
+                                        progress::State::Blocked(_, _) => theme.bar_blocked,
+                                        progress::State::Halted(_, _) => theme.bar_halted,
+                                        progress::State::Running => depth_color.unwrap_or(theme.bar_color(fraction)),
+                                    }
+                                });
+                            (bound, style, None)
+                        }
+                    };
+                let failed_style = Style::default().bg(theme.bar_failed).fg(theme.bar_text);
                 let style_fn = move |_t: &str, x: u16, _y: u16| {
-                    if x < bound.right() { style } else { Style::default() }
+                    if x < bound.right() {
+                        style
+                    } else if failed_bound.is_some_and(|failed_bound| x < failed_bound.right()) {
+                        failed_style
+                    } else {
+                        Style::default()
+                    }
                 };
                 draw_text_nowrap_fn(progress_rect, buf, progress_text, style_fn);
+                if let Some((label, column)) = overlay_label.flatten() {
+                    draw_text_nowrap_fn(rect::offset_x(progress_rect, column), buf, label, style_fn);
+                }
+                draw_detail(buf, progress_rect, text_width, detail);
             }
-            Some((None, state, step)) => {
-                let mut progress_text = progress_text;
-                add_block_eta(state, &mut progress_text);
-                draw_text_with_ellipsis_nowrap(progress_rect, buf, progress_text, None);
+            Some((None, state, value, step, detail, _outcome)) => {
+                add_block_eta(value, &mut progress_text);
+                let text_width = draw_text_with_ellipsis_nowrap(progress_rect, buf, progress_text, None);
+                draw_detail(buf, progress_rect, text_width, detail);
                 let bar_rect = rect::offset_x(line_bound, max_progress_label_width as u16);
                 draw_spinner(
                     buf,
@@ -270,26 +688,31 @@ pub fn draw_progress(
                     step,
                     line,
                     match state {
-                        progress::State::Blocked(_, _) => Color::Red,
-                        progress::State::Halted(_, _) => Color::LightRed,
-                        progress::State::Running => Color::White,
+                        progress::State::Blocked(_, _) => theme.bar_blocked,
+                        progress::State::Halted(_, _) => theme.bar_halted,
+                        progress::State::Running => depth_color.unwrap_or(theme.spinner),
                     },
                 );
             }
             None => {
-                let bold = Style::default().add_modifier(Modifier::BOLD);
                 draw_text_nowrap_fn(progress_rect, buf, progress_text, |_, _, _| Style::default());
-                draw_text_with_ellipsis_nowrap(progress_rect, buf, format!(" {} ", title), bold);
+                draw_text_with_ellipsis_nowrap(progress_rect, buf, format!(" {} ", title), theme.group_name);
             }
         }
     }
 }
 
-fn add_block_eta(state: progress::State, progress_text: &mut String) {
+fn add_block_eta(value: &progress::Value, progress_text: &mut String) {
+    let state = value.state;
+    if let Some(since) = value.paused_since {
+        progress_text.push_str(" [");
+        progress_text.push_str(&canvas::format_stopped("paused", Some(since)));
+        progress_text.push(']');
+    }
     match state {
         progress::State::Blocked(reason, maybe_eta) | progress::State::Halted(reason, maybe_eta) => {
             progress_text.push_str(" [");
-            progress_text.push_str(reason);
+            progress_text.push_str(&canvas::format_stopped(reason, value.stopped_since));
             progress_text.push(']');
             if let Some(eta) = maybe_eta {
                 let eta = jiff::Timestamp::try_from(eta).expect("reasonable system time");
@@ -314,62 +737,264 @@ fn add_block_eta(state: progress::State, progress_text: &mut String) {
     }
 }
 
+fn draw_detail(buf: &mut Buffer, bound: Rect, text_width: u16, detail: Option<&str>) {
+    if let Some(detail) = detail {
+        draw_text_with_ellipsis_nowrap(
+            rect::offset_x(bound, text_width),
+            buf,
+            format!(" {detail} "),
+            Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+        );
+    }
+}
+
 fn draw_spinner(buf: &mut Buffer, bound: Rect, step: Step, seed: usize, color: Color) {
-    if bound.width == 0 {
+    canvas::spinner(
+        &mut BufferLine { buf, bound },
+        0,
+        bound.width,
+        step,
+        seed,
+        " ",
+        Style::default().bg(color),
+    );
+}
+
+/// Draw `text` dimmed at the end of `line_bound` and shrink it accordingly, unless it would take up half of the line.
+fn draw_dim_at_end(line_bound: &mut Rect, buf: &mut Buffer, text: String) {
+    let width = block_width(&text);
+    if line_bound.width > width * 2 {
+        line_bound.width -= width;
+        draw_text_with_ellipsis_nowrap(
+            Rect {
+                x: line_bound.right(),
+                width,
+                ..*line_bound
+            },
+            buf,
+            text,
+            Style::default().add_modifier(Modifier::DIM),
+        );
+    }
+}
+
+/// Draw the user-defined `columns` at the end of `line_bound` and shrink it accordingly, unless they would take up
+/// half of the line.
+///
+/// Tasks without `progress` serve as headings, and show the header of each column instead.
+fn draw_columns_at_end(
+    line_bound: &mut Rect,
+    buf: &mut Buffer,
+    columns: &[Arc<dyn Column + Send + Sync>],
+    key: &Key,
+    progress: Option<&Value>,
+) {
+    let width: u16 = columns.iter().map(|column| column.width() + 1).sum();
+    if columns.is_empty() || line_bound.width <= width * 2 {
         return;
     }
-    let x = bound.x + ((step + seed) % bound.width as usize) as u16;
-    let width = 5;
-    let bound = rect::intersect(Rect { x, width, ..bound }, bound);
-    tui_react::fill_background(bound, buf, color);
+    line_bound.width -= width;
+    let mut x = line_bound.right();
+    for column in columns {
+        let mut bound = Rect {
+            x: x + 1,
+            width: column.width(),
+            ..*line_bound
+        };
+        x += column.width() + 1;
+        match progress {
+            Some(progress) => {
+                for token in column.render(key, progress) {
+                    if bound.width == 0 {
+                        break;
+                    }
+                    let drawn =
+                        draw_text_with_ellipsis_nowrap(bound, buf, token.as_str(), ansi_style(token.style_ref()))
+                            .min(bound.width);
+                    bound.x += drawn;
+                    bound.width -= drawn;
+                }
+            }
+            None => {
+                draw_text_with_ellipsis_nowrap(
+                    bound,
+                    buf,
+                    column.header(),
+                    Style::default().add_modifier(Modifier::DIM),
+                );
+            }
+        }
+    }
+}
+
+/// Translate the `style` of text rendered by a [`Column`] for the terminal into one for the terminal user interface.
+fn ansi_style(style: &crosstermion::nu_ansi_term::Style) -> Style {
+    let mut out = Style::default();
+    if let Some(color) = style.foreground {
+        out = out.fg(ansi_color(color));
+    }
+    if let Some(color) = style.background {
+        out = out.bg(ansi_color(color));
+    }
+    for (enabled, modifier) in [
+        (style.is_bold, Modifier::BOLD),
+        (style.is_dimmed, Modifier::DIM),
+        (style.is_italic, Modifier::ITALIC),
+        (style.is_underline, Modifier::UNDERLINED),
+        (style.is_blink, Modifier::SLOW_BLINK),
+        (style.is_reverse, Modifier::REVERSED),
+        (style.is_hidden, Modifier::HIDDEN),
+        (style.is_strikethrough, Modifier::CROSSED_OUT),
+    ] {
+        if enabled {
+            out = out.add_modifier(modifier);
+        }
+    }
+    out
+}
+
+fn ansi_color(color: crosstermion::nu_ansi_term::Color) -> Color {
+    use crosstermion::nu_ansi_term::Color as Ansi;
+    match color {
+        Ansi::Black => Color::Black,
+        Ansi::DarkGray => Color::DarkGray,
+        Ansi::Red => Color::Red,
+        Ansi::LightRed => Color::LightRed,
+        Ansi::Green => Color::Green,
+        Ansi::LightGreen => Color::LightGreen,
+        Ansi::Yellow => Color::Yellow,
+        Ansi::LightYellow => Color::LightYellow,
+        Ansi::Blue => Color::Blue,
+        Ansi::LightBlue => Color::LightBlue,
+        Ansi::Purple | Ansi::Magenta => Color::Magenta,
+        Ansi::LightPurple | Ansi::LightMagenta => Color::LightMagenta,
+        Ansi::Cyan => Color::Cyan,
+        Ansi::LightCyan => Color::LightCyan,
+        Ansi::White => Color::Gray,
+        Ansi::LightGray => Color::White,
+        Ansi::Fixed(index) => Color::Indexed(index),
+        Ansi::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        Ansi::Default => Color::Reset,
+    }
 }
 
 fn draw_progress_bar_fn(
     buf: &mut Buffer,
     bound: Rect,
     fraction: f32,
+    glyphs: &canvas::Glyphs,
+    text_color: Color,
     style: impl FnOnce(f32) -> Color,
 ) -> (Rect, Style) {
     if bound.width == 0 {
         return (Rect::default(), Style::default());
     }
-    let mut fractional_progress_rect = Rect {
-        width: ((bound.width as f32 * fraction).floor() as u16).min(bound.width),
-        ..bound
-    };
     let color = style(fraction);
-    for y in fractional_progress_rect.top()..fractional_progress_rect.bottom() {
-        for x in fractional_progress_rect.left()..fractional_progress_rect.right() {
-            let cell = &mut buf[(x, y)];
-            cell.set_fg(color);
-            cell.set_symbol(tui::symbols::block::FULL);
-        }
+    let filled_width = canvas::bar(
+        &mut BufferLine { buf, bound },
+        0,
+        bound.width,
+        fraction,
+        glyphs,
+        Style::default().fg(color),
+        None,
+    );
+    (
+        Rect {
+            width: filled_width,
+            ..bound
+        },
+        Style::default().bg(color).fg(text_color),
+    )
+}
+
+/// Return `entries` without the descendants of all tasks whose keys are in `folded`, while keeping these tasks.
+/// Return a copy of `entries` with siblings sorted by `order`, using `throughput` to rank them by their throughput.
+fn by_sort_order(entries: &[(Key, Task)], order: SortOrder, throughput: Option<&Throughput>) -> Vec<(Key, Task)> {
+    let step = |task: &Task| task.progress.as_ref().map(|p| p.step.load(Ordering::Relaxed));
+    match order {
+        SortOrder::Tree => entries.to_vec(),
+        SortOrder::Name => order::by_rank(entries, |_key, task| task.name.clone()),
+        SortOrder::StartTime => order::by_rank(entries, |_key, task| {
+            let started_at = task.progress.as_ref().and_then(|p| p.started_at);
+            (started_at.is_none(), started_at)
+        }),
+        SortOrder::Remaining => order::by_rank(entries, |_key, task| {
+            let remaining = task
+                .progress
+                .as_ref()
+                .and_then(|p| p.done_at)
+                .zip(step(task))
+                .map(|(done_at, step)| done_at.saturating_sub(step));
+            (remaining.is_none(), std::cmp::Reverse(remaining))
+        }),
+        SortOrder::Throughput => order::by_rank(entries, |key, _task| {
+            let current = throughput.and_then(|tp| tp.history(key).last().copied());
+            (current.is_none(), std::cmp::Reverse(current))
+        }),
     }
-    if fractional_progress_rect.width < bound.width {
-        static BLOCK_SECTIONS: [&str; 9] = [
-            " ",
-            tui::symbols::block::ONE_EIGHTH,
-            tui::symbols::block::ONE_QUARTER,
-            tui::symbols::block::THREE_EIGHTHS,
-            tui::symbols::block::HALF,
-            tui::symbols::block::FIVE_EIGHTHS,
-            tui::symbols::block::THREE_QUARTERS,
-            tui::symbols::block::SEVEN_EIGHTHS,
-            tui::symbols::block::FULL,
-        ];
-        // Get the index based on how filled the remaining part is
-        let index = ((((bound.width as f32 * fraction) - fractional_progress_rect.width as f32) * 8f32).round()
-            as usize)
-            % BLOCK_SECTIONS.len();
-        let cell = &mut buf[(fractional_progress_rect.right(), bound.y)];
-        cell.set_symbol(BLOCK_SECTIONS[index]);
-        cell.set_fg(color);
-        fractional_progress_rect.width += 1;
+}
+
+fn without_folded(entries: &[(Key, Task)], folded: &HashSet<Key>) -> Vec<(Key, Task)> {
+    let mut folded_at: Option<Key> = None;
+    entries
+        .iter()
+        .filter(|(key, _task)| {
+            if let Some(parent) = folded_at {
+                if key.level() > parent.level() && key.shares_parent_with(&parent, parent.level()) {
+                    return false;
+                }
+                folded_at = None;
+            }
+            if folded.contains(key) {
+                folded_at = Some(*key);
+            }
+            true
+        })
+        .cloned()
+        .collect()
+}
+
+/// Return the number of each task among all tasks of the same name in the order of their keys, keyed by the task's key,
+/// with tasks of unique names left out.
+///
+/// `sorted_entries` is the whole snapshot, so numbers stay the same no matter how tasks are ordered, filtered or folded.
+fn duplicate_name_numbers(sorted_entries: &[(Key, Task)]) -> HashMap<Key, usize> {
+    let mut count_by_name = HashMap::<&str, usize>::new();
+    for (_key, task) in sorted_entries {
+        *count_by_name.entry(task.name.as_str()).or_default() += 1;
     }
-    (fractional_progress_rect, Style::default().bg(color).fg(Color::Black))
+    let mut seen_by_name = HashMap::<&str, usize>::new();
+    sorted_entries
+        .iter()
+        .filter(|(_key, task)| count_by_name[task.name.as_str()] > 1)
+        .map(|(key, task)| {
+            let seen = seen_by_name.entry(task.name.as_str()).or_default();
+            *seen += 1;
+            (*key, *seen)
+        })
+        .collect()
 }
 
-pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset: u16) -> u16 {
+pub fn draw_tree(
+    entries: &[(Key, Task)],
+    sorted_entries: &[(Key, Task)],
+    buf: &mut Buffer,
+    bound: Rect,
+    state: &State,
+) -> u16 {
+    let State {
+        task_offset: offset,
+        layout,
+        show_child_count,
+        ref folded,
+        disambiguate_duplicate_names,
+        name_abbrev,
+        ref theme,
+        ref critical_path,
+        ..
+    } = *state;
+    let duplicate_numbers = disambiguate_duplicate_names.then(|| duplicate_name_numbers(sorted_entries));
     let mut max_prefix_len = 0;
     for (line, (entry_index, entry)) in entries
         .iter()
@@ -381,14 +1006,44 @@ pub fn draw_tree(entries: &[(Key, Task)], buf: &mut Buffer, bound: Rect, offset:
         let mut line_bound = rect::line_bound(bound, line);
         line_bound.x = line_bound.x.saturating_sub(1);
         line_bound.width = line_bound.width.saturating_sub(1);
-        let tree_prefix = format!("{} {} ", level_prefix(entries, entry_index), entry.1.name);
-        max_prefix_len = max_prefix_len.max(block_width(&tree_prefix));
+        let level_prefix = match layout {
+            Layout::Tree => level_prefix(entries, entry_index),
+            // Sections aren't nested, so only tasks are indented below their section header.
+            Layout::Sections if entry.1.progress.is_some() => " ".into(),
+            Layout::Sections => String::new(),
+        };
+        let mut suffix = String::new();
+        if let Some(number) = duplicate_numbers.as_ref().and_then(|numbers| numbers.get(&entry.0)) {
+            use std::fmt::Write;
+            write!(suffix, "#{number} ").expect("in-memory writes never fail");
+        }
+        if layout == Layout::Tree && folded.contains(&entry.0) {
+            suffix.push_str("▸ ");
+        }
+        if show_child_count && layout == Layout::Tree {
+            let num_children = Key::num_children(entries, entry_index);
+            if num_children > 0 {
+                use std::fmt::Write;
+                write!(suffix, "({num_children}) ").expect("in-memory writes never fail");
+            }
+        }
+        let surrounding_width = block_width(&level_prefix) + block_width(&suffix) + 2;
+        // The column grows to fit the whole name, even if it has to be shortened for now.
+        max_prefix_len = max_prefix_len.max(surrounding_width + block_width(&entry.1.name));
+        let name = name_abbrev.abbreviate(
+            &entry.1.name,
+            line_bound.width.saturating_sub(surrounding_width) as usize,
+        );
+        let tree_prefix = format!("{level_prefix} {name} {suffix}");
 
-        let style = if entry.1.progress.is_none() {
-            Style::default().add_modifier(Modifier::BOLD).into()
+        let mut style = if entry.1.progress.is_none() {
+            theme.group_name
         } else {
-            None
+            theme.task_name
         };
+        if critical_path.as_ref().is_some_and(|path| path.contains(&entry.0)) {
+            style = style.patch(theme.critical_path);
+        }
         draw_text_with_ellipsis_nowrap(line_bound, buf, tree_prefix, style);
     }
     max_prefix_len
@@ -454,6 +1109,7 @@ pub fn draw_overflow(
     label_offset: u16,
     num_entries_on_display: u16,
     offset: u16,
+    theme: &Theme,
 ) {
     let (count, mut progress_fraction) = entries
         .iter()
@@ -472,9 +1128,12 @@ pub fn draw_overflow(
             .len()
             .saturating_sub((offset + num_entries_on_display + 1) as usize)
     );
-    let (progress_rect, style) = draw_progress_bar_fn(buf, bound, progress_fraction, |_| Color::Green);
+    let (progress_rect, style) =
+        draw_progress_bar_fn(buf, bound, progress_fraction, &canvas::BLOCKS, theme.bar_text, |_| {
+            theme.bar_nearly_done
+        });
 
-    let bg_color = Color::Red;
+    let bg_color = theme.bar_failed;
     fill_background(rect::offset_x(bound, progress_rect.right() - 1), buf, bg_color);
     let color_text_according_to_progress = move |_g: &str, x: u16, _y: u16| {
         if x < progress_rect.right() {
@@ -0,0 +1,52 @@
+use crate::progress::{self, Key, Task};
+
+/// Return all tasks of `entries` that have progress of their own, grouped into sections of failed, running and
+/// completed tasks in that order, each preceded by a header with its name and the amount of tasks in it.
+///
+/// Tasks keep their relative order within a section, completed tasks are only counted, and empty sections are omitted.
+pub(crate) fn by_state(entries: &[(Key, Task)]) -> Vec<(Key, Task)> {
+    let mut failed = Vec::new();
+    let mut running = Vec::new();
+    let mut completed = 0;
+    for (key, task) in entries {
+        let Some(progress) = task.progress.as_ref().filter(|progress| !progress.aggregated) else {
+            continue;
+        };
+        if let progress::State::Halted(_, _) = progress.state {
+            failed.push((*key, task.clone()));
+        } else if progress.is_done() {
+            completed += 1;
+        } else {
+            running.push((*key, task.clone()));
+        }
+    }
+
+    let mut out = Vec::with_capacity(failed.len() + running.len() + 3);
+    for (index, (title, count, tasks)) in [
+        ("Failed", failed.len(), failed),
+        ("Running", running.len(), running),
+        ("Completed", completed, Vec::new()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if count == 0 {
+            continue;
+        }
+        out.push((
+            header_key(index as u16),
+            Task {
+                name: format!("{title} ({count})"),
+                ..Task::default()
+            },
+        ));
+        out.extend(tasks);
+    }
+    out
+}
+
+/// Return the key of the header of the section at `index`, which differs for each section so rows can be told apart by
+/// their key, and from the key of the row summarizing [finished tasks](crate::render::FinishedTasks::Summarize).
+fn header_key(index: u16) -> Key {
+    Key::default().add_child(u16::MAX - 1 - index)
+}
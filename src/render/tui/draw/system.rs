@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+
+use crate::render::tui::Line;
+
+/// The least amount of time between two samples, to keep the CPU usage steady enough to be readable.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A utility to sample the CPU and memory usage of this process, and optionally the load average of the system,
+/// to show them in the information pane.
+///
+/// Statistics are only available on Linux, and nothing is shown elsewhere.
+pub struct SystemStats {
+    load_average: bool,
+    previous_sample: Option<(Instant, Duration)>,
+    lines: Vec<Line>,
+}
+
+impl SystemStats {
+    /// Create a new instance which also samples the load average of the system if `load_average` is true.
+    pub fn new(load_average: bool) -> Self {
+        SystemStats {
+            load_average,
+            previous_sample: None,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Return `information` followed by the latest statistics, sampling them again if the last sample is old enough.
+    pub fn information(&mut self, information: &[Line]) -> Vec<Line> {
+        let now = Instant::now();
+        if self
+            .previous_sample
+            .is_none_or(|(sampled_at, _)| now.saturating_duration_since(sampled_at) >= SAMPLE_INTERVAL)
+        {
+            self.sample(now);
+        }
+        information.iter().chain(self.lines.iter()).cloned().collect()
+    }
+
+    fn sample(&mut self, now: Instant) {
+        self.lines.clear();
+        let cpu_time = sys::cpu_time();
+        if let (Some((sampled_at, previous_cpu_time)), Some(cpu_time)) = (self.previous_sample, cpu_time) {
+            let elapsed = now.saturating_duration_since(sampled_at).as_secs_f64();
+            if elapsed > 0.0 {
+                let usage = cpu_time.saturating_sub(previous_cpu_time).as_secs_f64() / elapsed * 100.0;
+                self.lines.push(Line::Text(format!("CPU {usage:.1}%")));
+            }
+        }
+        self.previous_sample = cpu_time.map(|cpu_time| (now, cpu_time));
+        if let Some(bytes) = sys::resident_memory() {
+            self.lines.push(Line::Text(format!(
+                "Memory {:.1} MiB",
+                bytes as f64 / (1024.0 * 1024.0)
+            )));
+        }
+        if let Some(load) = self.load_average.then(sys::load_average).flatten() {
+            self.lines.push(Line::Text(format!("Load {load}")));
+        }
+        if !self.lines.is_empty() {
+            self.lines.insert(0, Line::Title("System".into()));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::time::Duration;
+
+    /// The frequency at which the kernel reports times in `/proc`, which is fixed for its interface.
+    const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+    /// Return the time this process spent on the CPU in user and kernel mode.
+    pub fn cpu_time() -> Option<Duration> {
+        let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+        // The name of the executable is in parentheses and may contain spaces, so skip past it.
+        let mut fields = stat.get(stat.rfind(')')? + 1..)?.split_whitespace();
+        let user_ticks: u64 = fields.nth(11)?.parse().ok()?;
+        let system_ticks: u64 = fields.next()?.parse().ok()?;
+        Some(Duration::from_millis(
+            (user_ticks + system_ticks) * 1000 / CLOCK_TICKS_PER_SECOND,
+        ))
+    }
+
+    /// Return the amount of bytes of memory this process currently holds.
+    pub fn resident_memory() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let kilobytes: u64 = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))?
+            .trim()
+            .strip_suffix("kB")?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(kilobytes * 1024)
+    }
+
+    /// Return the load average of the system over the last 1, 5 and 15 minutes, as in `0.52 0.40 0.33`.
+    pub fn load_average() -> Option<String> {
+        let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+        let averages: Vec<_> = loadavg.split_whitespace().take(3).collect();
+        (averages.len() == 3).then(|| averages.join(" "))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::time::Duration;
+
+    pub fn cpu_time() -> Option<Duration> {
+        None
+    }
+
+    pub fn resident_memory() -> Option<u64> {
+        None
+    }
+
+    pub fn load_average() -> Option<String> {
+        None
+    }
+}
@@ -1,14 +1,41 @@
 use std::{
-    io::{self, Write},
-    time::Duration,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 use futures_lite::StreamExt;
-use tui::layout::Rect;
+use tui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+};
 
+#[cfg(feature = "render-tui-notify")]
+use crate::render::tui::{NotifyTrigger, notify::Notifier};
 use crate::{
     Root, Throughput, WeakRoot,
-    render::tui::{draw, ticker},
+    messages::MessageLevel,
+    progress,
+    render::{
+        BarStyle, Column, FinishedTasks, NameAbbrev, Profile,
+        last_update::LastUpdate,
+        message_log::MessageLog,
+        tui::{
+            Action, KeyBindings, Theme, draw,
+            record::Recorder,
+            ticker,
+            utils::{
+                RestoreTerminalOnPanic, SuspendSignal, pop_terminal_title, push_terminal_title, suspend,
+                terminal_event_stream,
+            },
+            widget::buffer_to_string,
+        },
+    },
+    time::{TimestampFormat, Timezone},
 };
 
 /// Configure the terminal user interface
@@ -26,11 +53,76 @@ pub struct Options {
 
     /// If true, (default false), we will keep track of the previous progress state to derive
     /// continuous throughput information from. Throughput will only show for units which have
-    /// explicitly enabled it, it is opt-in.
+    /// explicitly enabled it, it is opt-in, like with [`unit::display::Mode::with_throughput()`](crate::unit::display::Mode::with_throughput()).
+    /// It is shown as amount per second next to the progress of each task, as in `14.2 MB/s`.
     ///
     /// This comes at the cost of additional memory and CPU time.
     pub throughput: bool,
 
+    /// User-defined columns to draw at the end of each row, in order, after all built-in ones (default: none).
+    ///
+    /// These are the same columns the line renderer draws, and are left out if the window is too narrow for them.
+    pub columns: Vec<Arc<dyn Column + Send + Sync>>,
+
+    /// If true (default: false), show whether the throughput of tasks is increasing (`▲`), decreasing (`▼`) or
+    /// steady (`▬`) next to it.
+    ///
+    /// Has no effect unless `throughput` is enabled.
+    pub show_trend: bool,
+
+    /// If true (default: false), show a sparkline of the throughput of each task over the last seconds next to it,
+    /// as in `▂▃▅▇▆▃▁`, to see whether it's degrading over time.
+    ///
+    /// Has no effect unless `throughput` is enabled.
+    pub show_sparkline: bool,
+
+    /// If true (default: false), show the estimated time until each bounded task is done next to its progress,
+    /// as in `~1m 5s left`.
+    ///
+    /// See [`Throughput::eta()`](crate::Throughput::eta()) for how it is computed.
+    /// Has no effect unless `throughput` is enabled.
+    pub show_eta: bool,
+
+    /// If true (default: false), show how long each task has been running at the end of its line, as in `2m 13s`.
+    pub show_elapsed: bool,
+
+    /// If true, (default false), the estimated time until all tasks are done is shown in the headline.
+    ///
+    /// See [`progress::overall_eta()`](crate::progress::overall_eta()) for how it is computed. Each estimate is
+    /// blended with the previous one to avoid jumps as tasks are added and removed.
+    pub show_overall_eta: bool,
+
+    /// If true (default: false), a single bar pinned to the top of the task pane shows how much of all work is done,
+    /// along with the percentage and the estimated time until all tasks are done, if available.
+    ///
+    /// See [`progress::overall_fraction()`](crate::progress::overall_fraction()) for how it is computed.
+    /// If there are only unbounded tasks, a spinner is shown instead.
+    pub show_global_bar: bool,
+
+    /// If true (default: false), the headline shows how many tasks are done out of all tasks, as in `17/42 tasks done`.
+    ///
+    /// Only tasks with progress count, and those are done once a bounded task reached its last step,
+    /// independently of how many steps it has.
+    pub show_task_fraction: bool,
+
+    /// If true, (default false), a dot in front of each task indicates how recently and how frequently it made progress,
+    /// glowing hot for busy tasks and cooling down smoothly once they are idle.
+    pub show_activity: bool,
+
+    /// The amount of frames after which to query the size of the terminal again (default: 1, every frame).
+    ///
+    /// Querying the size is a system call on most platforms, so higher values reduce the cost of drawing a frame at high
+    /// frame rates, at the cost of reacting to resizes later. The size is queried right away after
+    /// [`Event::SetWindowSize`] or [`Event::RefreshWindowSize`].
+    pub size_query_interval: usize,
+
+    /// If true (default: false), frames are only drawn if the progress tree changed since the last one, as determined
+    /// by [`Root::changes()`][crate::Root::changes()], or if there was input, which makes idle frames almost free.
+    ///
+    /// Note that information which changes with time alone, like elapsed time, throughput and the estimated time
+    /// until tasks are done, isn't updated while nothing changes, which also delays `collapse_when_idle`.
+    pub redraw_only_on_state_change: bool,
+
     /// If set, recompute the column width of the task tree only every given frame. Otherwise the width will be recomputed every frame.
     ///
     /// Use this if there are many short-running tasks with varying names paired with high refresh rates of multiple frames per second to
@@ -44,23 +136,353 @@ pub struct Options {
     /// If unset, it will be retrieved from the current terminal.
     pub window_size: Option<Rect>,
 
+    /// If set (default: unset), the TUI collapses into a single summary line once no progress was made for the given
+    /// duration, and expands back to its previous layout as soon as progress changes again.
+    pub collapse_when_idle: Option<Duration>,
+
     /// If true (default: true), we will stop running the TUI once the progress isn't available anymore (went out of scope).
     pub stop_if_progress_missing: bool,
+
+    /// If true (default: true), a panic hook shows the cursor, leaves the alternate screen and disables raw mode while
+    /// the TUI is running, before the panic message is printed. Otherwise the message may be lost and the terminal left
+    /// unusable.
+    ///
+    /// The hook is installed once per process, calling the previous hook afterwards, and does nothing while no TUI runs.
+    /// As it can't reach the writer the TUI draws to, it always restores the terminal by writing to `stdout`, so a TUI
+    /// drawing to another terminal, like the one behind `stderr` if `stdout` is redirected, stays in the alternate screen.
+    pub restore_terminal_on_panic: bool,
+
+    /// If true (default: false), the title of the terminal window or tab is set to the [title](Options::title) of the
+    /// dashboard followed by the overall percentage of completion, as in `build · 42%`, while the TUI is running.
+    ///
+    /// The previous title is restored afterwards if the terminal supports it.
+    pub set_terminal_title: bool,
+
+    /// If true (default: false), the CPU and memory usage of this process are shown in the information pane,
+    /// sampled at most once a second when a frame is drawn.
+    ///
+    /// The statistics are only available on Linux, and nothing is shown elsewhere.
+    pub show_system_stats: bool,
+
+    /// If true (default: false), the load average of the system is shown along with the CPU and memory usage.
+    ///
+    /// Has no effect unless `show_system_stats` is enabled.
+    pub show_load_average: bool,
+
+    /// If true (default: false), the amount of direct children is shown next to the name of each task that has any,
+    /// as in `task (12)`.
+    pub show_child_count: bool,
+
+    /// If true (default: false), show the time at which each task last made progress at the end of its row.
+    ///
+    /// The time is formatted like message timestamps, with the configured `timestamp_format` and `timezone`.
+    pub show_last_update: bool,
+
+    /// If true (default: false), tasks sharing their name with other tasks get a number appended to it, as in `download #2`.
+    ///
+    /// Numbers are assigned in the order tasks were added, so they remain stable while tasks progress and no matter
+    /// how tasks are sorted, filtered or folded.
+    pub disambiguate_duplicate_names: bool,
+
+    /// If true (default: false), the names of the tasks on the [critical path][crate::progress::critical_path()] are
+    /// drawn with the [`critical_path`](Theme::critical_path) style of the theme, to show which tasks hold up completion
+    /// the most. It's updated whenever the tree changes other than by steps.
+    pub highlight_critical_path: bool,
+
+    /// If set (default: `None`), tasks are shown in the order of their names in this list, instead of the order in
+    /// which they were added. Tasks not in the list follow in their natural order, and each task remains below its parent.
+    pub explicit_order: Option<Vec<String>>,
+
+    /// The way tasks are arranged in the task pane (default: [`Layout::Tree`]).
+    pub layout: Layout,
+
+    /// How tasks that are done are shown in the task pane (default: [`FinishedTasks::Show`]).
+    pub finished_tasks: FinishedTasks,
+
+    /// If true (default: false), only ASCII characters are drawn, replacing block elements of bars, box-drawing
+    /// characters of borders and tree lines and other symbols with characters like `#`, `-`, `|` and `+`.
+    ///
+    /// Useful for terminals lacking the glyphs, and for capturing the output with tools that mangle them.
+    pub ascii: bool,
+
+    /// The order of sibling tasks in the task pane (default: [`SortOrder::Tree`]).
+    ///
+    /// It can be cycled through with the `s` key, or set with [`Event::SetSortOrder`].
+    pub sort_order: SortOrder,
+
+    /// If set (default: `None`), runs of consecutive messages from the same origin with more than the given amount of
+    /// messages are collapsed into a single line showing their count.
+    ///
+    /// The group in the topmost row of the messages pane can be expanded or collapsed with the `g` key.
+    pub collapse_message_groups_above: Option<usize>,
+
+    /// If set (default: `None`), only messages at least as severe as the given level are shown in the messages pane,
+    /// as determined by [`MessageLevel::severity()`].
+    pub message_level: Option<MessageLevel>,
+
+    /// If true (default: false), the [fields][crate::messages::Message::fields] of messages are shown below them
+    /// in the messages pane, otherwise only their amount is shown. They can be toggled with the `F` key.
+    pub show_message_fields: bool,
+
+    /// If true (default: true), the messages pane shows the newest message at the top as messages arrive. Otherwise,
+    /// or once the messages pane is scrolled, it keeps showing the same messages until following is enabled again
+    /// with the `t` key.
+    pub follow_messages: bool,
+
+    /// If true (default: false), the task pane scrolls down to show newly added tasks as long as it's scrolled
+    /// to the bottom, like `tail -f` does. Scrolling up pauses following until the bottom is reached again.
+    pub follow_new_tasks: bool,
+
+    /// If true (default: false), a line at the bottom of the task pane shows how tasks are currently ordered and
+    /// filtered. It can be toggled with the `l` key.
+    pub show_legend: bool,
+
+    /// If set (default: `None`), only up to the given amount of the newest messages are considered for the messages
+    /// pane each frame, and older ones can't be scrolled to.
+    ///
+    /// Only messages in the visible rows are formatted, but finding them still takes time proportional to the amount
+    /// of messages, which this limits for very large message buffers.
+    pub max_messages_per_frame: Option<usize>,
+
+    /// If true (default: false), a narrow strip at the right of the task pane shows all tasks compressed to its height,
+    /// colored by their state, with the rows of the tasks currently in view highlighted.
+    ///
+    /// With [`mouse`](Options::mouse) support, clicking a row of it shows the tasks it stands for, and scrolling over it
+    /// scrolls by as many tasks as each row stands for.
+    pub show_minimap: bool,
+
+    /// If true (default: false), capture the mouse to scroll the task and messages panes with the wheel, fold the children
+    /// of a task by clicking it, and toggle the size of the messages or information pane by clicking it.
+    ///
+    /// Note that while the mouse is captured, text can't be selected in most terminals.
+    pub mouse: bool,
+
+    /// The keys to press for each action (default: [`KeyBindings::default()`]), like `j` to scroll the task pane down.
+    pub key_bindings: KeyBindings,
+
+    /// The colors to use when drawing (default: [`Theme::dark()`]), use [`Theme::light()`] for terminals with a light background.
+    pub theme: Theme,
+
+    /// Configure how progress bars are drawn.
+    pub bar_style: BarStyle,
+
+    /// If set (default: `None`), the fraction of completion of each task is passed through this function to determine
+    /// how much of its bar to fill, while the percentage shown remains the true fraction.
+    ///
+    /// Use it for work that doesn't progress linearly. The function must be monotonic and map `[0, 1]` onto `[0, 1]`.
+    pub fill_mapping: Option<fn(f32) -> f32>,
+
+    /// The way task names that don't fit into the tree column are shortened (default: [`NameAbbrev::TruncateEnd`]).
+    pub name_abbrev: NameAbbrev,
+
+    /// The timezone in which to display the time of messages and the current time
+    /// (default: [`Timezone::default()`]).
+    ///
+    /// Set it to [`Timezone::from_env()`] to let users choose it with the `PRODASH_TIMEZONE` environment variable.
+    pub timezone: Timezone,
+
+    /// The way the time of messages and the time at which tasks last made progress are shown
+    /// (default: [`TimestampFormat::Time`]).
+    pub timestamp_format: TimestampFormat,
+
+    /// If set (default: `None`), all events received by the user interface, like key presses, are written to a file
+    /// at the given path, one JSON object per line, for later use with [`replay_events()`][crate::render::tui::replay_events()].
+    pub record_events: Option<PathBuf>,
+
+    /// If set (default: `None`), all messages are written to a file at the given path as they arrive, one per line,
+    /// to keep the full history of messages for later inspection, including those that don't fit into the ring buffer
+    /// anymore. The file is complete once the user interface stops.
+    ///
+    /// Note that messages sent faster than frames are drawn may be overwritten in the ring buffer before they are seen.
+    pub message_log: Option<PathBuf>,
+
+    /// If set (default: `None`), called with the buffer and the area of the window after each frame was drawn and before
+    /// it's shown, to allow drawing custom content like banners on top of it.
+    ///
+    /// As the [`Dashboard`][crate::render::tui::Dashboard] widget is drawn by the application, it doesn't use it.
+    pub post_draw: Option<PostDraw>,
+
+    /// If set (default: `None`), reserve space at the bottom of the window for a widget drawn by the application
+    /// each frame, like a custom gauge, see [`CustomPane`].
+    pub custom_pane: Option<CustomPane>,
+
+    /// Determines when to show a desktop notification summarizing the progress (default: [`NotifyTrigger::Never`]),
+    /// for example once all tasks are done.
+    ///
+    /// Each kind of notification is shown at most once, and nothing happens if notifications aren't available.
+    #[cfg(feature = "render-tui-notify")]
+    pub notify_on: NotifyTrigger,
 }
 
+/// A function to draw on top of each frame, see [`Options::post_draw`].
+pub type PostDraw = Arc<dyn Fn(&mut Buffer, Rect) + Send + Sync>;
+
 impl Default for Options {
     fn default() -> Self {
         Options {
             title: "Progress Dashboard".into(),
             frames_per_second: 10.0,
             throughput: false,
+            columns: Vec::new(),
+            show_trend: false,
+            show_sparkline: false,
+            show_eta: false,
+            show_elapsed: false,
+            show_overall_eta: false,
+            show_global_bar: false,
+            show_task_fraction: false,
+            show_activity: false,
+            size_query_interval: 1,
+            redraw_only_on_state_change: false,
             recompute_column_width_every_nth_frame: None,
             window_size: None,
+            collapse_when_idle: None,
             stop_if_progress_missing: true,
+            restore_terminal_on_panic: true,
+            set_terminal_title: false,
+            show_system_stats: false,
+            show_load_average: false,
+            show_child_count: false,
+            show_last_update: false,
+            disambiguate_duplicate_names: false,
+            highlight_critical_path: false,
+            explicit_order: None,
+            layout: Layout::default(),
+            finished_tasks: FinishedTasks::default(),
+            ascii: false,
+            sort_order: SortOrder::default(),
+            collapse_message_groups_above: None,
+            message_level: None,
+            show_message_fields: false,
+            follow_messages: true,
+            follow_new_tasks: false,
+            show_legend: false,
+            max_messages_per_frame: None,
+            show_minimap: false,
+            mouse: false,
+            key_bindings: KeyBindings::default(),
+            theme: Theme::default(),
+            bar_style: BarStyle::default(),
+            fill_mapping: None,
+            name_abbrev: NameAbbrev::default(),
+            timezone: Timezone::default(),
+            timestamp_format: TimestampFormat::default(),
+            record_events: None,
+            message_log: None,
+            post_draw: None,
+            custom_pane: None,
+            #[cfg(feature = "render-tui-notify")]
+            notify_on: NotifyTrigger::default(),
+        }
+    }
+}
+
+impl Options {
+    /// Create options bundled as `profile`:
+    ///
+    /// * [`Profile::Ci`] draws once per second and shows the estimated time until all is done.
+    /// * [`Profile::Minimal`] collapses into a single line when idle and collapses runs of messages.
+    /// * [`Profile::Detailed`] shows everything available, like throughput, its trend, sparkline and the time left,
+    ///   activity, how long tasks are running and when they last made progress, an overall bar, the amount of tasks done and percentages on bars.
+    pub fn from_profile(profile: Profile) -> Self {
+        let default = Options::default();
+        match profile {
+            Profile::Default => default,
+            Profile::Ci => Options {
+                frames_per_second: 1.0,
+                show_overall_eta: true,
+                ..default
+            },
+            Profile::Minimal => Options {
+                collapse_when_idle: Some(Duration::from_secs(5)),
+                collapse_message_groups_above: Some(3),
+                ..default
+            },
+            Profile::Detailed => Options {
+                throughput: true,
+                show_trend: true,
+                show_sparkline: true,
+                show_eta: true,
+                show_elapsed: true,
+                show_overall_eta: true,
+                show_global_bar: true,
+                show_task_fraction: true,
+                show_activity: true,
+                show_child_count: true,
+                show_last_update: true,
+                show_legend: true,
+                bar_style: BarStyle {
+                    label_overlay: true,
+                    adaptive_precision: true,
+                    ..BarStyle::default()
+                },
+                ..default
+            },
         }
     }
+
+    /// Create options bundled as the profile named in the environment, see [`Profile::from_env()`].
+    pub fn from_env_profile() -> Self {
+        Options::from_profile(Profile::from_env())
+    }
+}
+
+/// The way tasks are arranged in the task pane, see [`Options::layout`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Layout {
+    /// Show all tasks in the hierarchy they were added in.
+    #[default]
+    Tree,
+    /// Show tasks grouped into sections of failed, running and completed tasks, each with a header showing the amount
+    /// of tasks in it. Groups are not shown, and completed tasks are only counted.
+    ///
+    /// Empty sections are omitted.
+    Sections,
 }
 
+/// The order of sibling tasks in the task pane, see [`Options::sort_order`].
+///
+/// Each task remains placed below its parent, and tasks that can't be ranked follow all others in their natural order.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    /// Show tasks in the order they were added, or in the [explicit order](Options::explicit_order) if set.
+    #[default]
+    Tree,
+    /// Show tasks ordered by their name.
+    Name,
+    /// Show tasks that started first at the top.
+    StartTime,
+    /// Show tasks with the most remaining steps at the top.
+    Remaining,
+    /// Show tasks with the highest throughput at the top, which requires [`Options::throughput`].
+    Throughput,
+}
+
+impl SortOrder {
+    /// Return the order following this one, wrapping around, as used when cycling through them by key.
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::Tree => SortOrder::Name,
+            SortOrder::Name => SortOrder::StartTime,
+            SortOrder::StartTime => SortOrder::Remaining,
+            SortOrder::Remaining => SortOrder::Throughput,
+            SortOrder::Throughput => SortOrder::Tree,
+        }
+    }
+}
+
+/// A region at the bottom of the window, below tasks and messages, for the application to draw into, see [`Options::custom_pane`].
+#[derive(Clone)]
+pub struct CustomPane {
+    /// The amount of lines to reserve, which are only given if at least two lines remain for tasks.
+    pub height: u16,
+    /// Called each frame with the buffer, the reserved region and the sorted snapshot of all tasks the frame shows.
+    pub draw: DrawCustomPane,
+}
+
+/// A function to draw a [`CustomPane`] with, see [`CustomPane::draw`].
+pub type DrawCustomPane = Arc<dyn Fn(&mut Buffer, Rect, &[(progress::Key, progress::Task)]) + Send + Sync>;
+
 /// A line as used in [`Event::SetInformation`](./enum.Event.html#variant.SetInformation)
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Line {
@@ -92,9 +514,11 @@ pub(crate) enum InterruptDrawInfo {
 #[cfg(not(any(feature = "render-tui-crossterm")))]
 compile_error!("Please set the 'render-tui-crossterm' feature when using the 'render-tui'");
 
-use crosstermion::crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
+use crosstermion::crossterm::event::{
+    self as terminal_event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use crosstermion::{
-    input::{Key, key_input_stream},
+    input::Key,
     terminal::{AlternateRawScreen, tui::new_terminal},
 };
 
@@ -107,16 +531,84 @@ pub enum Event {
     Tick,
     /// Send any key - can be used to simulate user input, and is typically generated by the TUI's own input loop.
     Input(Key),
+    /// Send any mouse event - can be used to simulate user input, and is typically generated by the TUI's own input loop
+    /// if [`Options::mouse`] is enabled.
+    Mouse(MouseEvent),
     /// Change the size of the window to the given rectangle.
     ///
     /// Useful to embed the TUI into other terminal user interfaces that can resize dynamically.
     SetWindowSize(Rect),
+    /// Query the size of the terminal when drawing the next frame, for example after receiving a signal that it was resized.
+    ///
+    /// Useful with [`Options::size_query_interval`] to react to resizes right away.
+    RefreshWindowSize,
     /// Set the title of the progress dashboard
     SetTitle(String),
     /// Provide a list of titles and lines to populate the side bar on the right.
     SetInformation(Vec<Line>),
     /// The way the GUI will respond to interrupt requests. See `Interrupt` for more information.
     SetInterruptMode(Interrupt),
+    /// Hide the descendants of the task at the given key in the task pane, or show them again if they are hidden already.
+    ///
+    /// The `f` key does the same for the task in the topmost row of the task pane. Folding only applies to [`Layout::Tree`].
+    ToggleFold(progress::Key),
+    /// Change the order of sibling tasks in the task pane.
+    SetSortOrder(SortOrder),
+    /// Stop drawing and restore the terminal right away, as if the user pressed `q`, but regardless of the
+    /// [interrupt mode](Event::SetInterruptMode).
+    Quit,
+    /// Like [`Event::Quit`], but once the given amount of time passed, to give the user a chance to see the final state.
+    ///
+    /// If sent more than once, the earliest point in time is used.
+    QuitAfter(Duration),
+}
+
+impl Options {
+    /// Create the state for drawing as configured by these options.
+    pub(crate) fn draw_state(&self) -> draw::State {
+        draw::State {
+            title: self.title.clone(),
+            duration_per_frame: Duration::from_secs_f32(1.0 / self.frames_per_second),
+            theme: self.theme.clone(),
+            key_bindings: self.key_bindings.clone(),
+            bar_style: self.bar_style,
+            fill_mapping: self.fill_mapping,
+            name_abbrev: self.name_abbrev,
+            timezone: self.timezone,
+            timestamp_format: self.timestamp_format,
+            follow_new_tasks: self.follow_new_tasks,
+            message_follow: draw::MessageFollow::new(self.follow_messages),
+            show_legend: self.show_legend,
+            show_minimap: self.show_minimap,
+            show_child_count: self.show_child_count,
+            disambiguate_duplicate_names: self.disambiguate_duplicate_names,
+            critical_path: self.highlight_critical_path.then(Vec::new),
+            explicit_order: self.explicit_order.clone(),
+            layout: self.layout,
+            finished_tasks: self.finished_tasks,
+            sort_order: self.sort_order,
+            ascii: self.ascii,
+            message_groups: self.collapse_message_groups_above.map(draw::MessageGroups::new),
+            message_level: self.message_level,
+            max_messages_per_frame: self.max_messages_per_frame,
+            columns: self.columns.clone(),
+            show_message_fields: self.show_message_fields,
+            throughput: self.throughput.then(Throughput::default),
+            show_trend: self.show_trend,
+            show_sparkline: self.show_sparkline,
+            show_eta: self.show_eta,
+            show_elapsed: self.show_elapsed,
+            show_global_bar: self.show_global_bar,
+            show_task_fraction: self.show_task_fraction,
+            activity: self.show_activity.then(draw::Activity::default),
+            system_stats: self
+                .show_system_stats
+                .then(|| draw::SystemStats::new(self.show_load_average)),
+            last_update: self.show_last_update.then(LastUpdate::default),
+            custom_pane: self.custom_pane.clone(),
+            ..draw::State::default()
+        }
+    }
 }
 
 /// Returns a future that draws the terminal user interface indefinitely.
@@ -133,76 +625,248 @@ pub fn render_with_input(
     options: Options,
     events: impl futures_core::Stream<Item = Event> + Send + Unpin,
 ) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+    render_events(out, progress, options, events, false)
+}
+
+/// The window size of user interfaces driven by a script if neither [`Options::window_size`] nor
+/// [`Event::SetWindowSize`] set one.
+pub(crate) const SCRIPT_WINDOW_SIZE: Rect = Rect {
+    x: 0,
+    y: 0,
+    width: 80,
+    height: 24,
+};
+
+/// Where frames are drawn.
+enum Screen<T, W> {
+    /// The alternate screen of a terminal in raw mode.
+    Terminal(T),
+    /// Plain text written to `out` after each frame was drawn into `buf`.
+    Text { out: W, buf: Buffer },
+}
+
+/// Like [`render_with_input()`], but if `from_script` is true, draw each frame as plain text into `out` and only
+/// handle the given `events`, without a clock or terminal input, until they end.
+pub(crate) fn render_events(
+    out: impl std::io::Write,
+    progress: impl WeakRoot,
+    options: Options,
+    events: impl futures_core::Stream<Item = Event> + Send + Unpin,
+    from_script: bool,
+) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+    let mut state = options.draw_state();
     let Options {
-        title,
-        frames_per_second,
         window_size,
         recompute_column_width_every_nth_frame,
-        throughput,
+        size_query_interval,
+        redraw_only_on_state_change,
+        show_overall_eta,
+        show_global_bar,
+        collapse_when_idle,
         stop_if_progress_missing,
+        restore_terminal_on_panic,
+        set_terminal_title,
+        record_events,
+        message_log,
+        timezone,
+        mouse,
+        post_draw,
+        #[cfg(feature = "render-tui-notify")]
+        notify_on,
+        ..
     } = options;
-    let mut terminal = new_terminal(AlternateRawScreen::try_from(out)?)?;
-    terminal.hide_cursor()?;
+    let mut recorder = record_events.as_deref().map(Recorder::create).transpose()?;
+    let mut message_log = message_log
+        .as_deref()
+        .map(|path| MessageLog::create(path, timezone))
+        .transpose()?;
+    let mut screen = if from_script {
+        Screen::Text {
+            out,
+            buf: Buffer::default(),
+        }
+    } else {
+        let mut terminal = new_terminal(AlternateRawScreen::try_from(out)?)?;
+        terminal.hide_cursor()?;
+        if mouse {
+            crosstermion::crossterm::execute!(terminal.backend, terminal_event::EnableMouseCapture)?;
+        }
+        Screen::Terminal(terminal)
+    };
+    let restore_on_panic = (restore_terminal_on_panic && !from_script).then(RestoreTerminalOnPanic::install);
 
-    let duration_per_frame = Duration::from_secs_f32(1.0 / frames_per_second);
-    let key_receive = key_input_stream();
+    let duration_per_frame = state.duration_per_frame;
+    let input_receive = (!from_script).then(|| {
+        terminal_event_stream().filter_map(|event| match event {
+            terminal_event::Event::Key(key) => Some(Event::Input(key)),
+            terminal_event::Event::Mouse(mouse) => Some(Event::Mouse(mouse)),
+            terminal_event::Event::Resize(_, _) => Some(Event::RefreshWindowSize),
+            _ => None,
+        })
+    });
 
     let render_fut = async move {
-        let mut state = draw::State {
-            title,
-            duration_per_frame,
-            ..draw::State::default()
-        };
-        if throughput {
-            state.throughput = Some(Throughput::default());
-        }
         let mut interrupt_mode = InterruptDrawInfo::Instantly;
         let (entries_cap, messages_cap) = progress
             .upgrade()
             .map(|p| (p.num_tasks(), p.messages_capacity()))
             .unwrap_or_default();
         let mut entries = Vec::with_capacity(entries_cap);
+        let mut entries_generation = None;
         let mut messages = Vec::with_capacity(messages_cap);
-        let mut events = ticker(duration_per_frame)
-            .map(|_| Event::Tick)
-            .or(key_receive.map(Event::Input))
-            .or(events);
+        let mut new_messages = Vec::new();
+        let mut message_copy_state = None;
+        let clock_and_input: Pin<Box<dyn futures_core::Stream<Item = Event> + Send>> = match input_receive {
+            Some(input_receive) => Box::pin(ticker(duration_per_frame).map(|_| Event::Tick).or(input_receive)),
+            None => Box::pin(futures_lite::stream::pending()),
+        };
+        let mut events = clock_and_input.or(events);
 
+        #[cfg(feature = "render-tui-notify")]
+        let mut notifier = Notifier::new(notify_on);
+        let mut last_change: Option<(u64, Instant)> = None;
         let mut tick = 0usize;
         let store_task_size_every = recompute_column_width_every_nth_frame.unwrap_or(1).max(1);
+        let size_query_interval = size_query_interval.max(1);
+        let mut terminal_window_size = None;
+        let mut last_changes = None;
+        let mut quit_at: Option<Instant> = None;
+        let mut terminal_title: Option<String> = None;
+        if let Screen::Terminal(terminal) = &mut screen {
+            if set_terminal_title {
+                push_terminal_title(&mut terminal.backend).ok();
+            }
+        }
+        let suspend_signal = (!from_script).then(SuspendSignal::register);
         while let Some(event) = events.next().await {
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.record(&event).ok();
+            }
             let mut skip_redraw = false;
+            let message_offset = state.message_offset;
+            let mut suspend_requested = suspend_signal.as_ref().is_some_and(SuspendSignal::take);
             match event {
+                Event::Tick if redraw_only_on_state_change => {
+                    let changes = progress.upgrade().and_then(|p| p.changes());
+                    skip_redraw = changes.is_some() && changes == last_changes;
+                    last_changes = changes;
+                }
                 Event::Tick => {}
-                Event::Input(key) if key.kind != KeyEventKind::Release => match key.code {
-                    KeyCode::Char('c') | KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        match interrupt_mode {
+                Event::Input(key)
+                    if state.editing_filter
+                        && key.kind != KeyEventKind::Release
+                        && !key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    match key.code {
+                        KeyCode::Char(c) => state.filter.get_or_insert_default().push(c),
+                        KeyCode::Backspace => {
+                            state.filter.get_or_insert_default().pop();
+                        }
+                        KeyCode::Enter => state.editing_filter = false,
+                        KeyCode::Esc => {
+                            state.editing_filter = false;
+                            state.filter = None;
+                        }
+                        _ => skip_redraw = true,
+                    }
+                }
+                Event::Input(key)
+                    if state.message_search.as_ref().is_some_and(|search| search.editing)
+                        && key.kind != KeyEventKind::Release
+                        && !key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    if let Some(search) = state.message_search.as_mut() {
+                        match key.code {
+                            KeyCode::Char(c) => {
+                                search.query.push(c);
+                                search.jump_requested = Some(draw::SearchJump::Nearest);
+                            }
+                            KeyCode::Backspace => {
+                                search.query.pop();
+                                search.jump_requested = Some(draw::SearchJump::Nearest);
+                            }
+                            KeyCode::Enter => search.editing = false,
+                            KeyCode::Esc => state.message_search = None,
+                            _ => skip_redraw = true,
+                        }
+                    }
+                }
+                Event::Input(key) if key.kind != KeyEventKind::Release => {
+                    match state.key_bindings.action(key.code, key.modifiers) {
+                        _ if state.show_help && key.code == KeyCode::Esc => state.show_help = false,
+                        Some(Action::Quit) => match interrupt_mode {
                             InterruptDrawInfo::Instantly => break,
                             InterruptDrawInfo::Deferred(_) => interrupt_mode = InterruptDrawInfo::Deferred(true),
+                        },
+                        Some(Action::ToggleHelp) => state.show_help = !state.show_help,
+                        Some(Action::ScrollTasksDown) => state.task_offset = state.task_offset.saturating_add(1),
+                        Some(Action::ScrollTasksUp) => state.task_offset = state.task_offset.saturating_sub(1),
+                        Some(Action::PageTasksDown) => state.task_offset = state.task_offset.saturating_add(10),
+                        Some(Action::PageTasksUp) => state.task_offset = state.task_offset.saturating_sub(10),
+                        Some(Action::ScrollMessagesDown) => {
+                            state.message_offset = state.message_offset.saturating_add(1)
                         }
+                        Some(Action::ScrollMessagesUp) => state.message_offset = state.message_offset.saturating_sub(1),
+                        Some(Action::PageMessagesDown) => {
+                            state.message_offset = state.message_offset.saturating_add(10)
+                        }
+                        Some(Action::PageMessagesUp) => state.message_offset = state.message_offset.saturating_sub(10),
+                        Some(Action::ToggleFold) => state.fold_requested = Some(0),
+                        Some(Action::Filter) => state.editing_filter = true,
+                        Some(Action::CycleSortOrder) => state.sort_order = state.sort_order.next(),
+                        Some(Action::ToggleMessages) => state.hide_messages = !state.hide_messages,
+                        Some(Action::MaximizeMessages) => state.messages_fullscreen = !state.messages_fullscreen,
+                        Some(Action::ToggleMessageGroup) => {
+                            if let Some(groups) = state.message_groups.as_mut() {
+                                groups.toggle_requested = true;
+                            }
+                        }
+                        Some(Action::ToggleMessageFields) => state.show_message_fields = !state.show_message_fields,
+                        Some(Action::ToggleInformation) => state.hide_info = !state.hide_info,
+                        Some(Action::MaximizeInformation) => state.maximize_info = !state.maximize_info,
+                        Some(Action::ToggleLegend) => state.show_legend = !state.show_legend,
+                        Some(Action::ToggleFollowMessages) => {
+                            state.message_follow.enabled = !state.message_follow.enabled
+                        }
+                        Some(Action::SearchMessages) => {
+                            state.message_search = Some(draw::MessageSearch {
+                                editing: true,
+                                ..Default::default()
+                            })
+                        }
+                        Some(Action::NextMessageMatch) => match state.message_search.as_mut() {
+                            Some(search) => search.jump_requested = Some(draw::SearchJump::Next),
+                            None => skip_redraw = true,
+                        },
+                        Some(Action::PreviousMessageMatch) => match state.message_search.as_mut() {
+                            Some(search) => search.jump_requested = Some(draw::SearchJump::Previous),
+                            None => skip_redraw = true,
+                        },
+                        Some(Action::CopyMessage) => state.copy_message_requested = true,
+                        Some(Action::Suspend) => suspend_requested = true,
+                        None => skip_redraw = true,
                     }
-                    KeyCode::Esc | KeyCode::Char('q') => match interrupt_mode {
-                        InterruptDrawInfo::Instantly => break,
-                        InterruptDrawInfo::Deferred(_) => interrupt_mode = InterruptDrawInfo::Deferred(true),
-                    },
-                    KeyCode::Char('`') => state.hide_messages = !state.hide_messages,
-                    KeyCode::Char('~') => state.messages_fullscreen = !state.messages_fullscreen,
-                    KeyCode::Char('J') => state.message_offset = state.message_offset.saturating_add(1),
-                    KeyCode::Char('D') => state.message_offset = state.message_offset.saturating_add(10),
-                    KeyCode::Char('j') => state.task_offset = state.task_offset.saturating_add(1),
-                    KeyCode::Char('d') => state.task_offset = state.task_offset.saturating_add(10),
-                    KeyCode::Char('K') => state.message_offset = state.message_offset.saturating_sub(1),
-                    KeyCode::Char('U') => state.message_offset = state.message_offset.saturating_sub(10),
-                    KeyCode::Char('k') => state.task_offset = state.task_offset.saturating_sub(1),
-                    KeyCode::Char('u') => state.task_offset = state.task_offset.saturating_sub(10),
-                    KeyCode::Char('[') => state.hide_info = !state.hide_info,
-                    KeyCode::Char('{') => state.maximize_info = !state.maximize_info,
-                    _ => skip_redraw = true,
-                },
+                }
                 Event::Input(_) => skip_redraw = true,
-                Event::SetWindowSize(bound) => state.user_provided_window_size = Some(bound),
+                Event::Mouse(mouse) => skip_redraw = !handle_mouse(&mut state, mouse),
+                Event::SetWindowSize(bound) => {
+                    state.user_provided_window_size = Some(bound);
+                    terminal_window_size = None;
+                }
+                Event::RefreshWindowSize => terminal_window_size = None,
                 Event::SetTitle(title) => state.title = title,
+                Event::ToggleFold(key) => {
+                    if !state.folded.remove(&key) {
+                        state.folded.insert(key);
+                    }
+                }
+                Event::SetSortOrder(order) => state.sort_order = order,
                 Event::SetInformation(info) => state.information = info,
+                Event::Quit => break,
+                Event::QuitAfter(duration) => {
+                    let at = Instant::now() + duration;
+                    quit_at = Some(quit_at.map_or(at, |quit_at| quit_at.min(at)));
+                }
                 Event::SetInterruptMode(mode) => {
                     interrupt_mode = match mode {
                         Interrupt::Instantly => {
@@ -218,42 +882,183 @@ pub fn render_with_input(
                     };
                 }
             }
+            if quit_at.is_some_and(|at| Instant::now() >= at) {
+                break;
+            }
+            if state.message_offset != message_offset {
+                // Scrolling means the user wants to look at particular messages.
+                state.message_follow.enabled = false;
+            }
+            if let Screen::Terminal(terminal) = &mut screen {
+                if suspend_requested && suspend(&mut terminal.backend, mouse).unwrap_or(false) {
+                    // The terminal may have been used by others in the meantime, so its content and size are unknown.
+                    terminal.clear().ok();
+                    terminal_window_size = None;
+                    skip_redraw = false;
+                }
+            }
             if !skip_redraw {
                 tick += 1;
 
                 let progress = match progress.upgrade() {
                     Some(progress) => progress,
-                    None if stop_if_progress_missing => break,
+                    None if stop_if_progress_missing => {
+                        #[cfg(feature = "render-tui-notify")]
+                        notifier.finish(&state.title);
+                        break;
+                    }
                     None => continue,
                 };
-                progress.sorted_snapshot(&mut entries);
+                progress.sorted_snapshot_if_changed(&mut entries, &mut entries_generation);
                 if stop_if_progress_missing && entries.is_empty() {
+                    #[cfg(feature = "render-tui-notify")]
+                    notifier.finish(&state.title);
                     break;
                 }
-                let terminal_window_size = terminal.pre_render().expect("pre-render to work");
-                let window_size = state
-                    .user_provided_window_size
-                    .or(window_size)
-                    .unwrap_or(terminal_window_size);
-                let buf = terminal.current_buffer_mut();
-                if !state.hide_messages {
-                    progress.copy_messages(&mut messages);
+                #[cfg(feature = "render-tui-notify")]
+                notifier.update(&state.title, &entries);
+                if let Some(idle_duration) = collapse_when_idle {
+                    let mut hasher = DefaultHasher::new();
+                    entries.hash(&mut hasher);
+                    let hash = hasher.finish();
+                    let changed_at = match last_change {
+                        Some((last_hash, changed_at)) if last_hash == hash => changed_at,
+                        _ => Instant::now(),
+                    };
+                    last_change = Some((hash, changed_at));
+                    state.collapsed = changed_at.elapsed() >= idle_duration;
+                }
+                if show_overall_eta || show_global_bar {
+                    state.update_overall_eta(crate::progress::overall_eta(&entries));
                 }
+                // Steps change which task holds up completion, so follow them on every frame.
+                if let Some(critical_path) = state.critical_path.as_mut() {
+                    *critical_path = crate::progress::critical_path(&entries);
+                }
+                if show_global_bar {
+                    state.overall_fraction = crate::progress::overall_fraction(&entries);
+                }
+                let window_size = state.user_provided_window_size.or(window_size);
+                let (window_size, buf) = match &mut screen {
+                    Screen::Terminal(terminal) => {
+                        let queried_window_size = match terminal_window_size {
+                            Some(size) if tick % size_query_interval != 0 => size,
+                            _ => *terminal_window_size.insert(terminal.pre_render().expect("pre-render to work")),
+                        };
+                        (
+                            window_size.unwrap_or(queried_window_size),
+                            terminal.current_buffer_mut(),
+                        )
+                    }
+                    Screen::Text { buf, .. } => {
+                        let window_size = window_size.unwrap_or(SCRIPT_WINDOW_SIZE);
+                        *buf = Buffer::empty(window_size);
+                        (window_size, buf)
+                    }
+                };
+                // Only copy what's new to avoid cloning the whole message buffer each frame.
+                message_copy_state = Some(progress.copy_new_messages(&mut new_messages, message_copy_state));
+                if let Some(log) = message_log.as_mut() {
+                    log.append(&new_messages).ok();
+                }
+                messages.append(&mut new_messages);
+                // The capacity may change between frames, so keep only as many messages as the buffer holds now.
+                messages.drain(..messages.len().saturating_sub(progress.messages_capacity()));
 
                 draw::all(&mut state, interrupt_mode, &entries, &messages, window_size, buf);
+                if let Some(post_draw) = post_draw.as_deref() {
+                    post_draw(buf, window_size);
+                }
                 if tick == 1 || tick % store_task_size_every == 0 || state.last_tree_column_width.unwrap_or(0) == 0 {
                     state.next_tree_column_width = state.last_tree_column_width;
                 }
-                terminal.post_render().expect("post render to work");
+                match &mut screen {
+                    Screen::Terminal(terminal) => {
+                        terminal.post_render().expect("post render to work");
+                        if let Some(_text) = state.copied_message.take() {
+                            #[cfg(feature = "render-tui-clipboard")]
+                            crate::render::tui::clipboard::copy(&mut terminal.backend, &_text).ok();
+                        }
+                        if set_terminal_title {
+                            let title = match crate::progress::overall_fraction(&entries) {
+                                Some(fraction) => format!("{} · {:.0}%", state.title, (fraction * 100.0).floor()),
+                                None => state.title.clone(),
+                            };
+                            if terminal_title.as_ref() != Some(&title) {
+                                crate::render::tui::utils::set_terminal_title(&mut terminal.backend, &title).ok();
+                                terminal_title = Some(title);
+                            }
+                        }
+                    }
+                    Screen::Text { out, buf } => {
+                        out.write_all(buffer_to_string(buf).as_bytes()).ok();
+                    }
+                }
+            }
+        }
+        match &mut screen {
+            Screen::Terminal(terminal) => {
+                if mouse {
+                    crosstermion::crossterm::execute!(terminal.backend, terminal_event::DisableMouseCapture).ok();
+                }
+                if set_terminal_title {
+                    pop_terminal_title(&mut terminal.backend).ok();
+                }
+                // Make sure the terminal responds right away when this future stops, to reset back to the 'non-alternate' buffer
+                terminal.backend.flush().ok();
+            }
+            Screen::Text { out, .. } => {
+                out.flush().ok();
             }
         }
-        // Make sure the terminal responds right away when this future stops, to reset back to the 'non-alternate' buffer
-        drop(terminal);
-        io::stdout().flush().ok();
+        drop(screen);
+        if let Some(log) = message_log.as_mut() {
+            log.flush().ok();
+        }
+        drop(restore_on_panic);
     };
     Ok(render_fut)
 }
 
+/// Apply `mouse` to `state` according to the panes drawn last, and return true if a redraw is needed.
+fn handle_mouse(state: &mut draw::State, mouse: MouseEvent) -> bool {
+    let position = Position::new(mouse.column, mouse.row);
+    // The minimap is drawn into the task pane, so it has to be handled first.
+    if let Some(minimap) = state.last_minimap.filter(|minimap| minimap.contains(position)) {
+        let first_task_of_row = |row: u16| draw::minimap::first_entry_of_row(row, minimap.height, state.num_tasks_seen);
+        let tasks_per_row = u16::try_from(first_task_of_row(1).max(1)).unwrap_or(u16::MAX);
+        match mouse.kind {
+            MouseEventKind::ScrollDown => state.task_offset = state.task_offset.saturating_add(tasks_per_row),
+            MouseEventKind::ScrollUp => state.task_offset = state.task_offset.saturating_sub(tasks_per_row),
+            MouseEventKind::Down(MouseButton::Left) => {
+                // Center the tasks of the row, the offset is limited to the tasks there are when drawing.
+                let first_task = u16::try_from(first_task_of_row(mouse.row - minimap.y)).unwrap_or(u16::MAX);
+                state.task_offset = first_task.saturating_sub(state.last_task_pane.height / 2);
+            }
+            _ => return false,
+        }
+        return true;
+    }
+    let in_messages = state.last_messages_pane.is_some_and(|pane| pane.contains(position));
+    let in_info = state.last_info_pane.is_some_and(|pane| pane.contains(position));
+    let in_tasks = state.last_task_pane.contains(position);
+    match mouse.kind {
+        MouseEventKind::ScrollDown if in_messages => state.message_offset = state.message_offset.saturating_add(1),
+        MouseEventKind::ScrollUp if in_messages => state.message_offset = state.message_offset.saturating_sub(1),
+        MouseEventKind::ScrollDown if in_tasks => state.task_offset = state.task_offset.saturating_add(1),
+        MouseEventKind::ScrollUp if in_tasks => state.task_offset = state.task_offset.saturating_sub(1),
+        MouseEventKind::Down(MouseButton::Left) if in_messages => {
+            state.messages_fullscreen = !state.messages_fullscreen
+        }
+        MouseEventKind::Down(MouseButton::Left) if in_info => state.maximize_info = !state.maximize_info,
+        MouseEventKind::Down(MouseButton::Left) if in_tasks => {
+            state.fold_requested = Some(mouse.row - state.last_task_pane.y)
+        }
+        _ => return false,
+    }
+    true
+}
+
 /// An easy-to-use version of `render_with_input(…)` that does not allow state manipulation via an event stream.
 pub fn render(
     out: impl std::io::Write,
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use crosstermion::crossterm::event::{KeyCode, KeyModifiers};
+
+/// Something the user can do with a key press in the terminal user interface, see [`KeyBindings`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Action {
+    /// Stop the user interface, or request to stop it if interrupts are deferred.
+    Quit,
+    /// Show or hide the help listing all key bindings.
+    ToggleHelp,
+    /// Scroll the task pane down by one line.
+    ScrollTasksDown,
+    /// Scroll the task pane up by one line.
+    ScrollTasksUp,
+    /// Scroll the task pane down by ten lines.
+    PageTasksDown,
+    /// Scroll the task pane up by ten lines.
+    PageTasksUp,
+    /// Scroll the messages pane down by one line.
+    ScrollMessagesDown,
+    /// Scroll the messages pane up by one line.
+    ScrollMessagesUp,
+    /// Scroll the messages pane down by ten lines.
+    PageMessagesDown,
+    /// Scroll the messages pane up by ten lines.
+    PageMessagesUp,
+    /// Fold or unfold the children of the task in the topmost row of the task pane.
+    ToggleFold,
+    /// Start typing a filter for the names of tasks and the origins of messages.
+    Filter,
+    /// Sort the task pane by the next [order](crate::render::tui::SortOrder), wrapping around.
+    CycleSortOrder,
+    /// Show or hide the messages pane.
+    ToggleMessages,
+    /// Let the messages pane take up most of the window, or return it to its normal size.
+    MaximizeMessages,
+    /// Expand or collapse the group of messages in the topmost row of the messages pane.
+    ToggleMessageGroup,
+    /// Show or hide the fields of messages below them in the messages pane.
+    ToggleMessageFields,
+    /// Show or hide the information pane.
+    ToggleInformation,
+    /// Let the information pane take up most of the window, or return it to its normal size.
+    MaximizeInformation,
+    /// Show or hide the legend at the bottom of the task pane.
+    ToggleLegend,
+    /// Let the messages pane stick to the newest message, or keep showing the same messages while new ones arrive.
+    ///
+    /// Scrolling the messages pane stops following the newest message.
+    ToggleFollowMessages,
+    /// Start typing text to search for in messages, highlighting matches and scrolling to the nearest one.
+    SearchMessages,
+    /// Scroll the messages pane down to the next match of the message search, wrapping around.
+    NextMessageMatch,
+    /// Scroll the messages pane up to the previous match of the message search, wrapping around.
+    PreviousMessageMatch,
+    /// Copy the message in the topmost row of the messages pane to the clipboard, along with its time and origin.
+    /// There is no selection, so scroll the messages pane until the message to copy is at the top.
+    ///
+    /// Only supported with the `render-tui-clipboard` feature, and ignored otherwise.
+    CopyMessage,
+    /// Suspend the process like `Ctrl-Z` does in a shell, restoring the terminal until the process continues.
+    ///
+    /// Only supported on Unix with the `signal-hook` feature, and ignored otherwise.
+    Suspend,
+}
+
+impl Action {
+    /// All actions, in the order in which they are listed in the help.
+    pub const ALL: [Action; 26] = [
+        Action::Quit,
+        Action::ToggleHelp,
+        Action::ScrollTasksDown,
+        Action::ScrollTasksUp,
+        Action::PageTasksDown,
+        Action::PageTasksUp,
+        Action::ScrollMessagesDown,
+        Action::ScrollMessagesUp,
+        Action::PageMessagesDown,
+        Action::PageMessagesUp,
+        Action::ToggleFold,
+        Action::Filter,
+        Action::CycleSortOrder,
+        Action::ToggleMessages,
+        Action::MaximizeMessages,
+        Action::ToggleMessageGroup,
+        Action::ToggleMessageFields,
+        Action::ToggleInformation,
+        Action::MaximizeInformation,
+        Action::ToggleLegend,
+        Action::ToggleFollowMessages,
+        Action::SearchMessages,
+        Action::NextMessageMatch,
+        Action::PreviousMessageMatch,
+        Action::CopyMessage,
+        Action::Suspend,
+    ];
+
+    /// Return a short description of what this action does, as shown in the help.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit, or request to quit if it's deferred",
+            Action::ToggleHelp => "show this help",
+            Action::ScrollTasksDown => "scroll tasks down by one line",
+            Action::ScrollTasksUp => "scroll tasks up by one line",
+            Action::PageTasksDown => "scroll tasks down by ten lines",
+            Action::PageTasksUp => "scroll tasks up by ten lines",
+            Action::ScrollMessagesDown => "scroll messages down by one line",
+            Action::ScrollMessagesUp => "scroll messages up by one line",
+            Action::PageMessagesDown => "scroll messages down by ten lines",
+            Action::PageMessagesUp => "scroll messages up by ten lines",
+            Action::ToggleFold => "fold or unfold the children of the topmost task",
+            Action::Filter => "filter tasks and messages by name",
+            Action::CycleSortOrder => "sort tasks by name, start time, remaining work, throughput or not at all",
+            Action::ToggleMessages => "hide messages",
+            Action::MaximizeMessages => "maximize messages",
+            Action::ToggleMessageGroup => "expand or collapse the topmost message group",
+            Action::ToggleMessageFields => "show fields of messages",
+            Action::ToggleInformation => "hide information",
+            Action::MaximizeInformation => "maximize information",
+            Action::ToggleLegend => "show legend",
+            Action::ToggleFollowMessages => "follow the newest message",
+            Action::SearchMessages => "search for text in messages",
+            Action::NextMessageMatch => "scroll to the next message matching the search",
+            Action::PreviousMessageMatch => "scroll to the previous message matching the search",
+            Action::CopyMessage => "copy the topmost message to the clipboard",
+            Action::Suspend => "suspend, continue with `fg` in the shell",
+        }
+    }
+}
+
+/// A map from keys to the [actions](Action) they trigger in the terminal user interface, see
+/// [`Options::key_bindings`][crate::render::tui::Options::key_bindings].
+///
+/// The shift modifier is ignored when looking up keys, as it's already reflected in the character that was typed.
+///
+/// # Example
+///
+/// Scroll tasks with the arrow keys in addition to the default bindings.
+///
+/// ```
+/// use prodash::render::tui::{Action, KeyBindings, KeyCode, KeyModifiers};
+/// let bindings = KeyBindings::default()
+///     .bind(KeyCode::Down, KeyModifiers::NONE, Action::ScrollTasksDown)
+///     .bind(KeyCode::Up, KeyModifiers::NONE, Action::ScrollTasksUp);
+/// assert_eq!(bindings.action(KeyCode::Down, KeyModifiers::NONE), Some(Action::ScrollTasksDown));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    actions: HashMap<(KeyCode, KeyModifiers), Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let bindings = KeyBindings::empty()
+            .bind(KeyCode::Char('c'), KeyModifiers::CONTROL, Action::Quit)
+            .bind(KeyCode::Char('['), KeyModifiers::CONTROL, Action::Quit)
+            .bind(KeyCode::Char('z'), KeyModifiers::CONTROL, Action::Suspend)
+            .bind(KeyCode::Esc, KeyModifiers::NONE, Action::Quit)
+            .bind(KeyCode::Char('?'), KeyModifiers::NONE, Action::ToggleHelp);
+        let bindings = [
+            ('q', Action::Quit),
+            ('j', Action::ScrollTasksDown),
+            ('k', Action::ScrollTasksUp),
+            ('d', Action::PageTasksDown),
+            ('u', Action::PageTasksUp),
+            ('J', Action::ScrollMessagesDown),
+            ('K', Action::ScrollMessagesUp),
+            ('D', Action::PageMessagesDown),
+            ('U', Action::PageMessagesUp),
+            ('f', Action::ToggleFold),
+            ('/', Action::Filter),
+            ('s', Action::CycleSortOrder),
+            ('`', Action::ToggleMessages),
+            ('~', Action::MaximizeMessages),
+            ('g', Action::ToggleMessageGroup),
+            ('F', Action::ToggleMessageFields),
+            ('[', Action::ToggleInformation),
+            ('{', Action::MaximizeInformation),
+            ('l', Action::ToggleLegend),
+            ('t', Action::ToggleFollowMessages),
+            ('m', Action::SearchMessages),
+            ('n', Action::NextMessageMatch),
+            ('N', Action::PreviousMessageMatch),
+        ]
+        .into_iter()
+        .fold(bindings, |bindings, (c, action)| {
+            bindings.bind(KeyCode::Char(c), KeyModifiers::NONE, action)
+        });
+        #[cfg(feature = "render-tui-clipboard")]
+        let bindings = bindings.bind(KeyCode::Char('y'), KeyModifiers::NONE, Action::CopyMessage);
+        bindings
+    }
+}
+
+impl KeyBindings {
+    /// Create an instance without any bindings, which leaves the user interface without a way to quit it by key.
+    pub fn empty() -> Self {
+        KeyBindings {
+            actions: HashMap::new(),
+        }
+    }
+
+    /// Let `code` pressed along with `modifiers` trigger `action`, replacing what it triggered before.
+    pub fn bind(mut self, code: KeyCode, modifiers: KeyModifiers, action: Action) -> Self {
+        self.actions.insert((code, modifiers - KeyModifiers::SHIFT), action);
+        self
+    }
+
+    /// Let `code` pressed along with `modifiers` trigger nothing.
+    pub fn unbind(mut self, code: KeyCode, modifiers: KeyModifiers) -> Self {
+        self.actions.remove(&(code, modifiers - KeyModifiers::SHIFT));
+        self
+    }
+
+    /// Return the action triggered by `code` pressed along with `modifiers`, if any.
+    pub fn action(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        self.actions.get(&(code, modifiers - KeyModifiers::SHIFT)).copied()
+    }
+
+    /// Return the names of all keys triggering `action` in a stable order, as in `^c` or `q`.
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        let mut keys: Vec<_> = self
+            .actions
+            .iter()
+            .filter(|(_key, bound)| **bound == action)
+            .map(|((code, modifiers), _action)| key_name(*code, *modifiers))
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
+fn key_name(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let mut name = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        name.push('^');
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("M-");
+    }
+    match code {
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::Up => name.push('↑'),
+        KeyCode::Down => name.push('↓'),
+        KeyCode::Left => name.push('←'),
+        KeyCode::Right => name.push('→'),
+        KeyCode::PageUp => name.push_str("PgUp"),
+        KeyCode::PageDown => name.push_str("PgDn"),
+        code => name.push_str(&format!("{code:?}")),
+    }
+    name
+}
@@ -49,11 +49,27 @@
 * # }
 * ```
 */
+#[cfg(feature = "render-tui-clipboard")]
+mod clipboard;
 mod draw;
 mod engine;
+mod keys;
+#[cfg(feature = "render-tui-notify")]
+mod notify;
+mod record;
+mod theme;
 mod utils;
+mod widget;
 
+/// Useful for defining [`KeyBindings`] without bringing in `crossterm` yourself
+pub use crosstermion::crossterm::event::{KeyCode, KeyModifiers};
 pub use engine::*;
+pub use keys::{Action, KeyBindings};
+#[cfg(feature = "render-tui-notify")]
+pub use notify::NotifyTrigger;
+pub use record::{EventScript, render_with_script, replay_events};
+pub use theme::Theme;
 /// Useful for bringing up the TUI without bringing in the `tui` crate yourself
 pub use tui as tui_export;
 pub use utils::ticker;
+pub use widget::{Dashboard, DashboardState, render_to_string};
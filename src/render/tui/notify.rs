@@ -0,0 +1,144 @@
+use std::time::{Duration, Instant};
+
+use crate::progress::{self, Key, Task};
+
+/// The time to wait after the first failure before notifying, so failures happening at about the same time are reported together.
+const FAILURE_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Determines when to show a desktop notification, see [`Options::notify_on`][crate::render::tui::Options::notify_on].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum NotifyTrigger {
+    /// Never show a notification.
+    #[default]
+    Never,
+    /// Notify once all tasks are done, either because all bounded tasks reached their end or because the progress
+    /// went away while the user interface was stopping due to missing progress.
+    Completion,
+    /// Notify once the first task is halted.
+    Failure,
+    /// Notify on completion as well as on failure.
+    CompletionOrFailure,
+}
+
+impl NotifyTrigger {
+    fn on_completion(self) -> bool {
+        matches!(self, NotifyTrigger::Completion | NotifyTrigger::CompletionOrFailure)
+    }
+
+    fn on_failure(self) -> bool {
+        matches!(self, NotifyTrigger::Failure | NotifyTrigger::CompletionOrFailure)
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Summary {
+    done: usize,
+    failed: usize,
+    total: usize,
+}
+
+impl Summary {
+    fn from_entries(entries: &[(Key, Task)]) -> Self {
+        entries
+            .iter()
+            .filter_map(|(_key, task)| task.progress.as_ref().filter(|progress| !progress.aggregated))
+            .fold(Summary::default(), |mut summary, progress| {
+                summary.total += 1;
+                if let progress::State::Halted(_, _) = progress.state {
+                    summary.failed += 1;
+                } else if progress.is_done() {
+                    summary.done += 1;
+                }
+                summary
+            })
+    }
+
+    fn is_complete(&self) -> bool {
+        self.total > 0 && self.done == self.total
+    }
+}
+
+/// Shows desktop notifications about the progress shown in the user interface, at most once for each kind of event.
+pub(crate) struct Notifier {
+    trigger: NotifyTrigger,
+    started_at: Instant,
+    summary: Summary,
+    first_failure_at: Option<Instant>,
+    notified_completion: bool,
+    notified_failure: bool,
+}
+
+impl Notifier {
+    pub(crate) fn new(trigger: NotifyTrigger) -> Self {
+        Notifier {
+            trigger,
+            started_at: Instant::now(),
+            summary: Summary::default(),
+            first_failure_at: None,
+            notified_completion: false,
+            notified_failure: false,
+        }
+    }
+
+    /// Notify about the state of `entries` of the progress titled `title` if needed, to be called for every frame.
+    pub(crate) fn update(&mut self, title: &str, entries: &[(Key, Task)]) {
+        if self.trigger == NotifyTrigger::Never || entries.is_empty() {
+            return;
+        }
+        self.summary = Summary::from_entries(entries);
+        if self.summary.failed > 0 {
+            let first_failure_at = *self.first_failure_at.get_or_insert_with(Instant::now);
+            if first_failure_at.elapsed() >= FAILURE_DEBOUNCE {
+                self.notify_failure(title);
+            }
+        }
+        if self.summary.is_complete() {
+            self.notify_completion(title);
+        }
+    }
+
+    /// Notify about the last known state as the progress went away, which means all work is done.
+    pub(crate) fn finish(&mut self, title: &str) {
+        if self.trigger == NotifyTrigger::Never {
+            return;
+        }
+        if self.summary.failed > 0 {
+            self.notify_failure(title);
+        }
+        if self.summary.total > 0 {
+            self.notify_completion(title);
+        }
+    }
+
+    fn notify_failure(&mut self, title: &str) {
+        if self.trigger.on_failure() && !self.notified_failure {
+            self.notified_failure = true;
+            self.show(format!("{title}: task failed"));
+        }
+    }
+
+    fn notify_completion(&mut self, title: &str) {
+        if self.trigger.on_completion() && !self.notified_completion {
+            self.notified_completion = true;
+            self.show(format!("{title}: done"));
+        }
+    }
+
+    fn show(&self, summary: String) {
+        let Summary { done, failed, total } = self.summary;
+        let elapsed =
+            jiff::SignedDuration::from_secs(self.started_at.elapsed().as_secs().try_into().unwrap_or(i64::MAX));
+        let body = format!("{done} of {total} tasks done, {failed} failed, after {elapsed:#}");
+        // Showing a notification may block for a while, and fails if there is no notification daemon, which is fine.
+        std::thread::Builder::new()
+            .name("prodash-tui-notify".into())
+            .spawn(move || {
+                notify_rust::Notification::new()
+                    .summary(&summary)
+                    .body(&body)
+                    .show()
+                    .ok();
+            })
+            .ok();
+    }
+}
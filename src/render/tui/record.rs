@@ -0,0 +1,391 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crosstermion::crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MediaKeyCode, ModifierKeyCode, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use serde::{Deserialize, Serialize};
+use tui::layout::Rect;
+
+use crate::{
+    WeakRoot,
+    progress::Key,
+    render::tui::{Event, Interrupt, Line, Options, SortOrder, engine::render_events, render_with_input},
+};
+
+/// Writes events as JSON lines along with the time at which they were received, relative to the first one.
+pub(crate) struct Recorder {
+    out: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Recorder {
+            out: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+        })
+    }
+
+    /// Write `event` unless it's a tick, which is produced by the clock, or a key release.
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        let Some(event) = Recorded::from_event(event) else {
+            return Ok(());
+        };
+        let record = Record {
+            at: self.start.elapsed().as_secs_f64(),
+            event,
+        };
+        serde_json::to_writer(&mut self.out, &record)?;
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
+
+/// A single line written by the [`Recorder`].
+#[derive(Serialize, Deserialize)]
+struct Record {
+    /// The amount of seconds since the first event was received.
+    at: f64,
+    #[serde(flatten)]
+    event: Recorded,
+}
+
+/// The events that can be recorded, named by the `event` field.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Recorded {
+    Key {
+        #[serde(with = "KeyCodeDef")]
+        code: KeyCode,
+        modifiers: u8,
+    },
+    Mouse {
+        kind: MouseKind,
+        column: u16,
+        row: u16,
+    },
+    WindowSize {
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    },
+    RefreshWindowSize,
+    Quit,
+    QuitAfter {
+        seconds: f64,
+    },
+    Title {
+        title: String,
+    },
+    Information {
+        lines: Vec<InformationLine>,
+    },
+    ToggleFold {
+        key: Key,
+    },
+    SortOrder {
+        #[serde(with = "SortOrderDef")]
+        order: SortOrder,
+    },
+    InterruptMode {
+        #[serde(with = "InterruptDef")]
+        mode: Interrupt,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MouseKind {
+    Click,
+    ScrollDown,
+    ScrollUp,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum InformationLine {
+    Title(String),
+    Text(String),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "KeyCode")]
+enum KeyCodeDef {
+    Backspace,
+    Enter,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Tab,
+    BackTab,
+    Delete,
+    Insert,
+    F(u8),
+    Char(char),
+    Null,
+    Esc,
+    CapsLock,
+    ScrollLock,
+    NumLock,
+    PrintScreen,
+    Pause,
+    Menu,
+    KeypadBegin,
+    Media(#[serde(with = "MediaKeyCodeDef")] MediaKeyCode),
+    Modifier(#[serde(with = "ModifierKeyCodeDef")] ModifierKeyCode),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "MediaKeyCode")]
+enum MediaKeyCodeDef {
+    Play,
+    Pause,
+    PlayPause,
+    Reverse,
+    Stop,
+    FastForward,
+    Rewind,
+    TrackNext,
+    TrackPrevious,
+    Record,
+    LowerVolume,
+    RaiseVolume,
+    MuteVolume,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ModifierKeyCode")]
+enum ModifierKeyCodeDef {
+    LeftShift,
+    LeftControl,
+    LeftAlt,
+    LeftSuper,
+    LeftHyper,
+    LeftMeta,
+    RightShift,
+    RightControl,
+    RightAlt,
+    RightSuper,
+    RightHyper,
+    RightMeta,
+    IsoLevel3Shift,
+    IsoLevel5Shift,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "SortOrder", rename_all = "snake_case")]
+enum SortOrderDef {
+    Tree,
+    Name,
+    StartTime,
+    Remaining,
+    Throughput,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Interrupt", rename_all = "snake_case")]
+enum InterruptDef {
+    Instantly,
+    Deferred,
+}
+
+impl Recorded {
+    /// Return what to record of `event`, or `None` if it's not worth recording.
+    fn from_event(event: &Event) -> Option<Self> {
+        Some(match event {
+            Event::Tick => return None,
+            Event::Input(key) if key.kind == KeyEventKind::Release => return None,
+            Event::Input(key) => Recorded::Key {
+                code: key.code,
+                modifiers: key.modifiers.bits(),
+            },
+            Event::Mouse(mouse) => Recorded::Mouse {
+                kind: match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => MouseKind::Click,
+                    MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+                    MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+                    _ => return None,
+                },
+                column: mouse.column,
+                row: mouse.row,
+            },
+            Event::SetWindowSize(bound) => Recorded::WindowSize {
+                x: bound.x,
+                y: bound.y,
+                width: bound.width,
+                height: bound.height,
+            },
+            Event::RefreshWindowSize => Recorded::RefreshWindowSize,
+            Event::Quit => Recorded::Quit,
+            Event::QuitAfter(duration) => Recorded::QuitAfter {
+                seconds: duration.as_secs_f64(),
+            },
+            Event::SetTitle(title) => Recorded::Title { title: title.clone() },
+            Event::SetInformation(lines) => Recorded::Information {
+                lines: lines
+                    .iter()
+                    .map(|line| match line {
+                        Line::Title(text) => InformationLine::Title(text.clone()),
+                        Line::Text(text) => InformationLine::Text(text.clone()),
+                    })
+                    .collect(),
+            },
+            Event::ToggleFold(key) => Recorded::ToggleFold { key: *key },
+            Event::SetSortOrder(order) => Recorded::SortOrder { order: *order },
+            Event::SetInterruptMode(mode) => Recorded::InterruptMode { mode: *mode },
+        })
+    }
+
+    fn into_event(self) -> Event {
+        match self {
+            Recorded::Key { code, modifiers } => {
+                Event::Input(KeyEvent::new(code, KeyModifiers::from_bits_truncate(modifiers)))
+            }
+            Recorded::Mouse { kind, column, row } => Event::Mouse(MouseEvent {
+                kind: match kind {
+                    MouseKind::Click => MouseEventKind::Down(MouseButton::Left),
+                    MouseKind::ScrollDown => MouseEventKind::ScrollDown,
+                    MouseKind::ScrollUp => MouseEventKind::ScrollUp,
+                },
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            }),
+            Recorded::WindowSize { x, y, width, height } => Event::SetWindowSize(Rect { x, y, width, height }),
+            Recorded::RefreshWindowSize => Event::RefreshWindowSize,
+            Recorded::Quit => Event::Quit,
+            Recorded::QuitAfter { seconds } => {
+                Event::QuitAfter(Duration::try_from_secs_f64(seconds).unwrap_or_default())
+            }
+            Recorded::Title { title } => Event::SetTitle(title),
+            Recorded::Information { lines } => Event::SetInformation(
+                lines
+                    .into_iter()
+                    .map(|line| match line {
+                        InformationLine::Title(text) => Line::Title(text),
+                        InformationLine::Text(text) => Line::Text(text),
+                    })
+                    .collect(),
+            ),
+            Recorded::ToggleFold { key } => Event::ToggleFold(key),
+            Recorded::SortOrder { order } => Event::SetSortOrder(order),
+            Recorded::InterruptMode { mode } => Event::SetInterruptMode(mode),
+        }
+    }
+}
+
+/// Parse a single `line` as written by the [`Recorder`], returning the time at which it was received along with the event.
+fn parse_event(line: &str) -> Option<(Duration, Event)> {
+    let Record { at, event } = serde_json::from_str(line).ok()?;
+    Some((Duration::try_from_secs_f64(at).unwrap_or_default(), event.into_event()))
+}
+
+/// A sequence of events to send to [`render_with_input(…)`][crate::render::tui::render_with_input()], for example to test dashboards without user input.
+///
+/// It can be [loaded][EventScript::load()] from a file written with [`Options::record_events`], or be scripted
+/// by collecting events into it.
+#[derive(Debug, Clone, Default)]
+pub struct EventScript {
+    events: Vec<(Duration, Event)>,
+}
+
+impl EventScript {
+    /// Read the events recorded in the file at `path` with [`Options::record_events`], along with the time at which
+    /// they were received.
+    ///
+    /// Lines that can't be parsed are ignored.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for line in io::BufReader::new(File::open(path)?).lines() {
+            events.extend(parse_event(&line?));
+        }
+        Ok(EventScript { events })
+    }
+
+    /// Return all events in the order in which they will be sent.
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().map(|(_at, event)| event)
+    }
+
+    /// Turn this script into a stream for [`render_with_input(…)`][crate::render::tui::render_with_input()] which sends
+    /// each event followed by [`Event::Tick`] right away, regardless of the time at which it was recorded.
+    ///
+    /// Frames are still drawn on the clock of the user interface as well, and it stops once the stream ends. Use
+    /// [`render_with_script()`] to draw frames only as the events of the script say.
+    pub fn into_stream(self) -> impl futures_core::Stream<Item = Event> + Send + Unpin {
+        futures_lite::stream::iter(self.events.into_iter().flat_map(|(_at, event)| [event, Event::Tick]))
+    }
+}
+
+impl FromIterator<Event> for EventScript {
+    fn from_iter<T: IntoIterator<Item = Event>>(iter: T) -> Self {
+        EventScript {
+            events: iter.into_iter().map(|event| (Duration::ZERO, event)).collect(),
+        }
+    }
+}
+
+/// Returns a future that draws the terminal user interface of `progress` like [`render_with_input(…)`][crate::render::tui::render_with_input()], while sending
+/// the events recorded in the file at `path` with [`Options::record_events`] at the time they were received originally.
+///
+/// This makes it possible to follow what happened in an interactive session, even though frames are drawn on a clock
+/// of their own and may not match the original ones. Use [`EventScript`] to send them without delay instead.
+/// Lines that can't be parsed are ignored.
+///
+/// Failure may occur if the file can't be read or if there is no terminal to draw into.
+pub fn replay_events(
+    path: impl AsRef<Path>,
+    out: impl std::io::Write,
+    progress: impl WeakRoot,
+    options: Options,
+) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+    let EventScript { events } = EventScript::load(path)?;
+    let start = Instant::now();
+    let events = futures_lite::stream::unfold(events.into_iter(), move |mut events| async move {
+        let (at, event) = events.next()?;
+        async_io::Timer::at(start + at).await;
+        Some((event, events))
+    });
+    render_with_input(
+        out,
+        progress,
+        Options {
+            record_events: None,
+            ..options
+        },
+        Box::pin(events),
+    )
+}
+
+/// Returns a future that draws the terminal user interface of `progress` like [`render_with_input(…)`][crate::render::tui::render_with_input()],
+/// but only handles the events of `script`, without a clock or input from a terminal, and stops once they were all handled.
+///
+/// A frame is drawn for each event that changes what's shown, and for each [`Event::Tick`], which the script can use to
+/// draw the current state of `progress`. Instead of showing it in a terminal, each frame is written to `out` as plain
+/// text like with [`render_to_string()`][crate::render::tui::render_to_string()], with as many lines as the window
+/// has rows. Unless set by [`Options::window_size`] or [`Event::SetWindowSize`], the window is 80 columns wide and
+/// 24 rows high.
+///
+/// This makes it possible to test dashboards without a terminal, for example by counting frames with
+/// [`Options::post_draw`].
+pub fn render_with_script(
+    out: impl std::io::Write,
+    progress: impl WeakRoot,
+    options: Options,
+    script: EventScript,
+) -> Result<impl std::future::Future<Output = ()>, std::io::Error> {
+    let events = script.events.into_iter().map(|(_at, event)| event);
+    render_events(out, progress, options, futures_lite::stream::iter(events), true)
+}
@@ -0,0 +1,113 @@
+use tui::style::{Color, Modifier, Style};
+
+use crate::progress::key::Level;
+
+/// Colors used by the terminal user interface.
+///
+/// The default is made for terminals with a dark background, use [`Theme::light()`] for those with a light one.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// If not empty (default: empty), running progress bars are colored by their depth in the tree, cycling through
+    /// the given colors. The first color is used for top-level tasks.
+    ///
+    /// If empty, the color of progress bars is determined by how far they progressed.
+    pub depth_palette: Vec<Color>,
+    /// The color of running progress bars that are less than 80% done (default: yellow).
+    pub bar_in_progress: Color,
+    /// The color of running progress bars that are at least 80% done, and of successful steps (default: green).
+    pub bar_nearly_done: Color,
+    /// The color of progress bars of blocked tasks (default: red).
+    pub bar_blocked: Color,
+    /// The color of progress bars of halted tasks (default: light red).
+    pub bar_halted: Color,
+    /// The color of failed steps, and of the part of the overflow line representing undone work (default: red).
+    pub bar_failed: Color,
+    /// The color of text drawn on top of progress bars (default: black).
+    pub bar_text: Color,
+    /// The color of spinners shown for unbounded tasks (default: white).
+    pub spinner: Color,
+    /// The style of names of tasks with progress (default: unstyled).
+    pub task_name: Style,
+    /// The style of names of groups, which are tasks without progress (default: bold).
+    pub group_name: Style,
+    /// The style applied on top of the names of tasks on the critical path, if it's highlighted (default: underlined).
+    pub critical_path: Style,
+    /// The style of the title of the window, the headline and the titles of panes (default: bold).
+    pub title: Style,
+    /// The style of text lines in the information pane (default: unstyled).
+    pub information: Style,
+    /// The background color of the level of informational messages (default: white).
+    pub message_info: Color,
+    /// The background color of the level of failure messages (default: red).
+    pub message_failure: Color,
+    /// The background color of the level of success messages (default: green).
+    pub message_success: Color,
+    /// The background color of the level of warning messages (default: yellow).
+    pub message_warning: Color,
+    /// The background color of the level of debug messages (default: dark gray).
+    pub message_debug: Color,
+    /// The background color of the level of trace messages (default: dark gray).
+    pub message_trace: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    /// Return a theme for terminals with a dark background, which is the default.
+    pub fn dark() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Theme {
+            depth_palette: Vec::new(),
+            bar_in_progress: Color::Yellow,
+            bar_nearly_done: Color::Green,
+            bar_blocked: Color::Red,
+            bar_halted: Color::LightRed,
+            bar_failed: Color::Red,
+            bar_text: Color::Black,
+            spinner: Color::White,
+            task_name: Style::default(),
+            group_name: bold,
+            critical_path: Style::default().add_modifier(Modifier::UNDERLINED),
+            title: bold,
+            information: Style::default(),
+            message_info: Color::White,
+            message_failure: Color::Red,
+            message_success: Color::Green,
+            message_warning: Color::Yellow,
+            message_debug: Color::DarkGray,
+            message_trace: Color::DarkGray,
+        }
+    }
+
+    /// Return a theme for terminals with a light background.
+    pub fn light() -> Self {
+        Theme {
+            bar_in_progress: Color::Blue,
+            bar_text: Color::White,
+            spinner: Color::DarkGray,
+            message_info: Color::Gray,
+            ..Theme::dark()
+        }
+    }
+
+    /// Return the color for tasks at `level` of the hierarchy, or `None` if there is no [depth palette][Theme::depth_palette].
+    pub fn depth_color(&self, level: Level) -> Option<Color> {
+        if self.depth_palette.is_empty() {
+            return None;
+        }
+        Some(self.depth_palette[(level as usize).saturating_sub(1) % self.depth_palette.len()])
+    }
+
+    /// Return the color of a running progress bar that is `fraction` done.
+    pub fn bar_color(&self, fraction: f32) -> Color {
+        if fraction >= 0.8 {
+            self.bar_nearly_done
+        } else {
+            self.bar_in_progress
+        }
+    }
+}
@@ -1,6 +1,18 @@
-use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        Once,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::Poll,
+    time::Duration,
+};
 
 use async_io::Timer;
+use tui::{buffer::Buffer, layout::Rect, style::Style};
+
+use crate::render::canvas::Canvas;
 
 /// Returns a stream of 'ticks', each being duration `dur` apart.
 ///
@@ -20,6 +32,183 @@ pub fn ticker(dur: Duration) -> impl futures_core::Stream<Item = ()> {
     })
 }
 
+/// Returns a stream of all events of the terminal, like key presses and mouse events, read on a separate thread.
+pub(crate) fn terminal_event_stream()
+-> impl futures_core::Stream<Item = crosstermion::crossterm::event::Event> + Send + Unpin {
+    let (send, receive) = async_channel::bounded(1);
+    std::thread::Builder::new()
+        .name("render-tui-input".into())
+        .spawn(move || {
+            while let Ok(event) = crosstermion::crossterm::event::read() {
+                if send.send_blocking(event).is_err() {
+                    break;
+                }
+            }
+        })
+        .expect("starting a thread works");
+    Box::pin(receive)
+}
+
+/// The amount of user interfaces currently drawing, for the panic hook to know if the terminal needs restoring.
+static ACTIVE_TERMINALS: AtomicUsize = AtomicUsize::new(0);
+
+/// While alive, makes sure a panic restores the terminal before the panic message is printed, as otherwise it would
+/// be printed into the alternate screen in raw mode, leaving the terminal unusable.
+///
+/// The panic hook is installed once and calls the previously installed hook after restoring the terminal.
+/// It can't reach the writer the user interface draws to, so it writes the escape sequences to `stdout`.
+pub(crate) struct RestoreTerminalOnPanic(());
+
+impl RestoreTerminalOnPanic {
+    pub(crate) fn install() -> Self {
+        static INSTALL_HOOK: Once = Once::new();
+        INSTALL_HOOK.call_once(|| {
+            let previous_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                if ACTIVE_TERMINALS.load(Ordering::SeqCst) > 0 {
+                    restore_terminal(&mut std::io::stdout());
+                }
+                previous_hook(info)
+            }));
+        });
+        ACTIVE_TERMINALS.fetch_add(1, Ordering::SeqCst);
+        RestoreTerminalOnPanic(())
+    }
+}
+
+impl Drop for RestoreTerminalOnPanic {
+    fn drop(&mut self) {
+        ACTIVE_TERMINALS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Show the cursor, leave the alternate screen on `out` and disable raw mode, ignoring errors as it's a best effort.
+fn restore_terminal(out: &mut impl std::io::Write) {
+    use crosstermion::crossterm::{cursor, event, execute, terminal};
+    execute!(
+        out,
+        event::DisableMouseCapture,
+        terminal::LeaveAlternateScreen,
+        cursor::Show
+    )
+    .ok();
+    terminal::disable_raw_mode().ok();
+}
+
+/// Notices `SIGTSTP` while alive, which would otherwise stop the process with the terminal left in raw mode.
+#[cfg(all(unix, feature = "signal-hook"))]
+pub(crate) struct SuspendSignal {
+    received: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    id: Option<signal_hook::SigId>,
+}
+
+#[cfg(all(unix, feature = "signal-hook"))]
+impl SuspendSignal {
+    pub(crate) fn register() -> Self {
+        let received = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let id = signal_hook::flag::register(signal_hook::consts::SIGTSTP, received.clone()).ok();
+        SuspendSignal { received, id }
+    }
+
+    /// Return true if `SIGTSTP` was received since the last call.
+    pub(crate) fn take(&self) -> bool {
+        self.received.swap(false, Ordering::SeqCst)
+    }
+}
+
+#[cfg(all(unix, feature = "signal-hook"))]
+impl Drop for SuspendSignal {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            signal_hook::low_level::unregister(id);
+        }
+    }
+}
+
+/// Notices nothing, as signals can't be handled here.
+#[cfg(not(all(unix, feature = "signal-hook")))]
+pub(crate) struct SuspendSignal;
+
+#[cfg(not(all(unix, feature = "signal-hook")))]
+impl SuspendSignal {
+    pub(crate) fn register() -> Self {
+        SuspendSignal
+    }
+
+    pub(crate) fn take(&self) -> bool {
+        false
+    }
+}
+
+/// Restore the terminal on `out` and stop the process like `SIGTSTP` would, then return to the alternate screen in raw
+/// mode once the process continues, returning `true` if all content has to be drawn again.
+#[cfg(all(unix, feature = "signal-hook"))]
+pub(crate) fn suspend(out: &mut impl std::io::Write, mouse: bool) -> std::io::Result<bool> {
+    use crosstermion::crossterm::{cursor, event, execute, terminal};
+    restore_terminal(out);
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    if mouse {
+        execute!(out, event::EnableMouseCapture)?;
+    }
+    Ok(true)
+}
+
+/// Do nothing, as the process can't be suspended here.
+#[cfg(not(all(unix, feature = "signal-hook")))]
+pub(crate) fn suspend(_out: &mut impl std::io::Write, _mouse: bool) -> std::io::Result<bool> {
+    Ok(false)
+}
+
+/// Remember the title of the terminal window on the stack of titles of the terminal behind `out`, to restore it with
+/// [`pop_terminal_title()`] later.
+pub(crate) fn push_terminal_title(out: &mut impl std::io::Write) -> std::io::Result<()> {
+    write!(out, "\x1b[22;0t")?;
+    out.flush()
+}
+
+/// Set the title of the terminal window or tab behind `out` to `title` with the `OSC 0` escape sequence, which
+/// terminals not supporting it ignore.
+pub(crate) fn set_terminal_title(out: &mut impl std::io::Write, title: &str) -> std::io::Result<()> {
+    // Control characters could end the escape sequence early.
+    let title: String = title.chars().filter(|c| !c.is_control()).collect();
+    write!(out, "\x1b]0;{title}\x07")?;
+    out.flush()
+}
+
+/// Restore the title of the terminal window behind `out` remembered by [`push_terminal_title()`].
+pub(crate) fn pop_terminal_title(out: &mut impl std::io::Write) -> std::io::Result<()> {
+    write!(out, "\x1b[23;0t")?;
+    out.flush()
+}
+
 pub const VERTICAL_LINE: &str = "│";
 
 pub use tui_react::{draw_text_nowrap_fn, draw_text_with_ellipsis_nowrap, util::*};
+
+/// A single line of a buffer, starting at the left of `bound`, to draw shared renderer elements into.
+pub struct BufferLine<'a> {
+    pub buf: &'a mut Buffer,
+    pub bound: Rect,
+}
+
+impl Canvas for BufferLine<'_> {
+    type Style = Style;
+
+    fn put(&mut self, column: u16, width: u16, text: &str, style: Style) -> u16 {
+        let bound = rect::intersect(
+            Rect {
+                x: self.bound.x.saturating_add(column),
+                width,
+                height: 1,
+                ..self.bound
+            },
+            self.bound,
+        );
+        if bound.width == 0 {
+            return 0;
+        }
+        draw_text_with_ellipsis_nowrap(bound, self.buf, text, style)
+    }
+}
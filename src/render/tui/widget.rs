@@ -0,0 +1,134 @@
+use tui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+use crate::{
+    Root,
+    messages::{Message, MessageCopyState},
+    progress::{self, Key, Task},
+    render::tui::{InterruptDrawInfo, Options, draw},
+};
+
+/// A widget to draw the dashboard of a progress tree into an area of a frame, for applications which have their own
+/// `ratatui` based user interface and want to show progress as one of its parts.
+///
+/// The [state](DashboardState) is kept by the caller and passed along with each frame:
+///
+/// ```rust,no_run
+/// # fn draw(frame: &mut prodash::render::tui::tui_export::Frame<'_>, root: &std::sync::Arc<prodash::tree::Root>, state: &mut prodash::render::tui::DashboardState) {
+/// frame.render_stateful_widget(prodash::render::tui::Dashboard::new(root), frame.area(), state);
+/// # }
+/// ```
+pub struct Dashboard<'a, T> {
+    progress: &'a T,
+}
+
+impl<'a, T: Root> Dashboard<'a, T> {
+    /// Create a new instance to draw `progress`.
+    pub fn new(progress: &'a T) -> Self {
+        Dashboard { progress }
+    }
+}
+
+/// The state of a [`Dashboard`], to be kept from frame to frame.
+pub struct DashboardState {
+    state: draw::State,
+    show_overall_eta: bool,
+    show_global_bar: bool,
+    entries: Vec<(Key, Task)>,
+    entries_generation: Option<usize>,
+    messages: Vec<Message>,
+    new_messages: Vec<Message>,
+    message_copy_state: Option<MessageCopyState>,
+}
+
+impl DashboardState {
+    /// Create a new instance configured with `options`.
+    ///
+    /// Options related to driving the terminal, like `frames_per_second`, `window_size` or `stop_if_progress_missing`,
+    /// have no effect as the application controls when and where to draw.
+    pub fn new(options: &Options) -> Self {
+        DashboardState {
+            state: options.draw_state(),
+            show_overall_eta: options.show_overall_eta,
+            show_global_bar: options.show_global_bar,
+            entries: Vec::new(),
+            entries_generation: None,
+            messages: Vec::new(),
+            new_messages: Vec::new(),
+            message_copy_state: None,
+        }
+    }
+}
+
+impl Default for DashboardState {
+    fn default() -> Self {
+        DashboardState::new(&Options::default())
+    }
+}
+
+impl<T: Root> StatefulWidget for Dashboard<'_, T> {
+    type State = DashboardState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.progress
+            .sorted_snapshot_if_changed(&mut state.entries, &mut state.entries_generation);
+        state.message_copy_state = Some(
+            self.progress
+                .copy_new_messages(&mut state.new_messages, state.message_copy_state.take()),
+        );
+        state.messages.append(&mut state.new_messages);
+        state
+            .messages
+            .drain(..state.messages.len().saturating_sub(self.progress.messages_capacity()));
+        if state.show_overall_eta || state.show_global_bar {
+            state.state.update_overall_eta(progress::overall_eta(&state.entries));
+        }
+        if let Some(critical_path) = state.state.critical_path.as_mut() {
+            *critical_path = progress::critical_path(&state.entries);
+        }
+        if state.show_global_bar {
+            state.state.overall_fraction = progress::overall_fraction(&state.entries);
+        }
+
+        draw::all(
+            &mut state.state,
+            InterruptDrawInfo::Instantly,
+            &state.entries,
+            &state.messages,
+            area,
+            buf,
+        );
+        state.state.next_tree_column_width = state.state.last_tree_column_width;
+    }
+}
+
+/// Draw a frame of `progress` into an area of `width` × `height` cells without any terminal, and return it as plain
+/// text with one line per row, without trailing whitespace.
+///
+/// Pass the same `state` to draw subsequent frames. This is useful for snapshot tests of dashboards.
+pub fn render_to_string(progress: &impl Root, state: &mut DashboardState, width: u16, height: u16) -> String {
+    let area = Rect::new(0, 0, width, height);
+    let mut buf = Buffer::empty(area);
+    Dashboard::new(progress).render(area, &mut buf, state);
+    buffer_to_string(&buf)
+}
+
+/// Return the symbols of all cells in `buf` as text, one line per row without trailing whitespace.
+pub(crate) fn buffer_to_string(buf: &Buffer) -> String {
+    use unicode_width::UnicodeWidthStr;
+
+    let area = buf.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let symbol = buf[(x, y)].symbol();
+            line.push_str(symbol);
+            // Cells covered by wide characters would add spaces that aren't visible.
+            x += symbol.width().max(1) as u16;
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
@@ -8,8 +8,49 @@ use crate::{progress, unit};
 
 const THROTTLE_INTERVAL: Duration = Duration::from_secs(1);
 const ONCE_A_SECOND: Duration = Duration::from_secs(1);
+/// The relative change in throughput below which it is considered steady, to not flip trends on tiny fluctuations.
+const STEADY_TOLERANCE: f64 = 0.1;
+/// The weight of the most recent throughput when blending it with the previous ones to estimate the time left,
+/// so bursts of progress don't make the estimate jump.
+const ETA_SMOOTHING: f64 = 0.3;
+/// The amount of throughput values to keep per task, one per second.
+const HISTORY_LEN: usize = 16;
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+/// The direction in which the throughput of a task is heading.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+pub enum Trend {
+    /// Throughput is increasing.
+    Increasing,
+    /// Throughput is decreasing.
+    Decreasing,
+    /// Throughput remains about the same.
+    Steady,
+}
+
+impl Trend {
+    fn between(previous: progress::Step, current: progress::Step) -> Self {
+        let tolerance = (previous as f64 * STEADY_TOLERANCE).max(1.0);
+        let change = current as f64 - previous as f64;
+        if change > tolerance {
+            Trend::Increasing
+        } else if change < -tolerance {
+            Trend::Decreasing
+        } else {
+            Trend::Steady
+        }
+    }
+
+    /// Return a symbol to represent this trend, like `▲`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Trend::Increasing => "▲",
+            Trend::Decreasing => "▼",
+            Trend::Steady => "▬",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
 struct State {
     observed: Duration,
     last_value: progress::Step,
@@ -17,6 +58,9 @@ struct State {
 
     last_update_duration: Duration,
     precomputed_throughput: Option<progress::Step>,
+    smoothed_throughput: Option<f64>,
+    trend: Option<Trend>,
+    history: VecDeque<progress::Step>,
 }
 
 impl State {
@@ -32,6 +76,9 @@ impl State {
 
             last_update_duration: elapsed,
             precomputed_throughput: None,
+            smoothed_throughput: None,
+            trend: None,
+            history: VecDeque::with_capacity(HISTORY_LEN),
         }
     }
 
@@ -59,7 +106,19 @@ impl State {
             .push_back((elapsed, value.saturating_sub(self.last_value)));
         self.last_value = value;
         if self.observed - self.last_update_duration > THROTTLE_INTERVAL {
-            self.precomputed_throughput = Some(self.compute_throughput());
+            let throughput = self.compute_throughput();
+            self.trend = self
+                .precomputed_throughput
+                .map(|previous| Trend::between(previous, throughput));
+            self.precomputed_throughput = Some(throughput);
+            self.smoothed_throughput = Some(match self.smoothed_throughput {
+                Some(previous) => previous * (1.0 - ETA_SMOOTHING) + throughput as f64 * ETA_SMOOTHING,
+                None => throughput as f64,
+            });
+            if self.history.len() == HISTORY_LEN {
+                self.history.pop_front();
+            }
+            self.history.push_back(throughput);
             self.last_update_duration = self.observed;
         }
         self.throughput()
@@ -76,7 +135,7 @@ impl State {
 /// A utility to compute throughput of a set of progress values usually available to a renderer.
 #[derive(Default)]
 pub struct Throughput {
-    sorted_by_key: Vec<(progress::Key, State)>,
+    per_key: progress::PerKey<State>,
     updated_at: Option<SystemTime>,
     elapsed: Option<Duration>,
 }
@@ -92,25 +151,59 @@ impl Throughput {
 
     /// Lookup or create the progress value at `key` and set its current `progress`, returning its computed
     /// throughput.
+    ///
+    /// The throughput of [paused](progress::Value::paused_since) progress isn't updated until it's resumed.
     pub fn update_and_get(
         &mut self,
         key: &progress::Key,
         progress: Option<&progress::Value>,
     ) -> Option<unit::display::Throughput> {
-        progress.and_then(|progress| {
-            self.elapsed
-                .and_then(|elapsed| match self.sorted_by_key.binary_search_by_key(key, |t| t.0) {
-                    Ok(index) => self.sorted_by_key[index]
-                        .1
-                        .update(progress.step.load(Ordering::SeqCst), elapsed),
-                    Err(index) => {
-                        let state = State::new(progress.step.load(Ordering::SeqCst), elapsed);
-                        let tp = state.throughput();
-                        self.sorted_by_key.insert(index, (*key, state));
-                        tp
-                    }
-                })
-        })
+        let progress = progress?;
+        let elapsed = self.elapsed?;
+        let step = progress.step.load(Ordering::SeqCst);
+        match self.per_key.get_mut(key) {
+            Some(state) if progress.paused_since.is_some() => state.throughput(),
+            Some(state) => state.update(step, elapsed),
+            None => self
+                .per_key
+                .get_or_insert_with(key, || State::new(step, elapsed))
+                .throughput(),
+        }
+    }
+
+    /// Return the trend of the throughput of the progress value at `key`, or `None` if it wasn't observed for long
+    /// enough yet.
+    ///
+    /// The trend is recomputed at most once a second, along with the throughput itself.
+    pub fn trend(&self, key: &progress::Key) -> Option<Trend> {
+        self.per_key.get(key).and_then(|state| state.trend)
+    }
+
+    /// Return the most recent throughputs of the progress value at `key` in steps per second, from oldest to newest,
+    /// to see how the throughput changed over time.
+    ///
+    /// Up to 16 values are kept, and the result is empty if the task wasn't observed for long enough yet.
+    pub fn history(&self, key: &progress::Key) -> Vec<progress::Step> {
+        self.per_key
+            .get(key)
+            .map(|state| state.history.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Return the estimated time until the bounded `progress` value at `key` is done, or `None` if it's unbounded,
+    /// not progressing or wasn't observed for long enough yet.
+    ///
+    /// The estimate is based on the throughput smoothed over time to tolerate bursty progress, and is recomputed
+    /// at most once a second along with the throughput itself.
+    pub fn eta(&self, key: &progress::Key, progress: &progress::Value) -> Option<Duration> {
+        let done_at = progress.done_at?;
+        let throughput = self
+            .per_key
+            .get(key)
+            .and_then(|state| state.smoothed_throughput)
+            .filter(|throughput| *throughput > 0.0)?;
+        let steps_left = done_at.saturating_sub(progress.steps_done());
+        Some(ONCE_A_SECOND.mul_f64(steps_left as f64 / throughput))
     }
 
     /// Compare the keys in `sorted_values` with our internal state and remove all missing tasks from it.
@@ -118,7 +211,6 @@ impl Throughput {
     /// This should be called after [`update_and_get(…)`][Throughput::update_and_get()] to pick up removed/finished
     /// progress.
     pub fn reconcile(&mut self, sorted_values: &[(progress::Key, progress::Task)]) {
-        self.sorted_by_key
-            .retain(|(key, _)| sorted_values.binary_search_by_key(key, |e| e.0).is_ok());
+        self.per_key.reconcile(sorted_values);
     }
 }
@@ -1,53 +1,158 @@
-#[cfg(feature = "local-time")]
-mod localtime {
-    use std::time::SystemTime;
+use std::time::SystemTime;
 
-    use jiff::Zoned;
+/// An `hours:minute:seconds` format.
+pub const DATE_TIME_HMS: usize = "00:51:45".len();
 
-    /// Return a string representing the current date and time as localtime.
-    ///
-    /// Available with the `localtime` feature toggle.
-    pub fn format_now_datetime_seconds() -> String {
-        Zoned::now().strftime("%F %T %Z").to_string()
-    }
+/// The timezone to display times in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Timezone {
+    /// The timezone the system is configured with.
+    Local,
+    /// Coordinated Universal Time.
+    Utc,
+}
+
+/// The environment variable naming the [`Timezone`] to use by default, as in `PRODASH_TIMEZONE=local`.
+pub const TIMEZONE_ENV_VAR: &str = "PRODASH_TIMEZONE";
 
-    /// Return a string representing the current time as localtime.
-    ///
-    /// Available with the `localtime` feature toggle.
-    pub fn format_time_for_messages(time: SystemTime) -> String {
-        Zoned::try_from(time)
-            .expect("system time is always in range -9999-01-01..=9999-12-31")
-            .strftime("%T")
-            .to_string()
+/// Defaults to [`Timezone::Local`] with the `local-time` feature toggle, and to [`Timezone::Utc`] without it.
+///
+/// Use [`Timezone::from_env()`] to let users override it.
+impl Default for Timezone {
+    fn default() -> Self {
+        if cfg!(feature = "local-time") {
+            Timezone::Local
+        } else {
+            Timezone::Utc
+        }
     }
 }
 
-/// An `hours:minute:seconds` format.
-pub const DATE_TIME_HMS: usize = "00:51:45".len();
+impl Timezone {
+    /// Return the timezone with the given `name`, which is `local` or `utc` ignoring case, or `None` otherwise.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "local" => Timezone::Local,
+            "utc" => Timezone::Utc,
+            _ => return None,
+        })
+    }
+
+    /// Return the timezone named by the [`PRODASH_TIMEZONE`](TIMEZONE_ENV_VAR) environment variable,
+    /// or the default timezone if it's unset or names no known timezone.
+    pub fn from_env() -> Self {
+        std::env::var(TIMEZONE_ENV_VAR)
+            .ok()
+            .and_then(|name| Timezone::from_name(&name))
+            .unwrap_or_default()
+    }
+
+    /// Return a string representing the current date and time in this timezone.
+    pub fn format_now_datetime_seconds(&self) -> String {
+        match self {
+            Timezone::Local => jiff::Zoned::now().strftime("%F %T %Z").to_string(),
+            Timezone::Utc => jiff::Timestamp::now().strftime("%FT%TZ").to_string(),
+        }
+    }
 
-#[cfg(not(feature = "local-time"))]
-mod utc {
-    use std::time::SystemTime;
+    /// Return a string representing the date and time of `time` in this timezone as specified by RFC 3339, with seconds
+    /// and the offset from UTC, which is written as `Z` in UTC.
+    pub fn format_rfc3339(&self, time: SystemTime) -> String {
+        match self {
+            Timezone::Local => jiff::Zoned::try_from(time)
+                .expect("system time is always in range -9999-01-01..=9999-12-31")
+                .strftime("%FT%T%:z")
+                .to_string(),
+            Timezone::Utc => jiff::Timestamp::try_from(time)
+                .expect("reasonable system time")
+                .strftime("%FT%TZ")
+                .to_string(),
+        }
+    }
 
-    use super::DATE_TIME_HMS;
+    /// Return a string representing the date and time of `time` in this timezone, with seconds.
+    pub fn format_datetime_seconds(&self, time: SystemTime) -> String {
+        match self {
+            Timezone::Local => jiff::Zoned::try_from(time)
+                .expect("system time is always in range -9999-01-01..=9999-12-31")
+                .strftime("%F %T")
+                .to_string(),
+            Timezone::Utc => jiff::Timestamp::try_from(time)
+                .expect("reasonable system time")
+                .strftime("%F %T")
+                .to_string(),
+        }
+    }
 
-    /// Return a string representing the current date and time as UTC.
-    ///
-    /// Available without the `localtime` feature toggle.
-    pub fn format_time_for_messages(time: SystemTime) -> String {
-        let time = jiff::Timestamp::try_from(time).expect("reasonable system time");
-        time.strftime("%T").to_string()
+    /// Return a string representing `time` in this timezone, suitable for messages.
+    pub fn format_time_for_messages(&self, time: SystemTime) -> String {
+        match self {
+            Timezone::Local => jiff::Zoned::try_from(time)
+                .expect("system time is always in range -9999-01-01..=9999-12-31")
+                .strftime("%T")
+                .to_string(),
+            Timezone::Utc => jiff::Timestamp::try_from(time)
+                .expect("reasonable system time")
+                .strftime("%T")
+                .to_string(),
+        }
     }
+}
+
+/// The way renderers show the time of messages, and the time at which tasks last made progress.
+#[derive(Debug, Default, Copy, Clone)]
+pub enum TimestampFormat {
+    /// The time of day, as in `00:51:45`.
+    #[default]
+    Time,
+    /// The date and the time of day, as in `2024-05-01 00:51:45`.
+    DateTime,
+    /// The date and the time of day with the offset from UTC as specified by RFC 3339, as in `2024-05-01T00:51:45Z`
+    /// in UTC or `2024-05-01T02:51:45+02:00` in local time.
+    Rfc3339,
+    /// The time that passed since, as in `3s ago` or `2m 13s ago`.
+    Relative,
+    /// The string returned by the given function, called with the time to format and the configured timezone.
+    Custom(fn(SystemTime, Timezone) -> String),
+}
 
-    /// Return a string representing the current time as UTC.
-    ///
-    /// Available without the `localtime` feature toggle.
-    pub fn format_now_datetime_seconds() -> String {
-        jiff::Timestamp::now().strftime("%FT%T").to_string()
+impl TimestampFormat {
+    /// Return a string representing `time` in `timezone` in this format.
+    pub fn format(&self, time: SystemTime, timezone: Timezone) -> String {
+        match self {
+            TimestampFormat::Time => timezone.format_time_for_messages(time),
+            TimestampFormat::DateTime => timezone.format_datetime_seconds(time),
+            TimestampFormat::Rfc3339 => timezone.format_rfc3339(time),
+            TimestampFormat::Relative => {
+                let secs = SystemTime::now().duration_since(time).unwrap_or_default().as_secs();
+                let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+                match (hours, minutes) {
+                    (0, 0) => format!("{seconds}s ago"),
+                    (0, _) => format!("{minutes}m {seconds}s ago"),
+                    _ => format!("{hours}h {minutes}m ago"),
+                }
+            }
+            TimestampFormat::Custom(format) => format(time, timezone),
+        }
+    }
+
+    /// Return the width of all strings in this format, if it's fixed.
+    pub fn fixed_width(&self) -> Option<usize> {
+        match self {
+            TimestampFormat::Time => Some(DATE_TIME_HMS),
+            TimestampFormat::DateTime => Some("2024-05-01 00:51:45".len()),
+            // The offset takes more space in local time than the `Z` of UTC.
+            TimestampFormat::Rfc3339 | TimestampFormat::Relative | TimestampFormat::Custom(_) => None,
+        }
     }
 }
 
-#[cfg(feature = "local-time")]
-pub use localtime::*;
-#[cfg(not(feature = "local-time"))]
-pub use utc::*;
+/// Return a string representing the current date and time in the [default timezone][Timezone::default()].
+pub fn format_now_datetime_seconds() -> String {
+    Timezone::default().format_now_datetime_seconds()
+}
+
+/// Return a string representing `time` in the [default timezone][Timezone::default()], suitable for messages.
+pub fn format_time_for_messages(time: SystemTime) -> String {
+    Timezone::default().format_time_for_messages(time)
+}
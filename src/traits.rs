@@ -52,7 +52,22 @@ pub trait Count {
 /// An object-safe trait for describing hierarchical progress.
 ///
 /// This will be automatically implemented for any type that implements
-/// [`NestedProgress`].
+/// [`NestedProgress`], like `tree::Item` or [`Discard`](crate::progress::Discard),
+/// which allows libraries to accept any progress implementation without depending on a concrete one.
+///
+/// ```rust
+/// use prodash::{Count, DynNestedProgress, Progress};
+///
+/// fn work(progress: &mut dyn DynNestedProgress) {
+///     let mut child = progress.add_child("step".into());
+///     child.init(Some(2), None);
+///     child.inc();
+///     child.inc();
+///     child.done("finished".into());
+/// }
+///
+/// work(&mut prodash::progress::Discard);
+/// ```
 pub trait DynNestedProgress: Progress + impls::Sealed {
     /// See [`NestedProgress::add_child`]
     fn add_child(&mut self, name: String) -> BoxedDynNestedProgress;
@@ -138,6 +153,18 @@ pub trait Progress: Count + Send + Sync {
     fn fail(&self, message: String) {
         self.message(MessageLevel::Failure, message)
     }
+    /// Create a message warning about something unexpected
+    fn warn(&self, message: String) {
+        self.message(MessageLevel::Warning, message)
+    }
+    /// Create a message with information to help debugging
+    fn debug(&self, message: String) {
+        self.message(MessageLevel::Debug, message)
+    }
+    /// Create a message with very detailed information about what the task is doing
+    fn trace(&self, message: String) {
+        self.message(MessageLevel::Trace, message)
+    }
     /// A shorthand to print throughput information
     fn show_throughput(&self, start: Instant) {
         let step = self.step();
@@ -181,6 +208,29 @@ pub trait Progress: Count + Send + Sync {
 
         self.message(level, buf);
     }
+
+    /// Wrap `inner` so that all bytes read from it are counted as progress, using a unit for bytes unless
+    /// one is set already.
+    ///
+    /// `inner` may implement [`std::io::Read`] or [`std::io::BufRead`], or their async counterparts with the
+    /// `progress-async` feature.
+    fn reader<R>(self, inner: R) -> progress::Reader<R, Self>
+    where
+        Self: Sized,
+    {
+        progress::Reader::new(inner, self)
+    }
+
+    /// Wrap `inner` so that all bytes written to it are counted as progress, using a unit for bytes unless
+    /// one is set already.
+    ///
+    /// `inner` may implement [`std::io::Write`], or its async counterpart with the `progress-async` feature.
+    fn writer<W>(self, inner: W) -> progress::Writer<W, Self>
+    where
+        Self: Sized,
+    {
+        progress::Writer::new(inner, self)
+    }
 }
 
 use crate::{
@@ -216,6 +266,25 @@ pub trait Root {
     /// The `out` vec will be cleared automatically.
     fn sorted_snapshot(&self, out: &mut Vec<(progress::Key, progress::Task)>);
 
+    /// Like [`sorted_snapshot()`][Root::sorted_snapshot()], but leave `out` as is if the tree didn't change since
+    /// `generation` was set by the previous call, returning `true` only if `out` was updated.
+    ///
+    /// By default, `out` is always updated.
+    fn sorted_snapshot_if_changed(
+        &self,
+        out: &mut Vec<(progress::Key, progress::Task)>,
+        _generation: &mut Option<usize>,
+    ) -> bool {
+        self.sorted_snapshot(out);
+        true
+    }
+
+    /// Returns a number that changes whenever anything in the tree changes, or `None` if that isn't known, which
+    /// is the default.
+    fn changes(&self) -> Option<usize> {
+        None
+    }
+
     /// Copy all messages from the internal ring buffer into the given `out`
     /// vector. Messages are ordered from oldest to newest.
     fn copy_messages(&self, out: &mut Vec<Message>);
@@ -341,6 +410,18 @@ mod impls {
             self.deref().fail(message)
         }
 
+        fn warn(&self, message: String) {
+            self.deref().warn(message)
+        }
+
+        fn debug(&self, message: String) {
+            self.deref().debug(message)
+        }
+
+        fn trace(&self, message: String) {
+            self.deref().trace(message)
+        }
+
         fn show_throughput(&self, start: Instant) {
             self.deref().show_throughput(start)
         }
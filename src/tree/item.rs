@@ -5,13 +5,13 @@ use std::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
-    time::SystemTime,
+    time::{Instant, SystemTime},
 };
 
 use parking_lot::Mutex;
 
 use crate::{
-    messages::MessageLevel,
+    messages::{Message, MessageLevel},
     progress::{Id, State, Step, StepShared, Task, Value},
     tree::Item,
     unit::Unit,
@@ -19,7 +19,29 @@ use crate::{
 
 impl Drop for Item {
     fn drop(&mut self) {
+        if self.summarize_on_drop {
+            let elapsed;
+            #[cfg(feature = "progress-tree-hp-hashmap")]
+            {
+                elapsed = self
+                    .tree
+                    .get(&self.key)
+                    .and_then(|r| r.value().progress.as_ref().and_then(Value::elapsed));
+            }
+            #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+            {
+                elapsed = self
+                    .tree
+                    .get(&self.key, |v| v.progress.as_ref().and_then(Value::elapsed))
+                    .flatten();
+            }
+            if let Some(elapsed) = elapsed {
+                let start = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+                crate::Progress::show_throughput(self, start);
+            }
+        }
         self.tree.remove(&self.key);
+        self.mark_changed();
     }
 }
 
@@ -55,6 +77,7 @@ impl Item {
                     done_at: max,
                     unit,
                     step: Arc::clone(&self.value),
+                    started_at: Some(SystemTime::now()),
                     ..Default::default()
                 })
             };
@@ -67,10 +90,27 @@ impl Item {
                     done_at: max,
                     unit,
                     step: Arc::clone(&self.value),
+                    started_at: Some(SystemTime::now()),
                     ..Default::default()
                 });
             });
         }
+        self.mark_changed();
+    }
+
+    /// Advance the generation of the tree after it was changed other than by a step, see [`Root::generation()`].
+    ///
+    /// [`Root::generation()`]: crate::tree::Root::generation()
+    fn mark_changed(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.mark_messaged();
+    }
+
+    /// Advance the amount of changes only, as messages don't need new snapshots, see [`Root::changes()`].
+    ///
+    /// [`Root::changes()`]: crate::tree::Root::changes()
+    fn mark_messaged(&self) {
+        self.changes.fetch_add(1, Ordering::Relaxed);
     }
 
     fn alter_progress(&self, f: impl FnMut(&mut Value)) {
@@ -91,6 +131,7 @@ impl Item {
                 v.progress.as_mut().map(f);
             });
         }
+        self.mark_changed();
     }
 
     /// Set the name of this task's progress to the given `name`.
@@ -107,6 +148,7 @@ impl Item {
                 v.name = name.into();
             });
         }
+        self.mark_changed();
     }
 
     /// Get the name of this task's progress
@@ -159,22 +201,21 @@ impl Item {
 
     /// Set the maximum value to `max` and return the old maximum value.
     pub fn set_max(&self, max: Option<Step>) -> Option<Step> {
+        let prev;
         #[cfg(feature = "progress-tree-hp-hashmap")]
         {
-            self.tree
-                .get_mut(&self.key)?
-                .value_mut()
-                .progress
-                .as_mut()
-                .and_then(|p| {
+            prev = self.tree.get_mut(&self.key).and_then(|mut r| {
+                r.value_mut().progress.as_mut().and_then(|p| {
                     let prev = p.done_at;
                     p.done_at = max;
                     prev
                 })
+            });
         }
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         {
-            self.tree
+            prev = self
+                .tree
                 .get_mut(&self.key, |v| {
                     v.progress.as_mut().and_then(|p| {
                         let prev = p.done_at;
@@ -182,8 +223,10 @@ impl Item {
                         prev
                     })
                 })
-                .flatten()
+                .flatten();
         }
+        self.mark_changed();
+        prev
     }
 
     /// Returns the (cloned) unit associated with this Progress
@@ -223,6 +266,28 @@ impl Item {
         self.value.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Return the counter holding the current progress, to be updated from hot loops with relaxed atomic operations
+    /// that don't involve the tree at all. Renderers sample it whenever they draw.
+    ///
+    /// It remains the counter of this task even if it's initialized again.
+    pub fn counter(&self) -> StepShared {
+        Arc::clone(&self.value)
+    }
+
+    /// Increment the amount of failed steps by the given `step`, which are shown separately from the successful ones.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    pub fn inc_failed_by(&self, step: Step) {
+        self.alter_progress(|p| p.failed = p.failed.saturating_add(step));
+    }
+
+    /// Increment the amount of failed steps by one.
+    ///
+    /// **Note**: that this call has no effect unless `init(…)` was called before.
+    pub fn inc_failed(&self) {
+        self.inc_failed_by(1);
+    }
+
     /// Call to indicate that progress cannot be indicated, and that the task cannot be interrupted.
     /// Use this, as opposed to `halted(…)`, if a non-interruptable call is about to be made without support
     /// for any progress indication.
@@ -230,9 +295,14 @@ impl Item {
     /// If `eta` is `Some(…)`, it specifies the time at which this task is expected to
     /// make progress again.
     ///
-    /// The halted-state is undone next time [`tree::Item::running(…)`][Item::running()] is called.
+    /// The halted-state is undone next time [`tree::Item::running(…)`][Item::running()] is called, and
+    /// renderers show for how long the task has been blocked or halted until then.
     pub fn blocked(&self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Blocked(reason, eta));
+        self.alter_progress(|p| {
+            p.state = State::Blocked(reason, eta);
+            p.detail = None;
+            p.stopped_since.get_or_insert_with(SystemTime::now);
+        });
     }
 
     /// Call to indicate that progress cannot be indicated, even though the task can be interrupted.
@@ -244,13 +314,61 @@ impl Item {
     ///
     /// The halted-state is undone next time [`tree::Item::running(…)`][Item::running()] is called.
     pub fn halted(&self, reason: &'static str, eta: Option<SystemTime>) {
-        self.alter_progress(|p| p.state = State::Halted(reason, eta));
+        self.alter_progress(|p| {
+            p.state = State::Halted(reason, eta);
+            p.detail = None;
+            p.stopped_since.get_or_insert_with(SystemTime::now);
+        });
+    }
+
+    /// Like [`halted(…)`][Item::halted()], but additionally attaches a short `detail` to explain the failure
+    /// to be displayed next to the task.
+    ///
+    /// The detail is removed next time the state changes.
+    pub fn halted_with_detail(&self, reason: &'static str, detail: impl Into<Arc<str>>) {
+        let detail = detail.into();
+        self.alter_progress(|p| {
+            p.state = State::Halted(reason, None);
+            p.detail = Some(Arc::clone(&detail));
+            p.stopped_since.get_or_insert_with(SystemTime::now);
+        });
     }
 
     /// Call to indicate that progress is back in running state, which should be called after the reason for
     /// calling `blocked()` or `halted()` has passed.
     pub fn running(&self) {
-        self.alter_progress(|p| p.state = State::Running);
+        self.alter_progress(|p| {
+            p.state = State::Running;
+            p.detail = None;
+            p.stopped_since = None;
+        });
+    }
+
+    /// Indicate that this task is paused, for instance by a scheduler under load, so the time until it's
+    /// [resumed][Item::resume()] isn't counted as time it's running, and its throughput isn't computed meanwhile.
+    ///
+    /// Pausing is independent of the [state][State] of the task, and pausing it again has no effect.
+    pub fn pause(&self) {
+        self.alter_progress(|p| {
+            p.paused_since.get_or_insert_with(SystemTime::now);
+        });
+    }
+
+    /// Indicate that this task isn't [paused][Item::pause()] anymore.
+    pub fn resume(&self) {
+        self.alter_progress(|p| {
+            if let Some(since) = p.paused_since.take() {
+                p.paused_for += since.elapsed().unwrap_or_default();
+            }
+        });
+    }
+
+    /// Indicate that this task is waiting on `blocker`, or pass `None` to indicate it doesn't wait anymore.
+    ///
+    /// This is for display only, and the indicator remains until it's reset here or `blocker` is removed.
+    pub fn waiting_on(&self, blocker: Option<&Item>) {
+        let blocked_by = blocker.map(|item| item.key);
+        self.alter_progress(|p| p.blocked_by = blocked_by);
     }
 
     /// Adds a new child `Tree`, whose parent is this instance, with the given `name`.
@@ -279,44 +397,89 @@ impl Item {
         #[cfg(not(feature = "progress-tree-hp-hashmap"))]
         self.tree.insert(child_key, task);
         self.highest_child_id = self.highest_child_id.wrapping_add(1);
+        self.mark_changed();
         Item {
             highest_child_id: 0,
             value: Default::default(),
             key: child_key,
             tree: Arc::clone(&self.tree),
             messages: Arc::clone(&self.messages),
+            generation: Arc::clone(&self.generation),
+            changes: Arc::clone(&self.changes),
+            summarize_on_drop: self.summarize_on_drop,
         }
     }
 
+    /// If `enabled`, send a message summarizing how much progress was made and how long it took, like
+    /// `done 4123 items in 12.00s (343 items/s)`, when this task is dropped, which also removes it from the tree.
+    ///
+    /// Tasks inherit this setting from their parent when they are added, and it's off unless enabled with
+    /// [`Options::summarize_on_drop`][crate::tree::root::Options::summarize_on_drop].
+    /// Tasks that were never [initialized][Item::init()] aren't summarized.
+    pub fn set_summarize_on_drop(&mut self, enabled: bool) {
+        self.summarize_on_drop = enabled;
+    }
+
     /// Create a `message` of the given `level` and store it with the progress tree.
     ///
     /// Use this to provide additional,human-readable information about the progress
     /// made, including indicating success or failure.
     pub fn message(&self, level: MessageLevel, message: impl Into<String>) {
-        let message: String = message.into();
-        self.messages.lock().push_overwrite(
+        let name;
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        {
+            name = self.tree.get(&self.key).map(|v| v.name.to_owned()).unwrap_or_default();
+        }
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        {
+            name = self.tree.get(&self.key, |v| v.name.to_owned()).unwrap_or_default()
+        }
+        self.message_with_origin(level, name, message)
+    }
+
+    /// Like [`message(…)`][Item::message()], but with the given `origin` instead of the name of this task,
+    /// to be able to group messages under a label like `system`.
+    pub fn message_with_origin(&self, level: MessageLevel, origin: impl Into<String>, message: impl Into<String>) {
+        self.push_message(level, origin.into(), message.into(), Vec::new())
+    }
+
+    /// Like [`message(…)`][Item::message()], but with structured `fields` as pairs of names and values attached to it.
+    ///
+    /// The terminal user interface shows them below the message on demand, and renderers of JSON emit them as object.
+    pub fn message_with_fields<K, V>(
+        &self,
+        level: MessageLevel,
+        message: impl Into<String>,
+        fields: impl IntoIterator<Item = (K, V)>,
+    ) where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let name = self.name().unwrap_or_default();
+        let fields = fields.into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+        self.push_message(level, name, message.into(), fields)
+    }
+
+    fn push_message(&self, level: MessageLevel, origin: String, message: String, fields: Vec<(String, String)>) {
+        #[cfg(feature = "progress-tree-log")]
+        {
+            let fields: String = fields.iter().map(|(k, v)| format!(" {k}={v}")).collect();
+            match level {
+                MessageLevel::Failure | MessageLevel::Warning => crate::warn!("{} → {}{}", origin, message, fields),
+                MessageLevel::Info | MessageLevel::Success => crate::info!("{} → {}{}", origin, message, fields),
+                MessageLevel::Debug => crate::debug!("{} → {}{}", origin, message, fields),
+                MessageLevel::Trace => crate::trace!("{} → {}{}", origin, message, fields),
+            };
+        }
+
+        self.messages.lock().push(Message {
+            time: SystemTime::now(),
             level,
-            {
-                let name;
-                #[cfg(feature = "progress-tree-hp-hashmap")]
-                {
-                    name = self.tree.get(&self.key).map(|v| v.name.to_owned()).unwrap_or_default();
-                }
-                #[cfg(not(feature = "progress-tree-hp-hashmap"))]
-                {
-                    name = self.tree.get(&self.key, |v| v.name.to_owned()).unwrap_or_default()
-                }
-
-                #[cfg(feature = "progress-tree-log")]
-                match level {
-                    MessageLevel::Failure => crate::warn!("{} → {}", name, message),
-                    MessageLevel::Info | MessageLevel::Success => crate::info!("{} → {}", name, message),
-                };
-
-                name
-            },
+            origin,
             message,
-        )
+            fields,
+        });
+        self.mark_messaged();
     }
 
     /// Create a message indicating the task is done
@@ -334,6 +497,21 @@ impl Item {
         self.message(MessageLevel::Info, message)
     }
 
+    /// Create a message warning about something unexpected.
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Warning, message)
+    }
+
+    /// Create a message with information to help debugging.
+    pub fn debug(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Debug, message)
+    }
+
+    /// Create a message with very detailed information about what the task is doing.
+    pub fn trace(&mut self, message: impl Into<String>) {
+        self.message(MessageLevel::Trace, message)
+    }
+
     pub(crate) fn deep_clone(&self) -> Item {
         Item {
             key: self.key,
@@ -341,6 +519,9 @@ impl Item {
             highest_child_id: self.highest_child_id,
             tree: Arc::new(self.tree.deref().clone()),
             messages: Arc::new(Mutex::new(self.messages.lock().clone())),
+            generation: Arc::new(AtomicUsize::new(self.generation.load(Ordering::SeqCst))),
+            changes: Arc::new(AtomicUsize::new(self.changes.load(Ordering::Relaxed))),
+            summarize_on_drop: self.summarize_on_drop,
         }
     }
 }
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::{messages::MessageLevel, tree::Root};
+
+/// A [`log::Log`] implementation storing all records as messages of a progress tree, to show log output of dependencies
+/// in the message pane of renderers instead of letting it interfere with them.
+///
+/// Records of level `ERROR` become failures, `WARN` warnings, `INFO` information, `DEBUG` debug and `TRACE` trace
+/// messages, with the target of the record as origin.
+/// Records logged by this crate itself are ignored, as with the `progress-tree-log` feature they are copies of messages
+/// that are already in the tree.
+///
+/// ```rust
+/// let root = prodash::tree::Root::new();
+/// prodash::tree::log::Logger::new(root.clone())
+///     .install(log::LevelFilter::Info)
+///     .expect("no other logger is installed");
+/// log::warn!("careful");
+/// let mut messages = Vec::new();
+/// root.copy_messages(&mut messages);
+/// assert_eq!(messages[0].message, "careful");
+/// ```
+pub struct Logger {
+    root: Arc<Root>,
+}
+
+impl Logger {
+    /// Create a new instance to add messages to `root`.
+    pub fn new(root: Arc<Root>) -> Self {
+        Logger { root }
+    }
+
+    /// Install this instance as global logger, passing on records up to `max_level`.
+    ///
+    /// This fails if a logger was installed already.
+    pub fn install(self, max_level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        !metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = match record.level() {
+            log::Level::Error => MessageLevel::Failure,
+            log::Level::Warn => MessageLevel::Warning,
+            log::Level::Info => MessageLevel::Info,
+            log::Level::Debug => MessageLevel::Debug,
+            log::Level::Trace => MessageLevel::Trace,
+        };
+        self.root.message(level, record.target(), record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
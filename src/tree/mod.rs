@@ -4,6 +4,7 @@ use crate::messages::MessageRingBuffer;
 #[derive(Debug)]
 pub struct Root {
     pub(crate) inner: parking_lot::Mutex<Item>,
+    pub(crate) aggregation: root::Aggregation,
 }
 
 /// A `Tree` represents an element of the progress tree.
@@ -29,6 +30,11 @@ pub struct Item {
     pub(crate) highest_child_id: crate::progress::key::Id,
     pub(crate) tree: std::sync::Arc<HashMap<crate::progress::Key, crate::progress::Task>>,
     pub(crate) messages: std::sync::Arc<parking_lot::Mutex<MessageRingBuffer>>,
+    /// Advanced whenever the tree changes other than by steps, shared by all items of a tree.
+    pub(crate) generation: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    /// Advanced whenever the tree changes other than by steps or messages are sent, shared by all items of a tree.
+    pub(crate) changes: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    pub(crate) summarize_on_drop: bool,
 }
 
 #[cfg(feature = "dashmap")]
@@ -39,51 +45,94 @@ type HashMap<K, V> = sync::HashMap<K, V>;
 
 #[cfg(not(feature = "dashmap"))]
 pub(crate) mod sync {
-    pub struct HashMap<K, V>(parking_lot::Mutex<std::collections::HashMap<K, V>>);
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    /// A map split into shards that are locked independently, so threads working on different keys rarely contend.
+    pub struct HashMap<K, V> {
+        shards: Box<[parking_lot::Mutex<std::collections::HashMap<K, V>>]>,
+    }
 
     impl<K, V> HashMap<K, V>
     where
-        K: Eq + std::hash::Hash,
+        K: Eq + Hash,
     {
         pub fn with_capacity(cap: usize) -> Self {
-            HashMap(parking_lot::Mutex::new(std::collections::HashMap::with_capacity(cap)))
+            let num_shards = std::thread::available_parallelism()
+                .map_or(1, std::num::NonZeroUsize::get)
+                .next_power_of_two()
+                * 4;
+            HashMap {
+                shards: (0..num_shards)
+                    .map(|_| parking_lot::Mutex::new(std::collections::HashMap::with_capacity(cap / num_shards)))
+                    .collect(),
+            }
+        }
+        fn shard(&self, key: &K) -> &parking_lot::Mutex<std::collections::HashMap<K, V>> {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            &self.shards[hasher.finish() as usize % self.shards.len()]
         }
         pub fn extend_to(&self, out: &mut Vec<(K, V)>)
         where
             K: Clone,
             V: Clone,
         {
-            let lock = self.0.lock();
-            out.extend(lock.iter().map(|(k, v)| (k.clone(), v.clone())))
+            for shard in self.shards.iter() {
+                let lock = shard.lock();
+                out.extend(lock.iter().map(|(k, v)| (k.clone(), v.clone())))
+            }
         }
         pub fn remove(&self, key: &K) -> Option<V> {
-            self.0.lock().remove(key)
+            self.shard(key).lock().remove(key)
         }
         pub fn get<T>(&self, key: &K, cb: impl FnOnce(&V) -> T) -> Option<T> {
-            self.0.lock().get(key).map(cb)
+            self.shard(key).lock().get(key).map(cb)
         }
         pub fn get_mut<T>(&self, key: &K, cb: impl FnOnce(&mut V) -> T) -> Option<T> {
-            self.0.lock().get_mut(key).map(cb)
+            self.shard(key).lock().get_mut(key).map(cb)
         }
         pub fn insert(&self, key: K, value: V) {
-            self.0.lock().insert(key, value);
+            self.shard(&key).lock().insert(key, value);
+        }
+        pub fn for_each(&self, mut cb: impl FnMut(&K, &V)) {
+            for shard in self.shards.iter() {
+                shard.lock().iter().for_each(|(k, v)| cb(k, v));
+            }
         }
         pub fn len(&self) -> usize {
-            self.0.lock().len()
+            self.shards.iter().map(|shard| shard.lock().len()).sum()
         }
         pub fn clone(&self) -> Self
         where
             K: Clone,
             V: Clone,
         {
-            HashMap(parking_lot::Mutex::new(self.0.lock().clone()))
+            HashMap {
+                shards: self
+                    .shards
+                    .iter()
+                    .map(|shard| parking_lot::Mutex::new(shard.lock().clone()))
+                    .collect(),
+            }
         }
     }
 }
 
 mod item;
+#[cfg(feature = "progress-tree-logger")]
+///
+pub mod log;
 ///
 pub mod root;
+#[cfg(feature = "serde")]
+///
+pub mod snapshot;
+#[cfg(feature = "test-util")]
+///
+pub mod test_util;
+#[cfg(feature = "progress-tree-tracing")]
+///
+pub mod tracing;
 
 #[cfg(test)]
 mod tests;
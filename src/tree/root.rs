@@ -1,13 +1,18 @@
 use std::{
+    hash::{DefaultHasher, Hash, Hasher},
     ops::Deref,
-    sync::{Arc, Weak, atomic::AtomicUsize},
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, SystemTime},
 };
 
 use parking_lot::Mutex;
 
 use crate::{
-    messages::{Message, MessageCopyState, MessageRingBuffer},
-    progress::{Id, Key, Task},
+    messages::{Message, MessageCopyState, MessageLevel, MessageRingBuffer},
+    progress::{Id, Key, Step, Task, Value, earliest},
     tree::{Item, Root},
 };
 
@@ -22,7 +27,17 @@ impl Root {
 
     /// Returns the maximum amount of messages we can keep before overwriting older ones.
     pub fn messages_capacity(&self) -> usize {
-        self.inner.lock().messages.lock().buf.capacity()
+        self.inner.lock().messages.lock().capacity()
+    }
+
+    /// Change the maximum amount of messages we can keep to `capacity`, dropping the oldest ones if there are more.
+    pub fn set_messages_capacity(&self, capacity: usize) {
+        self.inner.lock().messages.lock().set_capacity(capacity)
+    }
+
+    /// Returns the amount of messages that were dropped as the message buffer was full, or shrunk.
+    pub fn messages_dropped(&self) -> usize {
+        self.inner.lock().messages.lock().dropped()
     }
 
     /// Returns the current amount of `Item`s stored in the tree.
@@ -54,9 +69,65 @@ impl Root {
         self.inner.lock().add_child_with_id(name, id)
     }
 
+    /// Create a `message` of the given `level` with the given `origin`, for messages that don't belong to any task.
+    pub fn message(&self, level: MessageLevel, origin: impl Into<String>, message: impl Into<String>) {
+        self.inner.lock().message_with_origin(level, origin, message)
+    }
+
     /// Copy the entire progress tree into the given `out` vector, so that
     /// it can be traversed from beginning to end in order of hierarchy.
+    ///
+    /// Tasks without progress show the progress of their children as configured by [`Options::aggregation`].
     pub fn sorted_snapshot(&self, out: &mut Vec<(Key, Task)>) {
+        self.sorted_snapshot_without_aggregation(out);
+        self.aggregation.apply(out);
+    }
+
+    /// Returns a number that changes whenever tasks are added, removed or changed in any way other than by their
+    /// step, which is shared with snapshots and thus always up to date.
+    pub fn generation(&self) -> usize {
+        self.inner.lock().generation.load(Ordering::SeqCst)
+    }
+
+    /// Returns a number that changes whenever the tree changes in any way, including steps and messages, to learn
+    /// whether anything changed since the last call without taking a snapshot.
+    ///
+    /// Steps are sampled from all tasks here, so making them stays a single atomic operation on the task's own counter,
+    /// which is why steps made through an [`Item::counter()`][crate::tree::Item::counter()] are seen as well.
+    pub fn changes(&self) -> usize {
+        let inner = self.inner.lock();
+        let mut hasher = DefaultHasher::new();
+        inner.changes.load(Ordering::Relaxed).hash(&mut hasher);
+        let mut hash_step = |task: &Task| {
+            if let Some(progress) = task.progress.as_ref() {
+                progress.step.load(Ordering::Relaxed).hash(&mut hasher);
+            }
+        };
+        #[cfg(feature = "progress-tree-hp-hashmap")]
+        inner.tree.iter().for_each(|r| hash_step(r.value()));
+        #[cfg(not(feature = "progress-tree-hp-hashmap"))]
+        inner.tree.for_each(|_key, task| hash_step(task));
+        hasher.finish() as usize
+    }
+
+    /// Like [`sorted_snapshot()`][Root::sorted_snapshot()], but leave `out` as is if the tree didn't change since
+    /// `generation` was set by the previous call, returning `true` only if `out` was updated.
+    ///
+    /// As the steps of tasks are shared with snapshots, reusing `out` is possible for as long as it isn't modified.
+    pub fn sorted_snapshot_if_changed(&self, out: &mut Vec<(Key, Task)>, generation: &mut Option<usize>) -> bool {
+        let current = self.generation();
+        // Aggregated progress doesn't share the steps of children and has to be recomputed.
+        if *generation == Some(current) && self.aggregation == Aggregation::Off {
+            return false;
+        }
+        self.sorted_snapshot(out);
+        *generation = Some(current);
+        true
+    }
+
+    /// Like [`sorted_snapshot()`][Root::sorted_snapshot()], but without aggregated progress so each step is only
+    /// counted once.
+    fn sorted_snapshot_without_aggregation(&self, out: &mut Vec<(Key, Task)>) {
         out.clear();
         #[cfg(feature = "progress-tree-hp-hashmap")]
         out.extend(self.inner.lock().tree.iter().map(|r| (*r.key(), r.value().clone())));
@@ -83,8 +154,52 @@ impl Root {
     pub fn deep_clone(&self) -> Arc<Root> {
         Arc::new(Root {
             inner: Mutex::new(self.inner.lock().deep_clone()),
+            aggregation: self.aggregation,
         })
     }
+
+    /// Return a copy of all tasks and messages that can be serialized, to persist it or send it to remote viewers.
+    #[cfg(feature = "serde")]
+    pub fn serializable_snapshot(&self) -> crate::tree::snapshot::Snapshot {
+        let mut tasks = Vec::new();
+        self.sorted_snapshot(&mut tasks);
+        let mut messages = Vec::new();
+        self.copy_messages(&mut messages);
+        crate::tree::snapshot::Snapshot {
+            tasks: tasks.into_iter().map(Into::into).collect(),
+            messages,
+        }
+    }
+
+    /// Estimate the time it takes until all bounded tasks currently in the tree are done, or `None` if there are none
+    /// or no progress was made yet.
+    ///
+    /// See [`progress::overall_eta()`][crate::progress::overall_eta()] for how it's computed. Renderers that already
+    /// took a snapshot should call it directly.
+    pub fn overall_eta(&self) -> Option<Duration> {
+        let mut entries = Vec::new();
+        self.sorted_snapshot(&mut entries);
+        crate::progress::overall_eta(&entries)
+    }
+
+    /// Return the fraction of work done across all bounded tasks, between `0.0` and `1.0`, or `None` if there are none.
+    ///
+    /// See [`progress::overall_fraction()`][crate::progress::overall_fraction()] for how it's computed.
+    pub fn overall_fraction(&self) -> Option<f32> {
+        let mut entries = Vec::new();
+        self.sorted_snapshot(&mut entries);
+        crate::progress::overall_fraction(&entries)
+    }
+
+    /// Return the keys of the chain of tasks that is expected to determine how long it takes until all work is done,
+    /// ordered from the first task of the chain to the last.
+    ///
+    /// See [`progress::critical_path()`][crate::progress::critical_path()] for how it's computed.
+    pub fn critical_path(&self) -> Vec<Key> {
+        let mut entries = Vec::new();
+        self.sorted_snapshot(&mut entries);
+        crate::progress::critical_path(&entries)
+    }
 }
 
 /// A way to configure new [`tree::Root`](./tree/struct.Root.html) instances
@@ -98,6 +213,88 @@ pub struct Options {
     pub initial_capacity: usize,
     /// The amount of messages we can hold before we start overwriting old ones.
     pub message_buffer_capacity: usize,
+    /// If true (default: false), [items][Item] send a message summarizing their progress when dropped,
+    /// see [`Item::set_summarize_on_drop()`].
+    pub summarize_on_drop: bool,
+    /// How tasks without progress of their own show the progress of their children (default: [`Aggregation::Off`]).
+    pub aggregation: Aggregation,
+}
+
+/// How tasks that merely structure the tree, without progress of their own, show the progress of their children
+/// in [`Root::sorted_snapshot()`].
+///
+/// Only direct children with bounded progress are considered, including those that show the aggregated progress of
+/// their own children.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Aggregation {
+    /// Tasks without progress show none.
+    #[default]
+    Off,
+    /// Show the mean of the fractions of the children as steps out of `100`, with each child counting the same.
+    Mean,
+    /// Show the sum of the steps of the children out of the sum of their totals, so children with more
+    /// steps to do count more.
+    Weighted,
+}
+
+impl Aggregation {
+    /// Set the progress of all tasks without progress in `sorted` to the aggregated progress of their children.
+    fn apply(self, sorted: &mut [(Key, Task)]) {
+        if self == Aggregation::Off {
+            return;
+        }
+        for index in (0..sorted.len()).rev() {
+            if sorted[index].1.progress.is_some() {
+                continue;
+            }
+            let level = sorted[index].0.level();
+            let children = sorted[index + 1..]
+                .iter()
+                .take_while(|(key, _)| key.level() > level)
+                .filter(|(key, _)| key.level() == level + 1)
+                .filter_map(|(_, task)| task.progress.as_ref())
+                .filter(|progress| progress.done_at.is_some());
+            let (step, done_at, started_at) = match self {
+                Aggregation::Off => unreachable!("handled above"),
+                Aggregation::Mean => {
+                    let (sum, count, started_at) =
+                        children.fold((0.0, 0, None::<SystemTime>), |(sum, count, started_at), progress| {
+                            (
+                                sum + progress.fraction().unwrap_or_default().min(1.0),
+                                count + 1,
+                                earliest(started_at, progress.started_at),
+                            )
+                        });
+                    if count == 0 {
+                        continue;
+                    }
+                    ((sum / count as f32 * 100.0).round() as Step, 100, started_at)
+                }
+                Aggregation::Weighted => {
+                    let (step, done_at, started_at) =
+                        children.fold((0, 0, None::<SystemTime>), |(step, done_at, started_at), progress| {
+                            let total = progress.done_at.unwrap_or_default();
+                            (
+                                step + progress.steps_done().min(total),
+                                done_at + total,
+                                earliest(started_at, progress.started_at),
+                            )
+                        });
+                    if done_at == 0 {
+                        continue;
+                    }
+                    (step, done_at, started_at)
+                }
+            };
+            sorted[index].1.progress = Some(Value {
+                step: Arc::new(AtomicUsize::new(step)),
+                done_at: Some(done_at),
+                started_at,
+                aggregated: true,
+                ..Value::default()
+            });
+        }
+    }
 }
 
 impl Options {
@@ -113,6 +310,8 @@ impl Default for Options {
         Options {
             initial_capacity: 100,
             message_buffer_capacity: 20,
+            summarize_on_drop: false,
+            aggregation: Aggregation::default(),
         }
     }
 }
@@ -128,6 +327,8 @@ impl From<Options> for Root {
         Options {
             initial_capacity,
             message_buffer_capacity,
+            summarize_on_drop,
+            aggregation,
         }: Options,
     ) -> Self {
         Root {
@@ -137,7 +338,11 @@ impl From<Options> for Root {
                 key: Key::default(),
                 tree: Arc::new(crate::tree::HashMap::with_capacity(initial_capacity)),
                 messages: Arc::new(Mutex::new(MessageRingBuffer::with_capacity(message_buffer_capacity))),
+                generation: Arc::new(AtomicUsize::default()),
+                changes: Arc::new(AtomicUsize::default()),
+                summarize_on_drop,
             }),
+            aggregation,
         }
     }
 }
@@ -165,6 +370,14 @@ impl crate::Root for Arc<Root> {
         self.deref().sorted_snapshot(out)
     }
 
+    fn sorted_snapshot_if_changed(&self, out: &mut Vec<(Key, Task)>, generation: &mut Option<usize>) -> bool {
+        self.deref().sorted_snapshot_if_changed(out, generation)
+    }
+
+    fn changes(&self) -> Option<usize> {
+        Some(self.deref().changes())
+    }
+
     fn copy_messages(&self, out: &mut Vec<Message>) {
         self.deref().copy_messages(out)
     }
@@ -0,0 +1,149 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    messages::Message,
+    progress::{self, Id, Key, Step},
+};
+
+/// A copy of all tasks and messages of a progress tree that can be serialized, to persist it or send it to remote viewers.
+///
+/// It's created by [`Root::serializable_snapshot()`](crate::tree::Root::serializable_snapshot()).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    /// All tasks in order of hierarchy.
+    pub tasks: Vec<Task>,
+    /// All messages, ordered from oldest to newest.
+    pub messages: Vec<Message>,
+}
+
+/// A task within a [`Snapshot`], see [`progress::Task`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Task {
+    /// The position of the task in the hierarchy.
+    pub key: Key,
+    /// The name of the task.
+    pub name: String,
+    /// The stable identifier of the task.
+    pub id: Id,
+    /// The progress of the task, unless it serves as organizational unit.
+    pub progress: Option<Progress>,
+}
+
+/// The progress of a [`Task`], see [`progress::Value`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Progress {
+    /// The amount of progress currently made.
+    pub step: Step,
+    /// The amount of steps that failed.
+    pub failed: Step,
+    /// The step at which no further progress has to be made, or `None` if the progress is unbounded.
+    pub done_at: Option<Step>,
+    /// The unit of `step` as it would be displayed, like `items`.
+    pub unit: Option<String>,
+    /// The values of the progress as they would be displayed, like `5/10 items`.
+    #[serde(default)]
+    pub display: String,
+    /// Whether progress can be made or not.
+    pub state: State,
+    /// A short explanation of why the task is halted.
+    pub detail: Option<String>,
+    /// The task this one is waiting on, if any.
+    pub blocked_by: Option<Key>,
+    /// The time at which the progress was initialized, if known.
+    #[serde(default)]
+    pub started_at: Option<SystemTime>,
+    /// The time at which the task was blocked or halted, if it is.
+    #[serde(default)]
+    pub stopped_since: Option<SystemTime>,
+    /// The time at which the task was paused, if it is.
+    #[serde(default)]
+    pub paused_since: Option<SystemTime>,
+    /// The time the task was paused for in the past, not counting the current pause.
+    #[serde(default)]
+    pub paused_for: Duration,
+    /// Whether the progress is aggregated from the progress of its children.
+    #[serde(default)]
+    pub aggregated: bool,
+}
+
+/// The state of [`Progress`], see [`progress::State`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum State {
+    /// The task is blocked for the given `reason`, optionally `until` the given time.
+    Blocked {
+        /// Why the task is blocked.
+        reason: String,
+        /// The time until which the task is expected to be blocked.
+        until: Option<SystemTime>,
+    },
+    /// The task is halted for the given `reason`, optionally `until` the given time.
+    Halted {
+        /// Why the task is halted.
+        reason: String,
+        /// The time until which the task is expected to be halted.
+        until: Option<SystemTime>,
+    },
+    /// The task is running.
+    Running,
+}
+
+impl From<(Key, progress::Task)> for Task {
+    fn from((key, task): (Key, progress::Task)) -> Self {
+        Task {
+            key,
+            name: task.name,
+            id: task.id,
+            progress: task.progress.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl From<&progress::Value> for Progress {
+    fn from(value: &progress::Value) -> Self {
+        let step = value.step.load(Ordering::Relaxed);
+        Progress {
+            step,
+            failed: value.failed,
+            done_at: value.done_at,
+            unit: value.unit.as_ref().map(|unit| {
+                let mut out = String::new();
+                unit.as_display_value().display_unit(&mut out, step).ok();
+                out
+            }),
+            display: match value.unit.as_ref() {
+                Some(unit) => unit.display(step, value.done_at, None).to_string(),
+                None => match value.done_at {
+                    Some(done_at) => format!("{step}/{done_at}"),
+                    None => step.to_string(),
+                },
+            },
+            state: value.state.into(),
+            detail: value.detail.as_deref().map(ToOwned::to_owned),
+            blocked_by: value.blocked_by,
+            started_at: value.started_at,
+            stopped_since: value.stopped_since,
+            paused_since: value.paused_since,
+            paused_for: value.paused_for,
+            aggregated: value.aggregated,
+        }
+    }
+}
+
+impl From<progress::State> for State {
+    fn from(state: progress::State) -> Self {
+        match state {
+            progress::State::Blocked(reason, until) => State::Blocked {
+                reason: reason.into(),
+                until,
+            },
+            progress::State::Halted(reason, until) => State::Halted {
+                reason: reason.into(),
+                until,
+            },
+            progress::State::Running => State::Running,
+        }
+    }
+}
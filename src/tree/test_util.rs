@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::{
+    messages::MessageLevel,
+    progress::{State, Step},
+    tree::{Item, Root},
+};
+
+/// A builder for progress trees with a known set of tasks and messages, useful to test renderers without real workloads.
+///
+/// Tasks are identified by `/`-separated paths, with missing parents being created as organizational units.
+/// All tasks are created using the same API available to producers of progress, and are kept alive
+/// by the [`SyntheticTree`] returned by [`build()`][TreeBuilder::build()].
+///
+/// ```rust
+/// use prodash::{messages::MessageLevel, tree::test_util::TreeBuilder};
+///
+/// let tree = TreeBuilder::new()
+///     .task("a", 3, 10)
+///     .task("a/b", 5, None)
+///     .message(MessageLevel::Failure, "x")
+///     .group("c")
+///     .build();
+/// assert_eq!(tree.root().num_tasks(), 3);
+/// ```
+pub struct TreeBuilder {
+    root: Arc<Root>,
+    items: Vec<(String, Item)>,
+}
+
+/// A progress tree as created by [`TreeBuilder`], which keeps all of its tasks alive.
+pub struct SyntheticTree {
+    root: Arc<Root>,
+    items: Vec<(String, Item)>,
+}
+
+impl Default for TreeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TreeBuilder {
+    /// Create a new builder for a tree with default configuration.
+    pub fn new() -> Self {
+        Self::with_root(Root::new())
+    }
+
+    /// Create a new builder which adds tasks to `root`.
+    pub fn with_root(root: Arc<Root>) -> Self {
+        TreeBuilder {
+            root,
+            items: Vec::new(),
+        }
+    }
+
+    /// Add an organizational unit without progress at `path`.
+    pub fn group(mut self, path: &str) -> Self {
+        self.add(path);
+        self
+    }
+
+    /// Add a task at `path` which made `step` progress out of `max`, with `None` marking it as unbounded.
+    pub fn task(mut self, path: &str, step: Step, max: impl Into<Option<Step>>) -> Self {
+        let item = self.add(path);
+        item.init(max.into(), None);
+        item.set(step);
+        self
+    }
+
+    /// Set the `state` of the most recently added task.
+    pub fn state(self, state: State) -> Self {
+        if let Some((_, item)) = self.items.last() {
+            match state {
+                State::Blocked(reason, eta) => item.blocked(reason, eta),
+                State::Halted(reason, eta) => item.halted(reason, eta),
+                State::Running => item.running(),
+            }
+        }
+        self
+    }
+
+    /// Send a `message` at `level` from the most recently added task, or from the root if there is none.
+    pub fn message(self, level: MessageLevel, message: impl Into<String>) -> Self {
+        match self.items.last() {
+            Some((_, item)) => item.message(level, message),
+            None => self.root.inner.lock().message(level, message),
+        }
+        self
+    }
+
+    /// Return the tree with all tasks and messages added so far.
+    pub fn build(self) -> SyntheticTree {
+        SyntheticTree {
+            root: self.root,
+            items: self.items,
+        }
+    }
+
+    fn position(&self, path: &str) -> Option<usize> {
+        self.items.iter().position(|(p, _)| p == path)
+    }
+
+    fn add(&mut self, path: &str) -> &mut Item {
+        let item = match path.rsplit_once('/') {
+            Some((parent, name)) => {
+                let parent = match self.position(parent) {
+                    Some(index) => index,
+                    None => {
+                        self.add(parent);
+                        self.items.len() - 1
+                    }
+                };
+                self.items[parent].1.add_child(name)
+            }
+            None => self.root.add_child(path),
+        };
+        self.items.push((path.to_owned(), item));
+        &mut self.items.last_mut().expect("just pushed").1
+    }
+}
+
+impl SyntheticTree {
+    /// Return the root of the tree, suitable for handing it to a renderer.
+    pub fn root(&self) -> &Arc<Root> {
+        &self.root
+    }
+
+    /// Return the first task added at `path`, if present.
+    pub fn item(&self, path: &str) -> Option<&Item> {
+        self.items.iter().find(|(p, _)| p == path).map(|(_, item)| item)
+    }
+}
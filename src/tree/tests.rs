@@ -38,6 +38,37 @@ mod message_buffer {
         assert_messages(&out, &["four", "five"]);
     }
 
+    #[test]
+    fn set_capacity() {
+        let mut buf = MessageRingBuffer::with_capacity(2);
+        let mut out = Vec::new();
+        for msg in ["one", "two", "three"] {
+            push(&mut buf, msg);
+        }
+        assert_eq!(buf.dropped(), 1);
+        let state = buf.copy_new(&mut out, None);
+
+        buf.set_capacity(3);
+        assert_eq!(buf.capacity(), 3);
+        push_and_copy_all(&mut buf, "four", &mut out);
+        assert_messages(&out, &["two", "three", "four"]);
+        assert_eq!(buf.dropped(), 1, "growing keeps all messages");
+
+        buf.set_capacity(1);
+        buf.copy_all(&mut out);
+        assert_messages(&out, &["four"]);
+        assert_eq!(buf.dropped(), 3, "shrinking drops the oldest messages");
+
+        push(&mut buf, "five");
+        buf.copy_new(&mut out, Some(state));
+        assert_messages(&out, &["five"]);
+
+        buf.set_capacity(0);
+        push_and_copy_all(&mut buf, "six", &mut out);
+        assert_messages(&out, &[]);
+        assert_eq!(buf.dropped(), 6);
+    }
+
     mod copy_new {
         use crate::{
             messages::{Message, MessageCopyState, MessageRingBuffer},
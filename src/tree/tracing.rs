@@ -0,0 +1,104 @@
+use std::{fmt, sync::Arc};
+
+use ::tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+use crate::{
+    messages::MessageLevel,
+    tree::{Item, Root},
+};
+
+/// A [`tracing_subscriber::Layer`] feeding spans and events into a progress tree, to visualize programs and libraries
+/// that are instrumented with `tracing` without wiring them up by hand.
+///
+/// Each span becomes a task whose parent is the task of its parent span, and which is removed once the span is closed.
+/// Events become messages of the task of the span they occur in, or messages of the `root` with their target as origin
+/// if they occur outside of any span. Events of level `ERROR` are failures, `WARN` warnings, `INFO` information,
+/// `DEBUG` debug and `TRACE` trace messages.
+///
+/// ```rust
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let root = prodash::tree::Root::new();
+/// let subscriber = tracing_subscriber::registry().with(prodash::tree::tracing::Layer::new(root.clone()));
+/// tracing::subscriber::with_default(subscriber, || {
+///     let _span = tracing::info_span!("task").entered();
+///     tracing::info!("hello");
+///     assert_eq!(root.num_tasks(), 1);
+/// });
+/// assert_eq!(root.num_tasks(), 0);
+/// ```
+pub struct Layer {
+    root: Arc<Root>,
+}
+
+impl Layer {
+    /// Create a new instance to add tasks and messages to `root`.
+    pub fn new(root: Arc<Root>) -> Self {
+        Layer { root }
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for Layer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let name = attrs.metadata().name();
+        let item = span
+            .parent()
+            .and_then(|parent| {
+                parent
+                    .extensions_mut()
+                    .get_mut::<Item>()
+                    .map(|parent| parent.add_child(name))
+            })
+            .unwrap_or_else(|| self.root.add_child(name));
+        item.init(None, None);
+        span.extensions_mut().insert(item);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let level = match *event.metadata().level() {
+            Level::ERROR => MessageLevel::Failure,
+            Level::WARN => MessageLevel::Warning,
+            Level::INFO => MessageLevel::Info,
+            Level::DEBUG => MessageLevel::Debug,
+            Level::TRACE => MessageLevel::Trace,
+        };
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        let span = ctx.event_span(event);
+        let extensions = span.as_ref().map(|span| span.extensions());
+        match extensions.as_ref().and_then(|extensions| extensions.get::<Item>()) {
+            Some(item) => item.message(level, message.0),
+            None => self.root.message(level, event.metadata().target(), message.0),
+        }
+    }
+}
+
+/// Collects the `message` field of an event followed by all other fields as `name=value`.
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        use std::fmt::Write;
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            write!(self.0, "{value:?}")
+        } else {
+            write!(self.0, "{}={value:?}", field.name())
+        }
+        .expect("in-memory writes never fail");
+    }
+}
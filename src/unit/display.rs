@@ -41,6 +41,11 @@ pub struct Mode {
 }
 
 impl Mode {
+    #[cfg(any(feature = "render-tui", feature = "render-line"))]
+    pub(crate) fn shows_throughput(&self) -> bool {
+        self.throughput
+    }
+
     fn percent_location(&self) -> Option<Location> {
         if self.percent { Some(self.location) } else { None }
     }
@@ -29,12 +29,27 @@ pub use traits::DisplayValue;
 pub mod display;
 
 /// A configurable and flexible unit for use in [Progress::init()][crate::Progress::init()].
+///
+/// Renderers use it to display values like `14.2 MB/1.1 GB` instead of plain numbers. Units are created with
+///
+/// * [`label()`] for a static label like `items`, also available as `"items".into()`,
+/// * [`dynamic()`] for values formatted by a type implementing [`DisplayValue`], like `Bytes` or
+///   `Duration` with the respective features enabled, or any custom type,
+/// * and [`label_and_mode()`] or [`dynamic_and_mode()`] to additionally show percentages or throughput.
 #[derive(Debug, Clone, Hash)]
 pub struct Unit {
     kind: Kind,
     mode: Option<display::Mode>,
 }
 
+impl Unit {
+    /// Return true if throughput is displayed along with the values of this unit.
+    #[cfg(any(feature = "render-tui", feature = "render-line"))]
+    pub(crate) fn shows_throughput(&self) -> bool {
+        self.mode.is_some_and(|mode| mode.shows_throughput())
+    }
+}
+
 /// Either a static label or a dynamic one implementing [`DisplayValue`].
 #[derive(Clone)]
 pub enum Kind {
@@ -175,3 +175,23 @@ mod adjacency {
         );
     }
 }
+
+mod num_children {
+    use prodash::progress::{Key, Task};
+
+    #[test]
+    fn only_direct_children_are_counted() {
+        let r = Key::default();
+        let p1 = r.add_child(1);
+        let p11 = p1.add_child(1);
+        let mut entries: Vec<_> = [p1, p11, p11.add_child(1), p1.add_child(2), r.add_child(2)]
+            .iter()
+            .map(|k| (*k, Task::default()))
+            .collect();
+        entries.sort_by_key(|v| v.0);
+        assert_eq!(Key::num_children(&entries, 0), 2, "grandchildren don't count");
+        assert_eq!(Key::num_children(&entries, 1), 1);
+        assert_eq!(Key::num_children(&entries, 2), 0);
+        assert_eq!(Key::num_children(&entries, 4), 0, "siblings don't count");
+    }
+}
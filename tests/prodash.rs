@@ -1,3 +1,4 @@
 mod nested_progress;
 mod progress;
+mod render;
 mod unit;
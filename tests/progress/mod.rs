@@ -24,3 +24,620 @@ fn thread_safe() {
     needs_send_sync(child_of_child);
     needs_send_sync(child);
 }
+
+#[test]
+fn overall_eta() {
+    let root = prodash::tree::Root::new();
+    assert_eq!(root.overall_eta(), None, "there is nothing to estimate");
+    let bounded = root.add_child("bounded");
+    bounded.init(Some(10), None);
+    assert_eq!(root.overall_eta(), None, "no progress was made yet");
+    bounded.set(5);
+    assert!(root.overall_eta().is_some());
+    let unbounded = root.add_child("unbounded");
+    unbounded.init(None, Some("items".into()));
+    assert!(root.overall_eta().is_some(), "unbounded tasks are ignored");
+}
+
+#[test]
+fn overall_eta_too_large_to_represent() {
+    use std::{
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use prodash::progress::{self, Key, Task, Value};
+
+    let tasks = [(
+        Key::default(),
+        Task {
+            progress: Some(Value {
+                step: Arc::new(1.into()),
+                done_at: Some(usize::MAX),
+                started_at: Some(SystemTime::now() - Duration::from_secs(3600)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )];
+    assert_eq!(progress::overall_eta(&tasks), None);
+}
+
+#[test]
+fn halted_with_detail() {
+    let root = prodash::tree::Root::new();
+    let item = root.add_child("task");
+    item.init(Some(10), None);
+    let detail = || {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        out[0].1.progress.as_ref().and_then(|p| p.detail.clone())
+    };
+    item.halted_with_detail("failed", "disk full");
+    assert_eq!(detail().as_deref(), Some("disk full"));
+    item.running();
+    assert_eq!(detail(), None, "changing the state clears the detail");
+}
+
+#[test]
+fn waiting_on() {
+    let root = prodash::tree::Root::new();
+    let build = root.add_child("build");
+    build.init(Some(10), None);
+    let test = root.add_child("test");
+    test.init(Some(10), None);
+    let blocker_name = || {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        let (_, task) = out.iter().find(|(_, task)| task.name == "test").expect("present");
+        let progress = task.progress.clone().expect("initialized");
+        progress.blocker(&out).map(|task| task.name.clone())
+    };
+    test.waiting_on(Some(&build));
+    assert_eq!(blocker_name().as_deref(), Some("build"));
+    test.waiting_on(None);
+    assert_eq!(blocker_name(), None);
+    test.waiting_on(Some(&build));
+    drop(build);
+    assert_eq!(blocker_name(), None, "blockers that are gone are ignored");
+}
+
+#[test]
+fn critical_path() {
+    let root = prodash::tree::Root::new();
+    assert!(root.critical_path().is_empty(), "there is nothing to go by");
+
+    let mut group = root.add_child("group");
+    let almost_done = group.add_child("almost done");
+    almost_done.init(Some(10), None);
+    almost_done.set(9);
+    let barely_started = group.add_child("barely started");
+    barely_started.init(Some(10), None);
+    barely_started.set(1);
+    let names = || {
+        let mut out = Vec::new();
+        root.sorted_snapshot(&mut out);
+        root.critical_path()
+            .into_iter()
+            .map(|key| {
+                out.iter()
+                    .find(|(other, _)| *other == key)
+                    .map(|(_, task)| task.name.clone())
+                    .expect("present")
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(),
+        ["group", "barely started"],
+        "without dependencies, the leaf with the most time left and its ancestors are chosen"
+    );
+
+    let waiting = root.add_child("waiting");
+    waiting.init(Some(10), None);
+    waiting.waiting_on(Some(&barely_started));
+    assert_eq!(
+        names(),
+        ["group", "barely started", "waiting"],
+        "the time left of the task waited on adds up"
+    );
+}
+
+#[test]
+fn critical_path_with_cycles_and_huge_estimates() {
+    use std::{
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use prodash::progress::{self, Key, Task, Value};
+
+    let first = Key::default().add_child(0);
+    let second = Key::default().add_child(1);
+    let task = |blocked_by: Key, done_at: usize| Task {
+        progress: Some(Value {
+            step: Arc::new(1.into()),
+            done_at: Some(done_at),
+            started_at: Some(SystemTime::now() - Duration::from_secs(3600)),
+            blocked_by: Some(blocked_by),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let tasks = [(first, task(second, usize::MAX)), (second, task(first, 2))];
+    assert_eq!(
+        progress::critical_path(&tasks),
+        [second, first],
+        "cycles are broken up, and estimates too large to represent are the largest"
+    );
+}
+
+#[test]
+fn overall_fraction() {
+    let root = prodash::tree::Root::new();
+    let unbounded = root.add_child("unbounded");
+    unbounded.init(None, Some("items".into()));
+    assert_eq!(root.overall_fraction(), None, "unbounded tasks are ignored");
+    let small = root.add_child("small");
+    small.init(Some(10), None);
+    small.set(10);
+    let large = root.add_child("large");
+    large.init(Some(30), None);
+    assert_eq!(root.overall_fraction(), Some(0.25), "tasks are weighted by their steps");
+}
+
+#[test]
+fn failed_steps() {
+    let root = prodash::tree::Root::new();
+    let task = root.add_child("batch");
+    task.init(Some(1000), None);
+    task.set(900);
+    task.inc_failed_by(49);
+    task.inc_failed();
+
+    let mut out = Vec::new();
+    root.sorted_snapshot(&mut out);
+    let progress = out[0].1.progress.as_ref().expect("initialized");
+    assert_eq!(progress.failed, 50);
+    assert_eq!(progress.fraction(), Some(0.95), "failed steps count as done");
+    assert_eq!(progress.outcome_fractions(), Some((0.9, 0.05)));
+    assert!(!progress.is_done());
+
+    task.set(950);
+    root.sorted_snapshot(&mut out);
+    let progress = out[0].1.progress.as_ref().expect("initialized");
+    assert!(
+        progress.is_done(),
+        "tasks complete once all steps are done, even if some failed"
+    );
+
+    task.set(1000);
+    root.sorted_snapshot(&mut out);
+    let progress = out[0].1.progress.as_ref().expect("initialized");
+    assert_eq!(
+        progress.outcome_fractions(),
+        Some((0.95, 0.05)),
+        "too many steps are clamped in favor of failed ones"
+    );
+
+    task.set(usize::MAX);
+    root.sorted_snapshot(&mut out);
+    let progress = out[0].1.progress.as_ref().expect("initialized");
+    assert_eq!(
+        progress.steps_done(),
+        usize::MAX,
+        "steps done saturate instead of overflowing"
+    );
+}
+
+#[test]
+fn message_with_origin() {
+    use prodash::messages::MessageLevel;
+
+    let root = prodash::tree::Root::new();
+    let task = root.add_child("task");
+    task.message(MessageLevel::Info, "derived");
+    task.message_with_origin(MessageLevel::Info, "user", "explicit");
+    root.message(MessageLevel::Success, "system", "banner");
+
+    let mut out = Vec::new();
+    root.copy_messages(&mut out);
+    let origins: Vec<_> = out.iter().map(|m| (m.origin.as_str(), m.message.as_str())).collect();
+    assert_eq!(
+        origins,
+        [("task", "derived"), ("user", "explicit"), ("system", "banner")]
+    );
+}
+
+#[test]
+fn stopped_since_is_kept_while_blocked_or_halted() {
+    let root = prodash::tree::Root::new();
+    let task = root.add_child("task");
+    task.init(None, Some("items".into()));
+    let stopped_since = || {
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        tasks[0].1.progress.as_ref().expect("initialized").stopped_since
+    };
+    assert_eq!(stopped_since(), None);
+
+    task.blocked("waiting for lock", None);
+    let since = stopped_since().expect("set when blocked");
+    task.halted("waiting for network", None);
+    assert_eq!(stopped_since(), Some(since), "changing the reason keeps the time");
+
+    task.running();
+    assert_eq!(stopped_since(), None);
+}
+
+#[test]
+fn paused_time_is_not_counted_as_elapsed() {
+    use std::time::{Duration, SystemTime};
+
+    let root = prodash::tree::Root::new();
+    let task = root.add_child("task");
+    task.init(None, Some("items".into()));
+    let value = || {
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        tasks[0].1.progress.clone().expect("initialized")
+    };
+
+    task.pause();
+    let mut paused = value();
+    assert!(paused.paused_since.is_some());
+    let now = SystemTime::now();
+    paused.started_at = Some(now - Duration::from_secs(60));
+    paused.paused_since = Some(now - Duration::from_secs(20));
+    paused.paused_for = Duration::from_secs(10);
+    let elapsed = paused.elapsed().expect("started");
+    assert!(
+        elapsed > Duration::from_secs(29) && elapsed <= Duration::from_secs(30),
+        "{elapsed:?}"
+    );
+
+    task.resume();
+    let resumed = value();
+    assert_eq!(resumed.paused_since, None);
+    assert!(resumed.elapsed().is_some());
+}
+
+#[test]
+fn aggregation_of_child_progress() {
+    use prodash::tree::root::{Aggregation, Options};
+
+    let fractions = |aggregation| {
+        let root: std::sync::Arc<prodash::tree::Root> = Options {
+            aggregation,
+            ..Default::default()
+        }
+        .into();
+        let mut group = root.add_child("group");
+        let mut nested = group.add_child("nested");
+        let small = nested.add_child("small");
+        small.init(Some(10), None);
+        small.set(10);
+        let large = nested.add_child("large");
+        large.init(Some(90), None);
+        let unbounded = group.add_child("unbounded");
+        unbounded.init(None, None);
+
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        let fractions: Vec<_> = tasks
+            .iter()
+            .map(|(_, task)| task.progress.as_ref().and_then(|p| p.fraction()))
+            .collect();
+        (fractions, root.overall_fraction())
+    };
+
+    let (off, overall) = fractions(Aggregation::Off);
+    assert_eq!(off[..2], [None, None], "structural tasks show no progress by default");
+    assert_eq!(overall, Some(0.1));
+
+    let (mean, overall) = fractions(Aggregation::Mean);
+    assert_eq!(
+        mean[..2],
+        [Some(0.5), Some(0.5)],
+        "the nested mean propagates to the group"
+    );
+    assert_eq!(overall, Some(0.1), "aggregated progress isn't counted twice");
+
+    let (weighted, _) = fractions(Aggregation::Weighted);
+    assert_eq!(weighted[..2], [Some(0.1), Some(0.1)]);
+}
+
+#[test]
+fn counter_updates_are_seen_by_snapshots() {
+    use std::sync::atomic::Ordering;
+
+    let root = prodash::tree::Root::new();
+    let task = root.add_child("task");
+    let counter = task.counter();
+    task.init(Some(100), None);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..10 {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    assert_eq!(tasks[0].1.progress.as_ref().and_then(|p| p.fraction()), Some(0.4));
+}
+
+#[test]
+fn tasks_are_added_and_updated_from_many_threads() {
+    let root = prodash::tree::Root::new();
+    let _items: Vec<_> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..8)
+            .map(|thread| {
+                let root = &root;
+                scope.spawn(move || {
+                    let mut parent = root.add_child(format!("thread {thread}"));
+                    let children: Vec<_> = (0..50)
+                        .map(|child| parent.add_child(format!("child {child}")))
+                        .collect();
+                    for child in &children {
+                        child.init(Some(2), None);
+                        child.inc();
+                    }
+                    (parent, children)
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().expect("no panic"))
+            .collect()
+    });
+
+    let mut tasks = Vec::new();
+    root.sorted_snapshot(&mut tasks);
+    assert_eq!(tasks.len(), 8 * 51);
+    assert_eq!(root.num_tasks(), tasks.len());
+    assert!(tasks.windows(2).all(|w| w[0].0 < w[1].0), "tasks are sorted by key");
+    assert_eq!(root.overall_fraction(), Some(0.5));
+}
+
+#[test]
+fn snapshots_are_only_taken_if_the_tree_changed() {
+    let root = prodash::tree::Root::new();
+    let mut tasks = Vec::new();
+    let mut generation = None;
+    assert!(root.sorted_snapshot_if_changed(&mut tasks, &mut generation));
+    assert!(!root.sorted_snapshot_if_changed(&mut tasks, &mut generation));
+
+    let task = root.add_child("task");
+    assert!(root.sorted_snapshot_if_changed(&mut tasks, &mut generation));
+    task.init(Some(10), None);
+    assert!(root.sorted_snapshot_if_changed(&mut tasks, &mut generation));
+
+    task.set(5);
+    assert!(
+        !root.sorted_snapshot_if_changed(&mut tasks, &mut generation),
+        "steps don't change the generation…"
+    );
+    assert_eq!(
+        tasks[0].1.progress.as_ref().and_then(|p| p.fraction()),
+        Some(0.5),
+        "…as they are shared with the snapshot"
+    );
+
+    drop(task);
+    assert!(root.sorted_snapshot_if_changed(&mut tasks, &mut generation));
+    assert!(tasks.is_empty());
+}
+
+#[test]
+fn changes_are_seen_including_steps_and_messages() {
+    let root = prodash::tree::Root::new();
+    let mut changes = root.changes();
+    let mut assert_changed = |expected: bool, what: &str| {
+        let current = root.changes();
+        assert_eq!(current != changes, expected, "{what}");
+        changes = current;
+    };
+    assert_changed(false, "nothing happened yet");
+
+    let task = root.add_child("task");
+    assert_changed(true, "adding a task");
+    task.init(Some(10), None);
+    assert_changed(true, "initializing it");
+    task.inc();
+    assert_changed(true, "making progress");
+    task.set_max(Some(20));
+    assert_changed(true, "changing the maximum");
+    task.info("hello".into());
+    assert_changed(true, "sending a message");
+    task.counter().fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    assert_changed(true, "making progress through the counter");
+    task.set(task.step().expect("initialized"));
+    assert_changed(false, "setting the same step again");
+    drop(task);
+    assert_changed(true, "removing the task");
+}
+
+#[test]
+fn summarize_on_drop() {
+    let root: std::sync::Arc<prodash::tree::Root> = prodash::tree::root::Options {
+        summarize_on_drop: true,
+        ..Default::default()
+    }
+    .into();
+    let mut group = root.add_child("group");
+    let task = group.add_child("task");
+    task.init(None, Some("items".into()));
+    task.set(42);
+    drop(task);
+    assert_eq!(root.num_tasks(), 1, "the task is removed");
+
+    group.set_summarize_on_drop(false);
+    let quiet = group.add_child("quiet");
+    quiet.init(None, Some("items".into()));
+    drop(quiet);
+    drop(group);
+
+    let mut messages = Vec::new();
+    root.copy_messages(&mut messages);
+    assert_eq!(
+        messages.len(),
+        1,
+        "only initialized tasks with the setting enabled are summarized"
+    );
+    assert_eq!(messages[0].origin, "task");
+    assert!(
+        messages[0].message.starts_with("done 42 items in "),
+        "{}",
+        messages[0].message
+    );
+}
+
+#[test]
+fn message_levels_by_severity() {
+    use prodash::messages::MessageLevel;
+
+    let root = prodash::tree::Root::new();
+    let task = root.add_child("task");
+    task.trace("trace".into());
+    task.debug("debug".into());
+    task.info("info".into());
+    task.warn("warning".into());
+    task.fail("failure".into());
+
+    let mut out = Vec::new();
+    root.copy_messages(&mut out);
+    let levels: Vec<_> = out.iter().map(|m| m.level).collect();
+    assert_eq!(
+        levels,
+        [
+            MessageLevel::Trace,
+            MessageLevel::Debug,
+            MessageLevel::Info,
+            MessageLevel::Warning,
+            MessageLevel::Failure
+        ]
+    );
+    assert!(
+        levels.windows(2).all(|w| w[0].severity() < w[1].severity()),
+        "each level is more severe than the previous one"
+    );
+    assert_eq!(MessageLevel::Success.severity(), MessageLevel::Info.severity());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serializable_snapshot() {
+    use prodash::{messages::MessageLevel, tree::snapshot};
+
+    let root = prodash::tree::Root::new();
+    let mut group = root.add_child("group");
+    let task = group.add_child("task");
+    task.init(Some(10), Some("items".into()));
+    task.set(4);
+    task.halted("waiting", None);
+    task.message(MessageLevel::Info, "hello");
+
+    let snapshot = root.serializable_snapshot();
+    assert_eq!(snapshot.tasks.len(), 2);
+    assert_eq!(snapshot.tasks[0].name, "group");
+    assert_eq!(snapshot.tasks[0].progress, None);
+    let progress = snapshot.tasks[1].progress.as_ref().expect("initialized");
+    assert_eq!(progress.step, 4);
+    assert_eq!(progress.done_at, Some(10));
+    assert_eq!(progress.unit.as_deref(), Some("items"));
+    assert_eq!(
+        progress.state,
+        snapshot::State::Halted {
+            reason: "waiting".into(),
+            until: None
+        }
+    );
+    assert_eq!(snapshot.messages[0].message, "hello");
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn metrics() {
+    use prodash::messages::MessageLevel;
+
+    let root = prodash::tree::Root::new();
+    let mut group = root.add_child("group");
+    let task = group.add_child("a \"task\"");
+    task.init(Some(10), None);
+    task.set(4);
+    task.message(MessageLevel::Info, "hello");
+
+    let mut exporter = prodash::metrics::Exporter::default();
+    let metrics = exporter.scrape(&root);
+    assert!(metrics.contains("\nprodash_task_step{key=\"0.0\",name=\"a \\\"task\\\"\"} 4\n"));
+    assert!(metrics.contains("\nprodash_task_done_at{key=\"0.0\",name=\"a \\\"task\\\"\"} 10\n"));
+    assert!(!metrics.contains("name=\"group\""), "groups have no progress");
+    assert!(metrics.contains("\nprodash_tasks 2\n"));
+    assert!(metrics.contains("\nprodash_messages_total{level=\"info\"} 1\n"));
+
+    task.message(MessageLevel::Info, "again");
+    let metrics = exporter.scrape(&root);
+    assert!(
+        metrics.contains("\nprodash_messages_total{level=\"info\"} 2\n"),
+        "messages are counted across scrapes"
+    );
+}
+
+#[test]
+fn reader_and_writer_count_bytes() -> std::io::Result<()> {
+    use std::io::{BufRead, Read, Write};
+
+    let root = prodash::tree::Root::new();
+    let mut read = root.add_child("read");
+    let mut buf = String::new();
+    (&mut read).reader(&b"hello\nworld"[..]).read_to_string(&mut buf)?;
+    assert_eq!(read.step(), Some(11));
+    assert!(Progress::unit(&read).is_some(), "a unit for bytes is chosen");
+
+    let mut lines = root.add_child("lines");
+    let mut line = String::new();
+    (&mut lines).reader(&b"hello\nworld"[..]).read_line(&mut line)?;
+    assert_eq!(lines.step(), Some(6), "buffered reads count consumed bytes");
+
+    let mut write = root.add_child("write");
+    write.init(None, Some("items".into()));
+    let mut out = (&mut write).writer(Vec::new());
+    out.write_all(b"hello")?;
+    assert_eq!(out.inner, b"hello");
+    drop(out);
+    assert_eq!(write.step(), Some(5));
+    assert_eq!(
+        Progress::unit(&write).map(|unit| unit.display(1, None, None).to_string()),
+        Some("1 items".into()),
+        "existing units are kept"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "progress-async")]
+#[test]
+fn async_adapters_count_items_and_bytes() -> std::io::Result<()> {
+    use futures::{AsyncReadExt, AsyncWriteExt, StreamExt as _, executor::block_on};
+    use prodash::progress::StreamExt;
+
+    let root = prodash::tree::Root::new();
+    let mut items = root.add_child("items");
+    let collected: Vec<_> = block_on(futures::stream::iter(0..3).inform_progress(&mut items).collect());
+    assert_eq!(collected, [0, 1, 2]);
+    assert_eq!(items.step(), Some(3));
+
+    let mut read = root.add_child("read");
+    let mut buf = String::new();
+    block_on((&mut read).reader(&b"hello\nworld"[..]).read_to_string(&mut buf))?;
+    assert_eq!(read.step(), Some(11));
+
+    let mut write = root.add_child("write");
+    block_on((&mut write).writer(Vec::new()).write_all(b"hello"))?;
+    assert_eq!(write.step(), Some(5));
+    Ok(())
+}
@@ -0,0 +1,1004 @@
+#[cfg(feature = "render-sse")]
+mod sse {
+    use prodash::{progress::Key, render::sse};
+
+    #[test]
+    fn multi_line_data_is_split_into_multiple_fields() {
+        let mut out = Vec::new();
+        sse::write_event(&mut out, "progress", "a\nb").unwrap();
+        assert_eq!(out, b"event: progress\ndata: a\ndata: b\n\n");
+    }
+
+    #[test]
+    fn snapshot_is_written_as_progress_event() {
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("a \"task\"");
+        task.init(Some(10), None);
+        task.set(5);
+        let mut tasks: Vec<(Key, _)> = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+
+        let mut out = Vec::new();
+        sse::write_snapshot(&mut out, &tasks).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.starts_with(
+                "event: progress\ndata: {\"tasks\":[{\"key\":[0,null,null,null,null,null],\"name\":\"a \\\"task\\\"\",\
+                 \"id\":[0,0,0,0],\"progress\":{\"step\":5,\"failed\":0,\"done_at\":10,\"unit\":null,\"display\":\"5/10\",\
+                 \"state\":\"Running\","
+            ),
+            "{out}"
+        );
+        assert!(out.ends_with("}}]}\n\n"), "{out}");
+    }
+}
+
+#[cfg(feature = "render-jsonl")]
+mod jsonl {
+    use prodash::{messages::MessageLevel, progress::Key, render::jsonl};
+
+    #[test]
+    fn each_task_is_written_as_line_with_its_frame_followed_by_the_end_of_the_frame() {
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("task");
+        task.init(Some(10), None);
+        task.set(5);
+        let _group = root.add_child("group");
+        let mut tasks: Vec<(Key, _)> = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+
+        let mut out = Vec::new();
+        jsonl::write_snapshot(&mut out, 3, &tasks).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 3, "{out}");
+        assert!(
+            lines[0].starts_with(
+                "{\"frame\":3,\"task\":{\"key\":[0,null,null,null,null,null],\"name\":\"task\",\"id\":[0,0,0,0],\
+                 \"progress\":{\"step\":5,\"failed\":0,\"done_at\":10,\"unit\":null,\"display\":\"5/10\",\"state\":\"Running\","
+            ),
+            "{out}"
+        );
+        assert_eq!(
+            lines[1],
+            "{\"frame\":3,\"task\":{\"key\":[1,null,null,null,null,null],\"name\":\"group\",\"id\":[0,0,0,0],\
+             \"progress\":null}}"
+        );
+        assert_eq!(lines[2], "{\"frame\":3,\"tasks\":2}");
+    }
+
+    #[test]
+    fn messages_are_written_as_lines() {
+        let root = prodash::tree::Root::new();
+        root.message(MessageLevel::Failure, "system", "oh no");
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+
+        let mut out = Vec::new();
+        jsonl::write_message(&mut out, &messages[0]).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("{\"message\":{\"time\":"));
+        assert!(
+            out.ends_with(",\"level\":\"Failure\",\"origin\":\"system\",\"message\":\"oh no\",\"fields\":[]}}\n"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn message_fields_are_written_as_pairs() {
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("task");
+        task.message_with_fields(MessageLevel::Info, "copied", [("path", "a.txt"), ("bytes", "42")]);
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+
+        let mut out = Vec::new();
+        jsonl::write_message(&mut out, &messages[0]).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.ends_with(",\"message\":\"copied\",\"fields\":[[\"path\",\"a.txt\"],[\"bytes\",\"42\"]]}}\n"),
+            "{out}"
+        );
+    }
+}
+
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod name_abbrev {
+    use prodash::render::NameAbbrev;
+
+    #[test]
+    fn names_that_fit_are_kept() {
+        for abbrev in [
+            NameAbbrev::None,
+            NameAbbrev::TruncateEnd,
+            NameAbbrev::TruncateMiddle,
+            NameAbbrev::ShortenPath,
+            NameAbbrev::HeadTail,
+        ] {
+            assert_eq!(abbrev.abbreviate("short", 5), "short");
+        }
+        assert_eq!(NameAbbrev::None.abbreviate("too long", 3), "too long");
+    }
+
+    #[test]
+    fn each_strategy_fits_the_width() {
+        assert_eq!(NameAbbrev::TruncateEnd.abbreviate("foo-and-bar", 7), "foo-an…");
+        assert_eq!(NameAbbrev::TruncateMiddle.abbreviate("foo-and-bar", 7), "foo…bar");
+        assert_eq!(
+            NameAbbrev::ShortenPath.abbreviate("src/render/line/draw.rs", 13),
+            "s/r/l/draw.rs"
+        );
+        assert_eq!(
+            NameAbbrev::ShortenPath.abbreviate("src/render/line/draw.rs", 17),
+            "s/r/line/draw.rs"
+        );
+        assert_eq!(
+            NameAbbrev::HeadTail.abbreviate("compiling many crates done", 16),
+            "compiling … done"
+        );
+        assert_eq!(NameAbbrev::HeadTail.abbreviate("compiling done", 7), "com…one");
+    }
+
+    #[test]
+    fn wide_characters_are_measured_by_their_width() {
+        assert_eq!(NameAbbrev::TruncateEnd.abbreviate("日本語のタスク", 5), "日本…");
+    }
+}
+
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod profile {
+    use prodash::render::Profile;
+
+    #[test]
+    fn from_name_ignores_case() {
+        assert_eq!(Profile::from_name("CI"), Some(Profile::Ci));
+        assert_eq!(Profile::from_name("detailed"), Some(Profile::Detailed));
+        assert_eq!(Profile::from_name("unknown"), None);
+    }
+}
+
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod timestamp_format {
+    use std::time::{Duration, SystemTime};
+
+    use prodash::time::{TimestampFormat, Timezone};
+
+    #[test]
+    fn each_format_matches_its_width() {
+        let time = SystemTime::now() - Duration::from_secs(133);
+        for format in [TimestampFormat::Time, TimestampFormat::DateTime] {
+            assert_eq!(
+                Some(format.format(time, Timezone::Utc).len()),
+                format.fixed_width(),
+                "{format:?}"
+            );
+        }
+        assert_eq!(TimestampFormat::Relative.format(time, Timezone::Utc), "2m 13s ago");
+        assert_eq!(TimestampFormat::Relative.fixed_width(), None);
+        assert_eq!(
+            TimestampFormat::Custom(|_time, timezone| format!("{timezone:?}")).format(time, Timezone::Utc),
+            "Utc"
+        );
+    }
+
+    #[test]
+    fn utc_timestamps_are_formatted_exactly() {
+        // 2024-05-01T00:51:45Z
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_524_705);
+        assert_eq!(TimestampFormat::Time.format(time, Timezone::Utc), "00:51:45");
+        assert_eq!(
+            TimestampFormat::DateTime.format(time, Timezone::Utc),
+            "2024-05-01 00:51:45"
+        );
+        assert_eq!(
+            TimestampFormat::Rfc3339.format(time, Timezone::Utc),
+            "2024-05-01T00:51:45Z"
+        );
+        assert_eq!(TimestampFormat::Rfc3339.fixed_width(), None);
+    }
+
+    #[test]
+    fn local_rfc3339_timestamps_have_an_offset() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_524_705);
+        let formatted = TimestampFormat::Rfc3339.format(time, Timezone::Local);
+        assert_eq!(formatted.len(), "2024-05-01T00:51:45+00:00".len(), "{formatted}");
+        assert!(matches!(&formatted[19..20], "+" | "-"), "{formatted}");
+    }
+
+    #[test]
+    fn timezones_are_named_ignoring_case() {
+        assert_eq!(Timezone::from_name("Local"), Some(Timezone::Local));
+        assert_eq!(Timezone::from_name("UTC"), Some(Timezone::Utc));
+        assert_eq!(Timezone::from_name("CET"), None);
+    }
+
+    #[test]
+    fn default_timezone_ignores_the_environment() {
+        let (expected, other) = if cfg!(feature = "local-time") {
+            (Timezone::Local, "utc")
+        } else {
+            (Timezone::Utc, "local")
+        };
+        // SAFETY: no other test reads or writes this variable.
+        unsafe { std::env::set_var(prodash::time::TIMEZONE_ENV_VAR, other) };
+        let (default, from_env) = (Timezone::default(), Timezone::from_env());
+        unsafe { std::env::remove_var(prodash::time::TIMEZONE_ENV_VAR) };
+
+        assert_eq!(default, expected);
+        assert_ne!(from_env, expected, "the environment is only used when asked for");
+    }
+}
+
+#[cfg(any(feature = "render-tui", feature = "render-line"))]
+mod elapsed {
+    use std::time::{Duration, SystemTime};
+
+    use prodash::{
+        progress::{Key, Value},
+        render::{Column, Elapsed},
+    };
+
+    #[test]
+    fn column_shows_time_since_start() {
+        let value = Value {
+            started_at: Some(SystemTime::now() - Duration::from_secs(133)),
+            ..Value::default()
+        };
+        let tokens = Elapsed.render(&Key::default(), &value);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].to_string(), "2m 13s");
+
+        assert!(Elapsed.render(&Key::default(), &Value::default()).is_empty());
+    }
+}
+
+#[cfg(feature = "render-line")]
+mod message_log {
+    use prodash::{Root, render::line};
+
+    #[test]
+    fn all_messages_are_written_once_the_renderer_stops() {
+        let path = std::env::temp_dir().join(format!("prodash-message-log-{}.txt", std::process::id()));
+        let root = prodash::tree::Root::new();
+        let handle = line::render(
+            std::io::sink(),
+            root.downgrade(),
+            line::Options {
+                output_is_terminal: false,
+                frames_per_second: 100.0,
+                message_log: Some(path.clone()),
+                ..Default::default()
+            },
+        );
+        let mut task = root.add_child("task");
+        task.info("first");
+        task.message_with_fields(prodash::messages::MessageLevel::Warning, "second", [("key", "value")]);
+        handle.shutdown_and_wait();
+
+        let log = std::fs::read_to_string(&path).expect("log was written");
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<_> = log.lines().collect();
+        assert_eq!(lines.len(), 2, "{log}");
+        assert!(lines[0].ends_with(" info    task → first"), "{}", lines[0]);
+        assert!(lines[1].ends_with(" warning task → second key=value"), "{}", lines[1]);
+    }
+}
+
+#[cfg(feature = "render-line")]
+mod line_builder {
+    use std::sync::{Arc, Mutex};
+
+    use prodash::{
+        Root,
+        render::line::{ColorChoice, LineRenderer},
+    };
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn draws_to_the_configured_output() {
+        let out = Shared::default();
+        let root = prodash::tree::Root::new();
+        let handle = LineRenderer::builder()
+            .output(out.clone())
+            .output_is_terminal(false)
+            .colored(ColorChoice::Never)
+            .fps(100.0)
+            .build(root.downgrade());
+        let mut task = root.add_child("task");
+        task.info("hello");
+        handle.shutdown_and_wait();
+
+        let written = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        assert!(written.contains("task hello"), "{written:?}");
+    }
+
+    #[test]
+    fn shutdown_draws_a_last_frame_and_shows_the_cursor_even_without_progress() {
+        let out = Shared::default();
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("task");
+        let handle = LineRenderer::builder()
+            .output(out.clone())
+            .colored(ColorChoice::Never)
+            .hide_cursor(true)
+            .keep_running_if_progress_is_empty(false)
+            .build(root.downgrade());
+        task.info("hello");
+        handle.shutdown_and_join().expect("writing works");
+
+        let written = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        assert!(written.contains("task hello"), "{written:?}");
+        assert!(written.contains("\x1b[?25h"), "the cursor is shown again: {written:?}");
+    }
+}
+
+#[cfg(feature = "render-line")]
+mod color_choice {
+    use prodash::render::line::ColorChoice;
+
+    #[test]
+    fn always_and_never_ignore_the_terminal() {
+        assert!(ColorChoice::Always.enabled(false));
+        assert!(!ColorChoice::Never.enabled(true));
+    }
+}
+
+#[cfg(feature = "render-tui")]
+mod key_bindings {
+    use prodash::render::tui::{Action, KeyBindings, KeyCode, KeyModifiers};
+
+    #[test]
+    fn shift_is_ignored_and_bindings_can_be_replaced() {
+        let bindings = KeyBindings::default();
+        assert_eq!(
+            bindings.action(KeyCode::Char('J'), KeyModifiers::SHIFT),
+            Some(Action::ScrollMessagesDown)
+        );
+        assert_eq!(
+            bindings.action(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        assert_eq!(bindings.action(KeyCode::Char('c'), KeyModifiers::NONE), None);
+        assert_eq!(bindings.keys(Action::Suspend), ["^z"]);
+        assert_eq!(bindings.keys(Action::ToggleFollowMessages), ["t"]);
+        #[cfg(feature = "render-tui-clipboard")]
+        assert_eq!(bindings.keys(Action::CopyMessage), ["y"]);
+
+        let bindings = bindings.unbind(KeyCode::Char('q'), KeyModifiers::NONE).bind(
+            KeyCode::Char('x'),
+            KeyModifiers::NONE,
+            Action::Quit,
+        );
+        assert_eq!(bindings.action(KeyCode::Char('q'), KeyModifiers::NONE), None);
+        assert_eq!(bindings.keys(Action::Quit), ["Esc", "^[", "^c", "x"]);
+    }
+}
+
+#[cfg(feature = "render-tui")]
+mod event_script {
+    use prodash::render::tui::{Event, EventScript, KeyCode};
+
+    #[test]
+    fn recorded_events_can_be_loaded_and_unparseable_lines_are_ignored() {
+        let path = std::env::temp_dir().join(format!("prodash-events-{}.jsonl", std::process::id()));
+        std::fs::write(
+            &path,
+            "{\"at\":0.5,\"event\":\"key\",\"code\":{\"Char\":\"j\"},\"modifiers\":0}\n\
+             not an event\n\
+             {\"at\":0.7,\"event\":\"key\",\"code\":{\"F\":5},\"modifiers\":0}\n\
+             {\"at\":1.0,\"event\":\"title\",\"title\":\"hi\"}\n\
+             {\"at\":1.5,\"event\":\"quit_after\",\"seconds\":2.0}\n",
+        )
+        .unwrap();
+        let script = EventScript::load(&path).expect("file can be read");
+        std::fs::remove_file(&path).ok();
+
+        let events: Vec<_> = script.events().collect();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[0], Event::Input(key) if key.code == KeyCode::Char('j')));
+        assert!(matches!(events[1], Event::Input(key) if key.code == KeyCode::F(5)));
+        assert!(matches!(events[2], Event::SetTitle(title) if title == "hi"));
+        assert!(matches!(events[3], Event::QuitAfter(after) if after.as_secs() == 2));
+    }
+
+    #[test]
+    fn scripts_send_a_tick_after_each_event() {
+        let script: EventScript = [Event::SetTitle("hi".into()), Event::RefreshWindowSize]
+            .into_iter()
+            .collect();
+        let events = futures::executor::block_on(futures::StreamExt::collect::<Vec<_>>(script.into_stream()));
+        assert!(matches!(
+            events.as_slice(),
+            [Event::SetTitle(_), Event::Tick, Event::RefreshWindowSize, Event::Tick]
+        ));
+    }
+
+    #[test]
+    fn scripts_draw_a_frame_for_each_event_until_they_end() {
+        use std::sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        };
+
+        use prodash::{
+            Root,
+            render::tui::{Options, render_with_script, tui_export::layout::Rect},
+        };
+
+        let root = prodash::tree::Root::new();
+        let _task = root.add_child("task");
+        let frames = Arc::new(AtomicUsize::default());
+        let options = Options {
+            window_size: Some(Rect::new(0, 0, 40, 10)),
+            post_draw: Some(Arc::new({
+                let frames = Arc::clone(&frames);
+                move |_buf, _area| {
+                    frames.fetch_add(1, Ordering::SeqCst);
+                }
+            })),
+            ..Default::default()
+        };
+        let script: EventScript = [Event::Tick, Event::SetTitle("scripted".into()), Event::Tick]
+            .into_iter()
+            .collect();
+        let mut out = Vec::new();
+        let render = render_with_script(&mut out, root.downgrade(), options, script).expect("no terminal needed");
+        futures::executor::block_on(render);
+
+        assert_eq!(
+            frames.load(Ordering::SeqCst),
+            3,
+            "there is no clock to draw more frames"
+        );
+        let text = String::from_utf8(out).expect("plain text");
+        assert_eq!(
+            text.lines().count(),
+            3 * 10,
+            "each frame is written with one line per row"
+        );
+        assert!(text.contains("scripted"), "{text}");
+    }
+
+    #[test]
+    fn clicking_the_minimap_shows_the_tasks_of_its_row() {
+        use prodash::{
+            Root,
+            render::tui::{Options, render_with_script, tui_export::layout::Rect},
+        };
+
+        let root = prodash::tree::Root::new();
+        let _tasks: Vec<_> = (0..100).map(|index| root.add_child(format!("task {index}"))).collect();
+        // The minimap is in the last column inside the border, with one row for each row of tasks.
+        let path = std::env::temp_dir().join(format!("prodash-minimap-{}.jsonl", std::process::id()));
+        std::fs::write(
+            &path,
+            "{\"at\":0.0,\"event\":\"refresh_window_size\"}\n\
+             {\"at\":0.0,\"event\":\"mouse\",\"kind\":\"click\",\"column\":58,\"row\":17}\n\
+             {\"at\":0.0,\"event\":\"mouse\",\"kind\":\"scroll_up\",\"column\":58,\"row\":17}\n",
+        )
+        .unwrap();
+        let script = EventScript::load(&path).expect("file can be read");
+        std::fs::remove_file(&path).ok();
+        let options = Options {
+            window_size: Some(Rect::new(0, 0, 60, 20)),
+            show_minimap: true,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let render = render_with_script(&mut out, root.downgrade(), options, script).expect("no terminal needed");
+        futures::executor::block_on(render);
+
+        let text = String::from_utf8(out).expect("plain text");
+        let lines: Vec<_> = text.lines().collect();
+        let frames: Vec<_> = lines.chunks(20).map(|frame| frame.join("\n")).collect();
+        assert_eq!(frames.len(), 3, "a frame for each event");
+        assert!(frames[0].contains("task 0 "), "{}", frames[0]);
+        assert!(
+            frames[1].contains("task 95"),
+            "the last row shows the last tasks: {}",
+            frames[1]
+        );
+        assert!(!frames[1].contains("task 0 "), "{}", frames[1]);
+        assert!(
+            !frames[2].contains("task 95"),
+            "scrolling goes back by the tasks of a row: {}",
+            frames[2]
+        );
+    }
+}
+
+#[cfg(feature = "render-tui")]
+mod sort_order {
+    use prodash::render::tui::SortOrder;
+
+    #[test]
+    fn next_cycles_through_all_orders() {
+        let mut order = SortOrder::default();
+        let mut seen = Vec::new();
+        loop {
+            seen.push(order);
+            order = order.next();
+            if order == SortOrder::default() {
+                break;
+            }
+        }
+        assert_eq!(
+            seen,
+            [
+                SortOrder::Tree,
+                SortOrder::Name,
+                SortOrder::StartTime,
+                SortOrder::Remaining,
+                SortOrder::Throughput
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "render-tui")]
+mod custom_pane {
+    use std::sync::{Arc, Mutex};
+
+    use prodash::progress::{Key, Task};
+    use prodash::render::tui::{
+        CustomPane, Dashboard, DashboardState, Options,
+        tui_export::{buffer::Buffer, layout::Rect, style::Style, widgets::StatefulWidget},
+    };
+
+    #[test]
+    fn is_drawn_into_the_reserved_region_at_the_bottom() {
+        let root = prodash::tree::Root::new();
+        let _task = root.add_child("task");
+        let drawn_with = Arc::new(Mutex::new(None));
+        let options = Options {
+            custom_pane: Some(CustomPane {
+                height: 2,
+                draw: Arc::new({
+                    let drawn_with = drawn_with.clone();
+                    move |buf: &mut Buffer, area: Rect, tasks: &[(Key, Task)]| {
+                        buf.set_string(area.x, area.y, "custom", Style::default());
+                        *drawn_with.lock().unwrap() = Some((area, tasks.len()));
+                    }
+                }),
+            }),
+            ..Default::default()
+        };
+        let area = Rect::new(0, 0, 40, 10);
+        let mut buf = Buffer::empty(area);
+        Dashboard::new(&root).render(area, &mut buf, &mut DashboardState::new(&options));
+
+        let (region, num_tasks) = drawn_with.lock().unwrap().expect("custom pane was drawn");
+        assert_eq!(num_tasks, 1);
+        assert_eq!(region.height, 2);
+        assert_eq!(
+            region.bottom(),
+            area.bottom() - 1,
+            "it's placed right above the border of the window"
+        );
+        assert_eq!(buf[(region.x, region.y)].symbol(), "c");
+    }
+}
+
+#[cfg(feature = "render-remote")]
+mod remote {
+    use prodash::{Root, messages::MessageLevel, render::jsonl, render::remote};
+
+    #[test]
+    fn mirror_reflects_tasks_and_messages_of_written_frames() {
+        let root = prodash::tree::Root::new();
+        let mut group = root.add_child("group");
+        let task = group.add_child("task");
+        task.init(Some(10), Some("items".into()));
+        task.set(5);
+        task.halted("stuck", None);
+        task.message_with_fields(MessageLevel::Failure, "oh no", [("path", "a \"b\"")]);
+
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        let mut messages = Vec::new();
+        root.copy_messages(&mut messages);
+        let mut out = Vec::new();
+        jsonl::write_message(&mut out, &messages[0]).unwrap();
+        jsonl::write_snapshot(&mut out, 0, &tasks).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let (task_lines, end_of_frame) = out.trim_end().rsplit_once('\n').unwrap();
+
+        let mirror = std::sync::Arc::new(remote::Mirror::default());
+        for line in task_lines.lines() {
+            assert!(mirror.apply(line), "{line}");
+        }
+        assert!(!mirror.apply("{\"frame\":0,\"task\":{\"key\":[0]}}"));
+        assert_eq!(mirror.num_tasks(), 0, "the frame isn't complete yet");
+        assert!(mirror.apply(end_of_frame));
+        assert_eq!(mirror.num_tasks(), 2);
+
+        let mut out = Vec::new();
+        jsonl::write_snapshot(&mut out, 1, &tasks[1..]).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(mirror.apply(out.lines().last().unwrap()));
+        assert_eq!(mirror.num_tasks(), 2, "frames with missing tasks are ignored");
+
+        let mut out = Vec::new();
+        jsonl::write_snapshot(&mut out, 2, &[]).unwrap();
+        assert!(mirror.apply(String::from_utf8(out).unwrap().trim_end()));
+        assert_eq!(mirror.num_tasks(), 0, "empty frames remove all tasks");
+
+        let mut messages = Vec::new();
+        mirror.copy_messages(&mut messages);
+        assert_eq!(messages[0].level, MessageLevel::Failure);
+        assert_eq!(messages[0].message, "oh no");
+        assert_eq!(messages[0].fields, [("path".to_string(), "a \"b\"".to_string())]);
+    }
+
+    #[test]
+    fn tasks_keep_their_keys_and_display() {
+        let root = prodash::tree::Root::new();
+        let mut group = root.add_child("group");
+        let task = group.add_child("task");
+        task.init(Some(10), None);
+        task.set(5);
+        task.halted("stuck", None);
+
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        let mut out = Vec::new();
+        jsonl::write_snapshot(&mut out, 0, &tasks).unwrap();
+        let mirror = std::sync::Arc::new(remote::Mirror::default());
+        for line in String::from_utf8(out).unwrap().lines() {
+            assert!(mirror.apply(line), "{line}");
+        }
+
+        let mut mirrored = Vec::new();
+        mirror.sorted_snapshot(&mut mirrored);
+        assert_eq!(mirrored.len(), 2);
+        assert_eq!(mirrored[1].0, tasks[1].0);
+        assert_eq!(mirrored[1].1.name, "task");
+        let progress = mirrored[1].1.progress.as_ref().expect("initialized");
+        assert_eq!(progress.done_at, Some(10));
+        assert_eq!(progress.state, prodash::progress::State::Halted("stuck", None));
+        let unit = progress.unit.as_ref().expect("display is kept as unit");
+        assert_eq!(unit.display(5, Some(10), None).to_string(), "5/10");
+    }
+}
+
+#[cfg(feature = "render-history")]
+mod history {
+    use prodash::{Root, render::history, render::remote};
+
+    #[test]
+    fn recordings_contain_timestamped_frames_and_messages() {
+        let path = std::env::temp_dir().join(format!("prodash-history-{}.jsonl", std::process::id()));
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("task");
+        task.init(Some(10), None);
+        task.set(5);
+        task.info("hello");
+        let recording = history::record(
+            &path,
+            root.downgrade(),
+            history::Options {
+                frames_per_second: 100.0,
+            },
+        )
+        .expect("file can be created");
+        recording.shutdown_and_wait().expect("writing works");
+
+        let lines = std::fs::read_to_string(&path).expect("recording was written");
+        std::fs::remove_file(&path).ok();
+        assert!(lines.starts_with("{\"at\":"), "{lines}");
+        let mirror = std::sync::Arc::new(remote::Mirror::default());
+        for line in lines.lines().filter(|line| !line.starts_with("{\"at\":")) {
+            assert!(mirror.apply(line), "{line}");
+        }
+        assert_eq!(mirror.num_tasks(), 1);
+        let mut messages = Vec::new();
+        mirror.copy_messages(&mut messages);
+        assert_eq!(messages.len(), 1, "the message is recorded only once");
+        assert_eq!(messages[0].message, "hello");
+    }
+}
+
+mod render_to_string {
+    #[cfg(feature = "render-line")]
+    #[test]
+    fn line_renderer_draws_plain_text() {
+        use prodash::render::line;
+
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("task");
+        task.init(Some(10), None);
+        task.set(5);
+        task.info("hello");
+        let frame = line::render_to_string(&root, line::Options::default());
+        assert!(!frame.contains('\x1b'), "{frame:?}");
+        let lines: Vec<_> = frame.lines().collect();
+        assert_eq!(lines.len(), 2, "{frame}");
+        assert!(lines[0].ends_with("task hello"), "{frame}");
+        assert!(lines[1].contains("task") && lines[1].contains("5/10"), "{frame}");
+    }
+
+    #[cfg(feature = "render-line")]
+    #[test]
+    fn line_renderer_shortens_names_only_if_asked_to() {
+        use prodash::render::{NameAbbrev, line};
+
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("a-task-with-a-rather-long-name");
+        task.init(Some(10), None);
+        task.set(5);
+        let options = line::Options {
+            terminal_dimensions: (40, 20),
+            ..Default::default()
+        };
+        let frame = line::render_to_string(&root, options.clone());
+        assert!(frame.contains("a-task-with-a-rather-long-name"), "{frame}");
+
+        let frame = line::render_to_string(
+            &root,
+            line::Options {
+                name_abbrev: NameAbbrev::TruncateEnd,
+                ..options
+            },
+        );
+        assert!(!frame.contains("a-task-with-a-rather-long-name"), "{frame}");
+        assert!(frame.contains("a-task-with-a-rathe…"), "{frame}");
+    }
+
+    #[cfg(feature = "render-line")]
+    #[test]
+    fn line_renderer_writes_only_the_newest_messages_up_to_the_limit() {
+        use prodash::render::line;
+
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("task");
+        for index in 0..5 {
+            task.info(format!("message {index}"));
+        }
+        let frame = line::render_to_string(
+            &root,
+            line::Options {
+                max_messages_per_frame: Some(2),
+                ..Default::default()
+            },
+        );
+        let lines: Vec<_> = frame.lines().collect();
+        assert!(lines[0].ends_with("3 older messages not shown"), "{frame}");
+        assert!(lines[1].ends_with("task message 3"), "{frame}");
+        assert!(lines[2].ends_with("task message 4"), "{frame}");
+    }
+
+    #[cfg(feature = "render-line")]
+    #[test]
+    fn line_renderer_summarizes_tasks_finished_with_failed_steps() {
+        use prodash::render::{FinishedTasks, line};
+
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("batch");
+        task.init(Some(1000), None);
+        task.set(950);
+        task.inc_failed_by(50);
+        let frame = line::render_to_string(
+            &root,
+            line::Options {
+                finished_tasks: FinishedTasks::Summarize,
+                ..Default::default()
+            },
+        );
+        assert!(!frame.contains("batch"), "{frame}");
+        assert!(frame.contains("1 tasks finished"), "{frame}");
+    }
+
+    #[cfg(feature = "render-line")]
+    #[test]
+    fn line_renderer_shows_the_last_update_if_configured() {
+        use prodash::{render::line, time::TimestampFormat};
+
+        let root = prodash::tree::Root::new();
+        let task = root.add_child("task");
+        task.init(Some(10), None);
+        let frame = line::render_to_string(
+            &root,
+            line::Options {
+                show_last_update: true,
+                timestamp_format: TimestampFormat::Custom(|_time, _timezone| "just now".into()),
+                ..Default::default()
+            },
+        );
+        assert!(frame.trim_end().ends_with("just now"), "{frame}");
+    }
+
+    /// Return a root with `aggregation`, holding a group of a bounded task half done and an unbounded one, along with
+    /// all of its items to keep them alive.
+    #[cfg(any(feature = "render-tui", all(feature = "render-line", feature = "unit-bytes")))]
+    fn group_of_tasks(
+        aggregation: prodash::tree::root::Aggregation,
+    ) -> (std::sync::Arc<prodash::tree::Root>, [prodash::tree::Item; 3]) {
+        let root: std::sync::Arc<prodash::tree::Root> = prodash::tree::root::Options {
+            aggregation,
+            ..Default::default()
+        }
+        .into();
+        let mut group = root.add_child("group");
+        let bounded = group.add_child("bounded");
+        bounded.init(Some(100), None);
+        bounded.set(50);
+        let unbounded = group.add_child("unbounded");
+        unbounded.init(None, Some("items".into()));
+        unbounded.set(30);
+        (root, [group, bounded, unbounded])
+    }
+
+    #[cfg(feature = "render-tui")]
+    #[test]
+    fn terminal_user_interface_counts_tasks_the_same_with_aggregated_progress() {
+        use prodash::{
+            render::tui::{self, DashboardState},
+            tree::root::Aggregation,
+        };
+
+        let header = |aggregation| {
+            let state = &mut DashboardState::new(&tui::Options {
+                show_task_fraction: true,
+                ..Default::default()
+            });
+            let frame = tui::render_to_string(&group_of_tasks(aggregation).0, state, 120, 10);
+            frame
+                .lines()
+                .find(|line| line.contains("tasks done"))
+                .expect("header is drawn")
+                .to_owned()
+        };
+        let off = header(Aggregation::Off);
+        assert!(off.contains(" 0/2 tasks done "), "{off}");
+        assert!(off.contains("2 running +   0 blocked +   1 groups"), "{off}");
+        assert_eq!(
+            header(Aggregation::Mean),
+            off,
+            "groups with aggregated progress aren't tasks"
+        );
+    }
+
+    #[cfg(all(feature = "render-line", feature = "unit-bytes"))]
+    #[test]
+    fn line_renderer_counts_bytes_the_same_with_aggregated_progress() {
+        use prodash::{render::line, tree::root::Aggregation};
+
+        let bytes_and_percentage = |aggregation| {
+            let frame = line::render_to_string(
+                &group_of_tasks(aggregation).0,
+                line::Options {
+                    layout: line::Layout::PvStyle,
+                    ..Default::default()
+                },
+            );
+            let words: Vec<_> = frame.split_whitespace().collect();
+            let percentage = words.iter().find(|word| word.ends_with('%')).expect("bounded tasks");
+            (words[0].to_owned(), percentage.to_string())
+        };
+        let off = bytes_and_percentage(Aggregation::Off);
+        assert_eq!(off.1, "50%");
+        assert_eq!(
+            bytes_and_percentage(Aggregation::Mean),
+            off,
+            "aggregated progress isn't counted twice"
+        );
+    }
+
+    #[cfg(all(feature = "render-line", feature = "unit-bytes"))]
+    #[test]
+    fn line_renderer_cuts_the_pv_line_to_the_terminal_width() {
+        use prodash::{render::line, tree::root::Aggregation};
+
+        let frame = line::render_to_string(
+            &group_of_tasks(Aggregation::Off).0,
+            line::Options {
+                layout: line::Layout::PvStyle,
+                terminal_dimensions: (12, 20),
+                ..Default::default()
+            },
+        );
+        let line = frame.lines().next().expect("one line");
+        assert_eq!(line.chars().count(), 12, "{line:?}");
+        assert!(line.ends_with('…'), "{line:?}");
+    }
+
+    #[cfg(feature = "render-tui")]
+    #[test]
+    fn terminal_user_interface_draws_each_row_as_line() {
+        use prodash::render::tui::{self, DashboardState};
+
+        let root = prodash::tree::Root::new();
+        let _task = root.add_child("task");
+        let state = &mut DashboardState::new(&tui::Options {
+            title: "title".into(),
+            ..Default::default()
+        });
+        let frame = tui::render_to_string(&root, state, 40, 10);
+        assert_eq!(frame.lines().count(), 10);
+        assert!(frame.contains("title"), "{frame}");
+        assert!(frame.contains("task"), "{frame}");
+        assert!(frame.lines().all(|line| line == line.trim_end()));
+    }
+
+    #[cfg(feature = "render-tui")]
+    #[test]
+    fn terminal_user_interface_considers_only_the_newest_messages_up_to_the_limit() {
+        use prodash::render::tui::{self, DashboardState};
+
+        let root = prodash::tree::Root::new();
+        let mut task = root.add_child("task");
+        for index in 0..5 {
+            task.info(format!("message {index}"));
+        }
+        let state = &mut DashboardState::new(&tui::Options {
+            max_messages_per_frame: Some(2),
+            ..Default::default()
+        });
+        let frame = tui::render_to_string(&root, state, 60, 40);
+        assert!(frame.contains("message 4"), "{frame}");
+        assert!(frame.contains("message 3"), "{frame}");
+        assert!(!frame.contains("message 2"), "{frame}");
+    }
+
+    #[cfg(feature = "render-tui")]
+    #[test]
+    fn terminal_user_interface_draws_user_defined_columns() {
+        use std::sync::Arc;
+
+        use prodash::{
+            progress::{Key, Value},
+            render::{
+                Column,
+                nu_ansi_term::{AnsiString, Color},
+                tui::{self, DashboardState},
+            },
+        };
+
+        struct Step;
+        impl Column for Step {
+            fn header(&self) -> &str {
+                "step"
+            }
+
+            fn width(&self) -> u16 {
+                8
+            }
+
+            fn render(&self, _key: &Key, value: &Value) -> Vec<AnsiString<'static>> {
+                vec![Color::Green.paint(format!("at {}", value.step.load(std::sync::atomic::Ordering::SeqCst)))]
+            }
+        }
+
+        let root = prodash::tree::Root::new();
+        let mut group = root.add_child("group");
+        let task = group.add_child("task");
+        task.init(Some(10), None);
+        task.set(5);
+        let state = &mut DashboardState::new(&tui::Options {
+            columns: vec![Arc::new(Step)],
+            ..Default::default()
+        });
+        let frame = tui::render_to_string(&root, state, 80, 10);
+        let row = |name: &str| {
+            frame
+                .lines()
+                .find(|line| line.contains(name))
+                .map(|line| line.trim_end_matches(['│', ' ']))
+                .expect("row is drawn")
+        };
+        assert!(row("group").ends_with("step"), "{frame}");
+        assert!(row("task").ends_with("at 5"), "{frame}");
+    }
+}